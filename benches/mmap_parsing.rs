@@ -0,0 +1,117 @@
+use std::{collections::HashMap, fs::File, io::BufReader, path::Path};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use mysql_binlog_connector_rust::{
+    binlog_mmap_parser::BinlogMmapParser,
+    binlog_parser::BinlogParser,
+    binlog_writer::BinlogWriter,
+    event::{event_data::EventData, event_data_ref::EventDataRef},
+};
+
+const TRANSACTION_COUNT: usize = 20_000;
+const THREAD_COUNT: usize = 4;
+
+/// Writes a binlog of the transactions in the mysql-bin.000080 fixture repeated many times.
+fn write_binlog(path: &Path) {
+    let fixture = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/parse_file_tests/mysql-bin.000080"
+    );
+    let mut file = File::open(fixture).unwrap();
+    let mut parser = BinlogParser {
+        checksum_length: 0,
+        table_map_event_by_table_id: HashMap::new(),
+    };
+    parser.check_magic(&mut file).unwrap();
+    let mut events = Vec::new();
+    while let Ok(event) = parser.next(&mut file) {
+        events.push(event);
+    }
+
+    let (header, EventData::FormatDescription(format_description_event)) = &events[0] else {
+        unreachable!();
+    };
+    let file = File::create(path).unwrap();
+    let mut writer = BinlogWriter::new(file, header, format_description_event).unwrap();
+    // the first DML transaction: Gtid, Query, TableMap, DeleteRows, Xid
+    let transaction = &events[2..7];
+    for _ in 0..TRANSACTION_COUNT {
+        for (header, data) in transaction {
+            writer.write(header, data).unwrap();
+        }
+    }
+    writer.flush().unwrap();
+}
+
+fn count_rows(data: EventDataRef<'_>) -> usize {
+    match data {
+        EventDataRef::WriteRows(event) | EventDataRef::DeleteRows(event) => event
+            .rows
+            .iter()
+            .map(|row| black_box(row.values().unwrap()).len())
+            .sum(),
+        EventDataRef::UpdateRows(event) => event.rows.len(),
+        _ => 0,
+    }
+}
+
+fn bench_mmap_parsing(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mysql-bin.000001");
+    write_binlog(&path);
+    let file_length = std::fs::metadata(&path).unwrap().len();
+
+    let mut group = c.benchmark_group("binlog_file");
+    group.throughput(Throughput::Bytes(file_length));
+    group.sample_size(10);
+
+    group.bench_function("parser_next", |b| {
+        b.iter(|| {
+            let mut reader = BufReader::new(File::open(&path).unwrap());
+            let mut parser = BinlogParser {
+                checksum_length: 0,
+                table_map_event_by_table_id: HashMap::new(),
+            };
+            parser.check_magic(&mut reader).unwrap();
+            let mut count = 0;
+            while let Ok((_, data)) = parser.next(&mut reader) {
+                count += black_box(matches!(data, EventData::DeleteRows(_))) as usize;
+            }
+            count
+        })
+    });
+
+    group.bench_function("mmap", |b| {
+        b.iter(|| {
+            // SAFETY: the bench file is not modified while mapped
+            let parser = unsafe { BinlogMmapParser::open(&path) }.unwrap();
+            let mut count = 0;
+            parser
+                .parse_region(parser.events_range(), |_, _, data| {
+                    count += count_rows(data);
+                    Ok(())
+                })
+                .unwrap();
+            count
+        })
+    });
+
+    group.bench_function("mmap_parallel", |b| {
+        b.iter(|| {
+            // SAFETY: the bench file is not modified while mapped
+            let parser = unsafe { BinlogMmapParser::open(&path) }.unwrap();
+            let regions = parser.split_regions(THREAD_COUNT);
+            parser
+                .parse_parallel(&regions, |_, _, data| {
+                    black_box(count_rows(data));
+                    Ok(())
+                })
+                .unwrap();
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_mmap_parsing);
+criterion_main!(benches);
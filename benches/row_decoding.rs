@@ -0,0 +1,128 @@
+use std::{collections::HashMap, io::Cursor};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use mysql_binlog_connector_rust::{
+    binlog_parser::BinlogParser,
+    column::column_value_ref::ColumnValueRef,
+    event::{event_data::EventData, event_data_ref::EventDataRef},
+};
+
+const ROW_COUNT: usize = 100;
+const BLOB_SIZE: usize = 4096;
+
+// table: id INT, name VARCHAR(64), payload BLOB, note MEDIUMBLOB
+const COLUMN_TYPES: [u8; 4] = [3, 15, 252, 252];
+
+fn event(event_type: u8, data: Vec<u8>) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(19 + data.len());
+    buf.extend_from_slice(&0u32.to_le_bytes()); // timestamp
+    buf.push(event_type);
+    buf.extend_from_slice(&1u32.to_le_bytes()); // server_id
+    buf.extend_from_slice(&(19 + data.len() as u32).to_le_bytes()); // event_length
+    buf.extend_from_slice(&0u32.to_le_bytes()); // next_event_position
+    buf.extend_from_slice(&0u16.to_le_bytes()); // flags
+    buf.extend(data);
+    buf
+}
+
+fn table_map_event() -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&1u64.to_le_bytes()[..6]); // table_id
+    data.extend_from_slice(&0u16.to_le_bytes()); // flags
+    for name in ["bench_db", "bench_tb"] {
+        data.push(name.len() as u8);
+        data.extend_from_slice(name.as_bytes());
+        data.push(0);
+    }
+    data.push(COLUMN_TYPES.len() as u8);
+    data.extend_from_slice(&COLUMN_TYPES);
+    // metadata: VARCHAR max length (2 bytes), BLOB length bytes (1 byte) * 2
+    data.extend_from_slice(&[4, 64, 0, 2, 3]);
+    data.push(0b1110); // null bits
+    event(19, data)
+}
+
+fn write_rows_event() -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&1u64.to_le_bytes()[..6]); // table_id
+    data.extend_from_slice(&0u16.to_le_bytes()); // flags
+    data.extend_from_slice(&2u16.to_le_bytes()); // extra data length
+    data.push(COLUMN_TYPES.len() as u8);
+    data.push(0b1111); // included columns
+    for i in 0..ROW_COUNT {
+        data.push(0); // null columns
+        data.extend_from_slice(&(i as i32).to_le_bytes());
+        let name = format!("name_{}", i);
+        data.push(name.len() as u8);
+        data.extend_from_slice(name.as_bytes());
+        data.extend_from_slice(&(BLOB_SIZE as u16).to_le_bytes());
+        data.extend(std::iter::repeat_n(b'a', BLOB_SIZE));
+        data.extend_from_slice(&(BLOB_SIZE as u32).to_le_bytes()[..3]);
+        data.extend(std::iter::repeat_n(b'b', BLOB_SIZE));
+    }
+    event(30, data)
+}
+
+fn new_parser() -> BinlogParser {
+    let mut parser = BinlogParser {
+        checksum_length: 0,
+        table_map_event_by_table_id: HashMap::new(),
+    };
+    parser.next(&mut Cursor::new(table_map_event())).unwrap();
+    parser
+}
+
+fn bench_row_decoding(c: &mut Criterion) {
+    let buf = write_rows_event();
+    let mut group = c.benchmark_group("write_rows");
+    group.throughput(Throughput::Bytes(buf.len() as u64));
+
+    let mut parser = new_parser();
+    group.bench_function("owned", |b| {
+        b.iter(|| {
+            let (_, data) = parser.next(&mut Cursor::new(&buf)).unwrap();
+            match data {
+                EventData::WriteRows(event) => black_box(event.rows.len()),
+                _ => unreachable!(),
+            }
+        })
+    });
+
+    let mut parser = new_parser();
+    group.bench_function("ref_all_columns", |b| {
+        b.iter(|| {
+            let (_, data) = parser.next_ref(&buf).unwrap();
+            match data {
+                EventDataRef::WriteRows(event) => {
+                    for row in event.rows.iter() {
+                        black_box(row.values().unwrap());
+                    }
+                }
+                _ => unreachable!(),
+            }
+        })
+    });
+
+    let mut parser = new_parser();
+    group.bench_function("ref_one_column", |b| {
+        b.iter(|| {
+            let (_, data) = parser.next_ref(&buf).unwrap();
+            match data {
+                EventDataRef::WriteRows(event) => {
+                    for row in event.rows.iter() {
+                        match row.get(0).unwrap() {
+                            ColumnValueRef::Long(id) => black_box(id),
+                            _ => unreachable!(),
+                        };
+                    }
+                }
+                _ => unreachable!(),
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_row_decoding);
+criterion_main!(benches);
@@ -0,0 +1,127 @@
+use std::{
+    collections::HashMap,
+    io::{Cursor, ErrorKind},
+    path::Path,
+    time::Duration,
+};
+
+use async_std::{
+    fs::File,
+    io::{Read as AsyncRead, ReadExt},
+};
+
+use crate::{
+    binlog_error::BinlogError,
+    binlog_parser::{BinlogParser, MAGIC_VALUE},
+    constants,
+    event::{event_data::EventData, event_header::EventHeader},
+};
+
+const READ_SIZE: usize = 64 * 1024;
+
+/// Reads binlog events from an AsyncRead such as a file, a pipe or a socket, the events
+/// are decoded by BinlogParser once they are complete.
+///
+/// With `with_follow`, the reader works like `tail -f`: at the end of the stream it waits for
+/// more data instead of returning, so a binlog file still being written by the server can be
+/// followed, a partial event at the end is kept until the rest is appended.
+pub struct BinlogAsyncReader<R: AsyncRead + Unpin> {
+    reader: R,
+    pub parser: BinlogParser,
+    /// Interval to poll for new data at the end of the stream, None to stop at the end.
+    pub follow_interval: Option<Duration>,
+    /// Bytes read but not parsed yet.
+    buf: Vec<u8>,
+    chunk: Vec<u8>,
+    magic_checked: bool,
+}
+
+impl BinlogAsyncReader<File> {
+    /// Opens a binlog file.
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self, BinlogError> {
+        Ok(Self::new(File::open(path.as_ref()).await?))
+    }
+}
+
+impl<R: AsyncRead + Unpin> BinlogAsyncReader<R> {
+    /// Creates a reader of a stream starting with the binlog magic.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            parser: BinlogParser {
+                checksum_length: 0,
+                table_map_event_by_table_id: HashMap::new(),
+            },
+            follow_interval: None,
+            buf: Vec::new(),
+            chunk: vec![0; READ_SIZE],
+            magic_checked: false,
+        }
+    }
+
+    /// Waits for new events at the end of the stream, polling at the interval.
+    pub fn with_follow(mut self, interval: Duration) -> Self {
+        self.follow_interval = Some(interval);
+        self
+    }
+
+    /// Reads the next event, None at the end of the stream if not following.
+    /// It is cancel safe, the bytes read are kept if the future is dropped.
+    pub async fn read(&mut self) -> Result<Option<(EventHeader, EventData)>, BinlogError> {
+        if !self.magic_checked {
+            if !self.fill(MAGIC_VALUE.len()).await? {
+                return Ok(None);
+            }
+            self.parser
+                .check_magic(&mut Cursor::new(&self.buf[..MAGIC_VALUE.len()]))?;
+            self.buf.drain(..MAGIC_VALUE.len());
+            self.magic_checked = true;
+        }
+
+        if !self.fill(constants::EVENT_HEADER_LENGTH).await? {
+            return Ok(None);
+        }
+        let header = EventHeader::parse(&mut Cursor::new(&self.buf))?;
+        let event_length = header.event_length as usize;
+        if event_length < constants::EVENT_HEADER_LENGTH {
+            return Err(BinlogError::UnexpectedData(format!(
+                "invalid event length: {}",
+                event_length
+            )));
+        }
+        self.fill(event_length).await?;
+
+        let event: Vec<u8> = self.buf.drain(..event_length).collect();
+        let event = self.parser.next(&mut Cursor::new(&event))?;
+        Ok(Some(event))
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads until at least `length` bytes are buffered, returns false if the stream
+    /// ends with nothing buffered, and fails if it ends within an event.
+    async fn fill(&mut self, length: usize) -> Result<bool, BinlogError> {
+        while self.buf.len() < length {
+            let n = self.reader.read(&mut self.chunk).await?;
+            self.buf.extend_from_slice(&self.chunk[..n]);
+            if n > 0 {
+                continue;
+            }
+
+            if let Some(interval) = self.follow_interval {
+                async_std::task::sleep(interval).await;
+            } else if self.buf.is_empty() {
+                return Ok(false);
+            } else {
+                return Err(std::io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "stream ended within an event",
+                )
+                .into());
+            }
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, Seek, SeekFrom},
+    path::Path,
+};
+
+use crate::{
+    binlog_error::BinlogError,
+    binlog_file::BinlogFile,
+    binlog_offset_index::BinlogOffsetIndex,
+    binlog_parser::BinlogParser,
+    change::change_tracker::QueryKind,
+    command::gtid_set::GtidSet,
+    event::{event_data::EventData, event_header::EventHeader},
+};
+
+/// Reads the events of a binlog file within bounds, like the `--start-position`, `--stop-position`,
+/// `--start-datetime`, `--stop-datetime`, `--include-gtids` and `--exclude-gtids` options
+/// of mysqlbinlog.
+///
+/// Bounds are applied to whole transactions, decided by the first event of each: reading stops
+/// before the first transaction starting at or after a stop bound, and a transaction started
+/// before it is read to its end. Events of the file itself (FormatDescriptionEvent,
+/// PreviousGtidsEvent, RotateEvent) are not filtered.
+pub struct BinlogBoundedReader<S: BufRead + Seek> {
+    stream: S,
+    parser: BinlogParser,
+    pub start_position: Option<u64>,
+    pub stop_position: Option<u64>,
+    /// Event times in seconds, [start, stop).
+    pub start_time: Option<u32>,
+    pub stop_time: Option<u32>,
+    /// Only transactions in the set are read, those without GTID are excluded.
+    pub include_gtids: Option<GtidSet>,
+    pub exclude_gtids: Option<GtidSet>,
+    format_description_read: bool,
+    in_transaction: bool,
+    boundary: TransactionBoundary,
+    /// Whether the events of the current transaction are returned.
+    included: bool,
+    finished: bool,
+}
+
+impl BinlogBoundedReader<BinlogFile> {
+    /// Opens a binlog file, which may be compressed, see BinlogFile.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, BinlogError> {
+        Self::new(BinlogFile::open(path)?)
+    }
+}
+
+impl<S: BufRead + Seek> BinlogBoundedReader<S> {
+    /// Creates a reader of a binlog stream, which starts with the magic.
+    pub fn new(mut stream: S) -> Result<Self, BinlogError> {
+        let mut parser = BinlogParser {
+            checksum_length: 0,
+            table_map_event_by_table_id: HashMap::new(),
+        };
+        parser.check_magic(&mut stream)?;
+        Ok(Self {
+            stream,
+            parser,
+            start_position: None,
+            stop_position: None,
+            start_time: None,
+            stop_time: None,
+            include_gtids: None,
+            exclude_gtids: None,
+            format_description_read: false,
+            in_transaction: false,
+            boundary: TransactionBoundary::default(),
+            included: true,
+            finished: false,
+        })
+    }
+
+    /// Starts reading at an event position, which is seeked to after the FormatDescriptionEvent.
+    pub fn with_start_position(mut self, start_position: u64) -> Self {
+        self.start_position = Some(start_position);
+        self
+    }
+
+    pub fn with_stop_position(mut self, stop_position: u64) -> Self {
+        self.stop_position = Some(stop_position);
+        self
+    }
+
+    pub fn with_start_time(mut self, start_time: u32) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    pub fn with_stop_time(mut self, stop_time: u32) -> Self {
+        self.stop_time = Some(stop_time);
+        self
+    }
+
+    pub fn with_include_gtids(mut self, gtid_set: GtidSet) -> Self {
+        self.include_gtids = Some(gtid_set);
+        self
+    }
+
+    pub fn with_exclude_gtids(mut self, gtid_set: GtidSet) -> Self {
+        self.exclude_gtids = Some(gtid_set);
+        self
+    }
+
+    /// Reads the next event within the bounds, None if a stop bound or the end is reached.
+    pub fn read(&mut self) -> Result<Option<(EventHeader, EventData)>, BinlogError> {
+        while !self.finished {
+            if self.stream.fill_buf()?.is_empty() {
+                self.finished = true;
+                break;
+            }
+
+            let (header, data) = self.parser.next(&mut self.stream)?;
+            match &data {
+                EventData::FormatDescription(_) => {
+                    self.format_description_read = true;
+                    if let Some(start_position) = self.start_position.take() {
+                        if start_position > self.stream.stream_position()? {
+                            self.stream.seek(SeekFrom::Start(start_position))?;
+                        }
+                    }
+                    return Ok(Some((header, data)));
+                }
+
+                EventData::PreviousGtids(_) | EventData::Rotate(_) | EventData::HeartBeat => {
+                    return Ok(Some((header, data)));
+                }
+
+                _ => {}
+            }
+
+            if !self.in_transaction {
+                if self.is_after_stop(&header) {
+                    self.finished = true;
+                    break;
+                }
+                self.in_transaction = true;
+                self.included = self.start_time.is_none_or(|t| header.timestamp >= t);
+                if self.include_gtids.is_some() && !matches!(data, EventData::Gtid(_)) {
+                    self.included = false;
+                }
+            }
+
+            if let EventData::Gtid(event) = &data {
+                if self.include_gtids.is_some() || self.exclude_gtids.is_some() {
+                    let (source_id, transaction_id) = GtidSet::parse_gtid(&event.gtid)?;
+                    if let Some(gtid_set) = &self.include_gtids {
+                        self.included &= gtid_set.contains_transaction(&source_id, transaction_id);
+                    }
+                    if let Some(gtid_set) = &self.exclude_gtids {
+                        self.included &= !gtid_set.contains_transaction(&source_id, transaction_id);
+                    }
+                }
+            }
+            if self.boundary.is_end(&data) {
+                self.in_transaction = false;
+            }
+
+            if self.included {
+                return Ok(Some((header, data)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Seeks to the transaction of a GTID found in the index of the file,
+    /// returns false if the GTID is not in the index.
+    pub fn seek_to_gtid(
+        &mut self,
+        index: &BinlogOffsetIndex,
+        gtid: &str,
+    ) -> Result<bool, BinlogError> {
+        self.seek_to(index.find_gtid(gtid).map(|t| t.position))
+    }
+
+    /// Seeks to the first transaction at or after a time in seconds found in the index.
+    pub fn seek_to_time(
+        &mut self,
+        index: &BinlogOffsetIndex,
+        timestamp: u32,
+    ) -> Result<bool, BinlogError> {
+        self.seek_to(index.find_time(timestamp).map(|t| t.position))
+    }
+
+    /// Seeks to the start of the transaction containing a position found in the index.
+    pub fn seek_to_position(
+        &mut self,
+        index: &BinlogOffsetIndex,
+        position: u64,
+    ) -> Result<bool, BinlogError> {
+        self.seek_to(index.find_position(position).map(|t| t.position))
+    }
+
+    /// Position of the next event in the stream.
+    pub fn position(&mut self) -> Result<u64, BinlogError> {
+        Ok(self.stream.stream_position()?)
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    /// Seeks to a transaction start, after the FormatDescriptionEvent is read
+    /// since the events can not be parsed without it.
+    fn seek_to(&mut self, position: Option<u64>) -> Result<bool, BinlogError> {
+        let Some(position) = position else {
+            return Ok(false);
+        };
+        if self.format_description_read {
+            self.stream.seek(SeekFrom::Start(position))?;
+            self.in_transaction = false;
+            self.boundary = TransactionBoundary::default();
+            self.finished = false;
+        } else {
+            self.start_position = Some(position);
+        }
+        Ok(true)
+    }
+
+    fn is_after_stop(&self, header: &EventHeader) -> bool {
+        let event_position =
+            (header.next_event_position as u64).saturating_sub(header.event_length as u64);
+        self.stop_position.is_some_and(|p| event_position >= p)
+            || self.stop_time.is_some_and(|t| header.timestamp >= t)
+    }
+}
+
+/// Finds the ends of transactions by the QueryKind of the ChangeTracker: a XidEvent,
+/// a XaPrepareEvent, a TransactionPayloadEvent, or a COMMIT, ROLLBACK or DDL QueryEvent.
+/// Other statements logged without BEGIN / XA START are transactions by themselves.
+#[derive(Debug, Default)]
+pub(crate) struct TransactionBoundary {
+    /// Whether the current transaction was started by BEGIN or XA START.
+    explicit: bool,
+}
+
+impl TransactionBoundary {
+    /// Whether the event ends the current transaction.
+    pub(crate) fn is_end(&mut self, data: &EventData) -> bool {
+        let is_end = match data {
+            EventData::Query(event) => match QueryKind::of(&event.query) {
+                QueryKind::Begin | QueryKind::XaStart => {
+                    self.explicit = true;
+                    false
+                }
+                QueryKind::Commit | QueryKind::Rollback | QueryKind::Ddl => true,
+                QueryKind::Other => !self.explicit,
+            },
+            EventData::Xid(_) | EventData::XaPrepare(_) | EventData::TransactionPayload(_) => true,
+            _ => false,
+        };
+        if is_end {
+            self.explicit = false;
+        }
+        is_end
+    }
+}
+
+impl<S: BufRead + Seek> Iterator for BinlogBoundedReader<S> {
+    type Item = Result<(EventHeader, EventData), BinlogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = self.read().transpose();
+        if matches!(res, Some(Err(_))) {
+            self.finished = true;
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransactionBoundary;
+    use crate::event::{event_data::EventData, query_event::QueryEvent};
+
+    fn query_event(query: &str) -> EventData {
+        EventData::Query(QueryEvent {
+            thread_id: 7,
+            exec_time: 0,
+            error_code: 0,
+            status_vars: Vec::new(),
+            schema: "db_1".into(),
+            query: query.into(),
+        })
+    }
+
+    #[test]
+    fn test_transaction_boundary() {
+        let mut boundary = TransactionBoundary::default();
+        // statement-based DML inside BEGIN ... COMMIT
+        assert!(!boundary.is_end(&query_event("BEGIN")));
+        assert!(!boundary.is_end(&query_event("INSERT INTO t VALUES (1)")));
+        assert!(!boundary.is_end(&query_event("SAVEPOINT sp_1")));
+        assert!(boundary.is_end(&query_event("/* app */ COMMIT")));
+
+        // statements logged without BEGIN are transactions by themselves
+        assert!(boundary.is_end(&query_event("/* app */ ALTER TABLE t ADD c INT")));
+        assert!(boundary.is_end(&query_event("GRANT SELECT ON *.* TO u")));
+
+        // the first phase of an XA transaction ends with a XaPrepareEvent
+        assert!(!boundary.is_end(&query_event("XA START X'31',X'',1")));
+        assert!(!boundary.is_end(&query_event("INSERT INTO t VALUES (1)")));
+        assert!(!boundary.is_end(&query_event("XA END X'31',X'',1")));
+        assert!(boundary.is_end(&query_event("XA COMMIT X'31',X'',1")));
+    }
+}
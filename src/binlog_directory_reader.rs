@@ -0,0 +1,305 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    binlog_error::BinlogError,
+    binlog_file::{binlog_file_sequence, BinlogFile, Compression},
+    binlog_parser::BinlogParser,
+    command::gtid_set::GtidSet,
+    event::{event_data::EventData, event_header::EventHeader},
+};
+
+/// Reads the events of the binlog files in a directory in order, like a replica reading
+/// the binlogs of its source. The files are listed by an index file such as `mysql-bin.index`,
+/// or found by scanning the directory for files named like `mysql-bin.000001`. Files compressed
+/// by gzip, zstd or xz (e.g. `mysql-bin.000001.gz`) are decompressed while read.
+///
+/// A RotateEvent switches to the file it names, and the end of a file without one switches to
+/// the next file in the list. Each file is parsed from scratch: the checksum type is taken from
+/// its FormatDescriptionEvent and table maps of the previous file are dropped.
+pub struct BinlogDirectoryReader {
+    /// Paths of the binlog files, in order.
+    pub binlog_paths: Vec<PathBuf>,
+    start_position: Option<(String, u64)>,
+    start_gtid_set: Option<GtidSet>,
+    parser: BinlogParser,
+    current: Option<CurrentFile>,
+    /// Index of the next file to open.
+    next_file_index: usize,
+    /// Whether the events of the current transaction are in start_gtid_set and skipped.
+    skipping: bool,
+    started: bool,
+    finished: bool,
+}
+
+struct CurrentFile {
+    binlog_filename: String,
+    reader: BinlogFile,
+    /// Position to seek to after the FormatDescriptionEvent.
+    seek_position: Option<u64>,
+}
+
+impl BinlogDirectoryReader {
+    /// Reads the files listed in an index file, relative paths are resolved against its directory.
+    pub fn from_index_file(index_path: impl AsRef<Path>) -> Result<Self, BinlogError> {
+        let index_path = index_path.as_ref();
+        let dir = index_path.parent().unwrap_or(Path::new(""));
+        let binlog_paths = fs::read_to_string(index_path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| dir.join(line))
+            .collect();
+        Ok(Self::new(binlog_paths))
+    }
+
+    /// Reads the files of a directory named like `<basename>.<sequence number>`, ordered by
+    /// sequence number. The files must share a basename, see `from_dir_with_basename`.
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self, BinlogError> {
+        let binlog_paths = Self::scan_dir(dir.as_ref(), None)?;
+        let mut basenames: Vec<String> = binlog_paths
+            .iter()
+            .map(|path| binlog_file_sequence(&file_name(path)).0.to_string())
+            .collect();
+        basenames.dedup();
+        if basenames.len() > 1 {
+            return Err(BinlogError::UnexpectedData(format!(
+                "binlog files of several basenames in {}: {}",
+                dir.as_ref().display(),
+                basenames.join(", ")
+            )));
+        }
+        Ok(Self::new(binlog_paths))
+    }
+
+    /// Reads the files of a directory named like `<basename>.<sequence number>` for the given
+    /// basename, e.g. `mysql-bin`, ordered by sequence number.
+    pub fn from_dir_with_basename(
+        dir: impl AsRef<Path>,
+        basename: &str,
+    ) -> Result<Self, BinlogError> {
+        Ok(Self::new(Self::scan_dir(dir.as_ref(), Some(basename))?))
+    }
+
+    /// Finds the binlog files of a directory, ordered by basename and sequence number, so
+    /// `mysql-bin.1000000` follows `mysql-bin.999999`.
+    fn scan_dir(dir: &Path, basename: Option<&str>) -> Result<Vec<PathBuf>, BinlogError> {
+        let mut binlog_paths = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let name = file_name(&path);
+            let is_binlog = path.is_file()
+                && Path::new(&name)
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| !e.is_empty() && e.chars().all(|c| c.is_ascii_digit()))
+                && basename.is_none_or(|b| binlog_file_sequence(&name).0 == b);
+            if is_binlog {
+                binlog_paths.push(path);
+            }
+        }
+        binlog_paths.sort_by_cached_key(|path| {
+            let name = file_name(path);
+            let (basename, sequence) = binlog_file_sequence(&name);
+            (basename.to_string(), sequence)
+        });
+        Ok(binlog_paths)
+    }
+
+    pub fn new(binlog_paths: Vec<PathBuf>) -> Self {
+        Self {
+            binlog_paths,
+            start_position: None,
+            start_gtid_set: None,
+            parser: new_parser(),
+            current: None,
+            next_file_index: 0,
+            skipping: false,
+            started: false,
+            finished: false,
+        }
+    }
+
+    /// Starts reading at a position of a binlog file, the FormatDescriptionEvent
+    /// of the file is still returned first.
+    pub fn with_start_position(mut self, binlog_filename: &str, binlog_position: u64) -> Self {
+        self.start_position = Some((binlog_filename.to_string(), binlog_position));
+        self
+    }
+
+    /// Starts reading after the transactions of an executed GTID set, like a replica with
+    /// MASTER_AUTO_POSITION=1: reading starts from the last file whose PreviousGtidsEvent is
+    /// contained in the set, and transactions in the set are skipped.
+    pub fn with_start_gtid_set(mut self, gtid_set: GtidSet) -> Self {
+        self.start_gtid_set = Some(gtid_set);
+        self
+    }
+
+    /// Name of the file being read.
+    pub fn binlog_filename(&self) -> Option<&str> {
+        self.current
+            .as_ref()
+            .map(|file| file.binlog_filename.as_str())
+    }
+
+    /// Position of the next event in the file being read.
+    pub fn binlog_position(&mut self) -> Option<u64> {
+        self.current
+            .as_mut()
+            .and_then(|file| file.reader.stream_position().ok())
+    }
+
+    /// Reads the next event, None if all files are read.
+    pub fn read(&mut self) -> Result<Option<(EventHeader, EventData)>, BinlogError> {
+        if !self.started {
+            self.started = true;
+            self.start()?;
+        }
+
+        loop {
+            let Some(file) = self.current.as_mut() else {
+                if self.next_file_index >= self.binlog_paths.len() {
+                    return Ok(None);
+                }
+                self.open(self.next_file_index, None)?;
+                continue;
+            };
+
+            if file.reader.is_eof()? {
+                self.current = None;
+                continue;
+            }
+
+            let (header, data) = self.parser.next(&mut file.reader)?;
+            match &data {
+                EventData::FormatDescription(_) => {
+                    if let Some(position) = file.seek_position.take() {
+                        file.reader.seek(SeekFrom::Start(position))?;
+                    }
+                }
+
+                EventData::Rotate(event) => {
+                    // the RotateEvent of a file points to the next one
+                    self.current = None;
+                    if let Some(index) = self.find_file(&event.binlog_filename) {
+                        self.next_file_index = index;
+                    }
+                }
+
+                EventData::Gtid(event) => {
+                    self.skipping = match &self.start_gtid_set {
+                        Some(gtid_set) => gtid_set.contains(&event.gtid)?,
+                        None => false,
+                    };
+                }
+
+                _ => {}
+            }
+
+            // events other than the transactional ones are not skipped
+            let is_file_event = matches!(
+                data,
+                EventData::FormatDescription(_)
+                    | EventData::PreviousGtids(_)
+                    | EventData::Rotate(_)
+            );
+            if self.skipping && !is_file_event {
+                continue;
+            }
+            return Ok(Some((header, data)));
+        }
+    }
+
+    fn start(&mut self) -> Result<(), BinlogError> {
+        if let Some((binlog_filename, position)) = self.start_position.clone() {
+            let index = self.find_file(&binlog_filename).ok_or_else(|| {
+                BinlogError::UnexpectedData(format!("binlog file {} not found", binlog_filename))
+            })?;
+            return self.open(index, Some(position));
+        }
+
+        if let Some(gtid_set) = &self.start_gtid_set {
+            let mut start_index = 0;
+            for (index, path) in self.binlog_paths.iter().enumerate().rev() {
+                let previous_gtid_set = read_previous_gtid_set(path)?;
+                if previous_gtid_set.is_some_and(|set| set.is_contained_within(gtid_set)) {
+                    start_index = index;
+                    break;
+                }
+            }
+            self.next_file_index = start_index;
+        }
+        Ok(())
+    }
+
+    fn open(&mut self, index: usize, position: Option<u64>) -> Result<(), BinlogError> {
+        let path = &self.binlog_paths[index];
+        let mut reader = BinlogFile::open(path)?;
+
+        self.parser = new_parser();
+        self.parser.check_magic(&mut reader)?;
+        self.skipping = false;
+        self.current = Some(CurrentFile {
+            binlog_filename: file_name(path),
+            reader,
+            seek_position: position.filter(|p| *p > 4),
+        });
+        self.next_file_index = index + 1;
+        Ok(())
+    }
+
+    fn find_file(&self, binlog_filename: &str) -> Option<usize> {
+        self.binlog_paths
+            .iter()
+            .position(|path| file_name(path) == binlog_filename)
+    }
+}
+
+impl Iterator for BinlogDirectoryReader {
+    type Item = Result<(EventHeader, EventData), BinlogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let res = self.read().transpose();
+        if !matches!(res, Some(Ok(_))) {
+            self.finished = true;
+        }
+        res
+    }
+}
+
+fn new_parser() -> BinlogParser {
+    BinlogParser {
+        checksum_length: 0,
+        table_map_event_by_table_id: HashMap::new(),
+    }
+}
+
+/// Name of the binlog file, without the compression extension.
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| Compression::strip_extension(&name.to_string_lossy()).to_string())
+        .unwrap_or_default()
+}
+
+/// Reads the GTID set of the PreviousGtidsEvent at the start of a file, None if not found.
+fn read_previous_gtid_set(path: &Path) -> Result<Option<GtidSet>, BinlogError> {
+    let mut reader = BinlogFile::open(path)?;
+    let mut parser = new_parser();
+    parser.check_magic(&mut reader)?;
+    // a file just created may only have the FormatDescriptionEvent
+    while let Ok((_, data)) = parser.next(&mut reader) {
+        match data {
+            EventData::FormatDescription(_) => continue,
+            EventData::PreviousGtids(event) => return Ok(Some(GtidSet::new(&event.gtid_set)?)),
+            _ => return Ok(None),
+        }
+    }
+    Ok(None)
+}
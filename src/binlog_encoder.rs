@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use crate::{
+    binlog_error::BinlogError,
+    binlog_parser::BinlogParser,
+    constants,
+    event::{
+        checksum_type::ChecksumType, event_data::EventData, event_header::EventHeader,
+        event_type::EventType, table_map_event::TableMapEvent,
+    },
+};
+
+/// Encodes events back to the binlog format, the reverse of BinlogParser.
+/// Like the parser, it keeps the checksum type of the last FormatDescriptionEvent
+/// and the TableMapEvents needed to encode rows events.
+pub struct BinlogEncoder {
+    pub checksum_type: ChecksumType,
+    pub table_map_event_by_table_id: HashMap<u64, TableMapEvent>,
+    /// Whether the unused bits of the column bitmaps in rows events are set to 1,
+    /// as MySQL 5.x and MariaDB do, it is decided by the server version of FormatDescriptionEvent.
+    pub legacy_bitmap_padding: bool,
+}
+
+impl Default for BinlogEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BinlogEncoder {
+    pub fn new() -> Self {
+        Self {
+            checksum_type: ChecksumType::None,
+            table_map_event_by_table_id: HashMap::new(),
+            legacy_bitmap_padding: false,
+        }
+    }
+
+    /// Encodes an event into its header, data and checksum. The event_length of the header
+    /// is computed from the encoded data, other fields of the header are written as is.
+    pub fn encode(
+        &mut self,
+        header: &EventHeader,
+        data: &EventData,
+    ) -> Result<Vec<u8>, BinlogError> {
+        self.encode_event(header, data, None)
+    }
+
+    /// Encodes an event to be written at `position` of a binlog file,
+    /// the next_event_position of the header is set to the position after the event.
+    pub fn encode_at(
+        &mut self,
+        header: &EventHeader,
+        data: &EventData,
+        position: u64,
+    ) -> Result<Vec<u8>, BinlogError> {
+        self.encode_event(header, data, Some(position))
+    }
+
+    fn encode_event(
+        &mut self,
+        header: &EventHeader,
+        data: &EventData,
+        position: Option<u64>,
+    ) -> Result<Vec<u8>, BinlogError> {
+        let mut body = Vec::new();
+        let event_type = EventType::from_code(header.event_type);
+        let row_event_version = BinlogParser::get_row_event_version(&event_type);
+        match data {
+            EventData::FormatDescription(event) => {
+                event.encode(&mut body)?;
+                self.checksum_type = event.checksum_type.clone();
+                self.legacy_bitmap_padding = event.server_version.starts_with("5.")
+                    || event.server_version.contains("MariaDB");
+            }
+
+            EventData::PreviousGtids(event) => event.encode(&mut body)?,
+
+            EventData::Gtid(event) => event.encode(&mut body)?,
+
+            EventData::Query(event) => event.encode(&mut body)?,
+
+            EventData::TableMap(event) => {
+                event.encode(&mut body)?;
+                self.table_map_event_by_table_id
+                    .insert(event.table_id, event.clone());
+            }
+
+            EventData::WriteRows(event) => {
+                let table_map_event = self.get_table_map_event(event.table_id)?;
+                event.encode(
+                    &mut body,
+                    table_map_event,
+                    row_event_version,
+                    self.legacy_bitmap_padding,
+                )?;
+            }
+
+            EventData::UpdateRows(event) => {
+                let table_map_event = self.get_table_map_event(event.table_id)?;
+                event.encode(
+                    &mut body,
+                    table_map_event,
+                    row_event_version,
+                    self.legacy_bitmap_padding,
+                )?;
+            }
+
+            EventData::DeleteRows(event) => {
+                let table_map_event = self.get_table_map_event(event.table_id)?;
+                event.encode(
+                    &mut body,
+                    table_map_event,
+                    row_event_version,
+                    self.legacy_bitmap_padding,
+                )?;
+            }
+
+            EventData::Xid(event) => event.encode(&mut body)?,
+
+            EventData::XaPrepare(event) => event.encode(&mut body)?,
+
+            EventData::Rotate(event) => event.encode(&mut body)?,
+
+            EventData::TransactionPayload(event) => event.encode(&mut body)?,
+
+            EventData::RowsQuery(event) => event.encode(&mut body)?,
+
+            EventData::HeartBeat | EventData::NotSupported => {
+                return Err(BinlogError::UnexpectedData(format!(
+                    "can not encode event of type {:?}",
+                    event_type
+                )))
+            }
+        }
+
+        let checksum_length = self.checksum_type.get_length() as usize;
+        let event_length = constants::EVENT_HEADER_LENGTH + body.len() + checksum_length;
+        let next_event_position = match position {
+            Some(position) => (position + event_length as u64) as u32,
+            None => header.next_event_position,
+        };
+        let mut buf = Vec::with_capacity(event_length);
+        EventHeader {
+            event_length: event_length as u32,
+            next_event_position,
+            ..header.clone()
+        }
+        .encode(&mut buf)?;
+        buf.append(&mut body);
+
+        if let ChecksumType::CRC32 = self.checksum_type {
+            let checksum = crc32fast::hash(&buf);
+            buf.extend_from_slice(&checksum.to_le_bytes());
+        }
+        Ok(buf)
+    }
+
+    fn get_table_map_event(&self, table_id: u64) -> Result<&TableMapEvent, BinlogError> {
+        self.table_map_event_by_table_id
+            .get(&table_id)
+            .ok_or_else(|| {
+                BinlogError::UnexpectedData(format!("table map event of {} not found", table_id))
+            })
+    }
+}
@@ -0,0 +1,231 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use aes::{
+    cipher::{BlockDecryptMut, KeyIvInit, StreamCipher, StreamCipherSeek},
+    Aes256,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha512};
+
+use crate::binlog_error::BinlogError;
+pub use crate::binlog_parser::ENCRYPTED_MAGIC_VALUE;
+
+type Aes256Ctr = ctr::Ctr128BE<Aes256>;
+type Aes256CbcDec = cbc::Decryptor<Aes256>;
+
+/// Size of the encryption header, the encrypted binlog follows it.
+pub const ENCRYPTION_HEADER_SIZE: u64 = 512;
+
+const HEADER_VERSION: u8 = 1;
+const KEY_ID_FIELD: u8 = 1;
+const ENCRYPTED_PASSWORD_FIELD: u8 = 2;
+const IV_FIELD: u8 = 3;
+const PASSWORD_SIZE: usize = 32;
+const IV_SIZE: usize = 16;
+
+/// Master keys by key id, such as `MySQLReplicationKey_<server uuid>_<sequence number>`.
+#[derive(Debug, Clone, Default)]
+pub struct Keyring {
+    keys: HashMap<String, Vec<u8>>,
+}
+
+#[derive(Deserialize)]
+struct KeyringFile {
+    elements: Vec<KeyringElement>,
+}
+
+#[derive(Deserialize)]
+struct KeyringElement {
+    data_id: String,
+    /// Hex of the key.
+    data: String,
+}
+
+impl Keyring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the keys of a component_keyring_file data file, the JSON file like
+    /// `{"version":"1.0","elements":[{"user":"","data_id":"...","data_type":"AES","data":"<hex>"}]}`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, BinlogError> {
+        let content = std::fs::read_to_string(path)?;
+        let file: KeyringFile = serde_json::from_str(&content)
+            .map_err(|e| BinlogError::DecryptError(format!("invalid keyring file: {}", e)))?;
+        let mut keyring = Self::new();
+        for element in file.elements {
+            let key = hex_decode(&element.data).ok_or_else(|| {
+                BinlogError::DecryptError(format!("invalid key data of {}", element.data_id))
+            })?;
+            keyring.insert(&element.data_id, key);
+        }
+        Ok(keyring)
+    }
+
+    pub fn insert(&mut self, key_id: &str, key: Vec<u8>) {
+        self.keys.insert(key_id.to_string(), key);
+    }
+
+    pub fn get(&self, key_id: &str) -> Option<&[u8]> {
+        self.keys.get(key_id).map(|key| key.as_slice())
+    }
+}
+
+/// The header at the start of an encrypted binlog file.
+#[derive(Debug, Clone)]
+pub struct EncryptionHeader {
+    pub key_id: String,
+    pub encrypted_password: [u8; PASSWORD_SIZE],
+    pub iv: [u8; IV_SIZE],
+}
+
+impl EncryptionHeader {
+    pub fn parse(buf: &[u8]) -> Result<Self, BinlogError> {
+        let invalid = |msg: &str| BinlogError::DecryptError(format!("invalid header: {}", msg));
+        if buf.len() < ENCRYPTION_HEADER_SIZE as usize || buf[..4] != ENCRYPTED_MAGIC_VALUE {
+            return Err(invalid("bad magic"));
+        }
+        if buf[4] != HEADER_VERSION {
+            return Err(invalid(&format!("unsupported version {}", buf[4])));
+        }
+
+        let mut key_id = None;
+        let mut encrypted_password = None;
+        let mut iv = None;
+        let mut offset = 5;
+        // fields are type + value, the rest of the header is padded with 0
+        while offset < ENCRYPTION_HEADER_SIZE as usize {
+            let field_type = buf[offset];
+            offset += 1;
+            let value_end = match field_type {
+                KEY_ID_FIELD => {
+                    offset += 1;
+                    offset + buf[offset - 1] as usize
+                }
+                ENCRYPTED_PASSWORD_FIELD => offset + PASSWORD_SIZE,
+                IV_FIELD => offset + IV_SIZE,
+                _ => break,
+            };
+            let value = buf
+                .get(offset..value_end)
+                .ok_or_else(|| invalid("field out of bounds"))?;
+            match field_type {
+                KEY_ID_FIELD => key_id = Some(String::from_utf8(value.to_vec())?),
+                ENCRYPTED_PASSWORD_FIELD => encrypted_password = value.try_into().ok(),
+                _ => iv = value.try_into().ok(),
+            }
+            offset = value_end;
+        }
+
+        Ok(Self {
+            key_id: key_id.ok_or_else(|| invalid("missing key id"))?,
+            encrypted_password: encrypted_password.ok_or_else(|| invalid("missing password"))?,
+            iv: iv.ok_or_else(|| invalid("missing iv"))?,
+        })
+    }
+
+    /// Decrypts the file password with the master key, by AES-256-CBC without padding.
+    pub fn decrypt_password(&self, master_key: &[u8]) -> Result<[u8; PASSWORD_SIZE], BinlogError> {
+        let mut password = self.encrypted_password;
+        let decryptor = Aes256CbcDec::new_from_slices(master_key, &self.iv).map_err(|_| {
+            BinlogError::DecryptError(format!("invalid length of key {}", self.key_id))
+        })?;
+        decryptor
+            .decrypt_padded_mut::<cbc::cipher::block_padding::NoPadding>(&mut password)
+            .map_err(|_| BinlogError::DecryptError("failed to decrypt file password".into()))?;
+        Ok(password)
+    }
+}
+
+/// Reads an encrypted binlog file as the plain binlog, starting with the normal magic.
+///
+/// The file password in the header is decrypted by the master key of its key id, and the
+/// binlog is decrypted on the fly by AES-256-CTR, whose key and IV are derived from the
+/// SHA-512 of the password. Positions are in plain binlog coordinates.
+pub struct EncryptedBinlogReader<R: Read + Seek> {
+    reader: R,
+    cipher: Aes256Ctr,
+    pub header: EncryptionHeader,
+    position: u64,
+}
+
+impl<R: Read + Seek> EncryptedBinlogReader<R> {
+    /// Creates a reader with the master key from a keyring.
+    pub fn new(reader: R, keyring: &Keyring) -> Result<Self, BinlogError> {
+        Self::with_key_provider(reader, |key_id| keyring.get(key_id).map(|key| key.to_vec()))
+    }
+
+    /// Creates a reader with the master key returned by a callback of the key id.
+    pub fn with_key_provider<F>(mut reader: R, key_provider: F) -> Result<Self, BinlogError>
+    where
+        F: FnOnce(&str) -> Option<Vec<u8>>,
+    {
+        let mut buf = vec![0u8; ENCRYPTION_HEADER_SIZE as usize];
+        reader.seek(SeekFrom::Start(0))?;
+        reader.read_exact(&mut buf)?;
+        let header = EncryptionHeader::parse(&buf)?;
+
+        let master_key = key_provider(&header.key_id).ok_or_else(|| {
+            BinlogError::DecryptError(format!("master key {} not found", header.key_id))
+        })?;
+        let password = header.decrypt_password(&master_key)?;
+        let digest = Sha512::digest(password);
+        let cipher = Aes256Ctr::new_from_slices(&digest[..32], &digest[32..48])
+            .map_err(|_| BinlogError::DecryptError("invalid key or iv".into()))?;
+
+        Ok(Self {
+            reader,
+            cipher,
+            header,
+            position: 0,
+        })
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read + Seek> Read for EncryptedBinlogReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.cipher.seek(self.position);
+        self.cipher.apply_keystream(&mut buf[..n]);
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Read + Seek> Seek for EncryptedBinlogReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(position) => SeekFrom::Start(position + ENCRYPTION_HEADER_SIZE),
+            pos => pos,
+        };
+        let file_position = self.reader.seek(pos)?;
+        if file_position < ENCRYPTION_HEADER_SIZE {
+            self.reader
+                .seek(SeekFrom::Start(self.position + ENCRYPTION_HEADER_SIZE))?;
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek before the start of the encrypted binlog",
+            ));
+        }
+        self.position = file_position - ENCRYPTION_HEADER_SIZE;
+        Ok(self.position)
+    }
+}
+
+fn hex_decode(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
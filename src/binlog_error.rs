@@ -31,4 +31,18 @@ pub enum BinlogError {
 
     #[error("invalid gtid: {0}")]
     InvalidGtid(String),
+
+    #[error("unsupported row image: {0}")]
+    UnsupportedRowImage(String),
+
+    #[error("decrypt error: {0}")]
+    DecryptError(String),
+
+    #[cfg(feature = "arrow")]
+    #[error("arrow error: {0}")]
+    ArrowError(#[from] arrow_schema::ArrowError),
+
+    #[cfg(feature = "parquet")]
+    #[error("parquet error: {0}")]
+    ParquetError(#[from] parquet::errors::ParquetError),
 }
@@ -0,0 +1,207 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+#[cfg(feature = "gzip")]
+use flate2::read::MultiGzDecoder;
+#[cfg(feature = "xz")]
+use xz2::read::XzDecoder;
+
+use crate::{binlog_error::BinlogError, binlog_parser::ENCRYPTED_MAGIC_VALUE};
+
+/// Compression of a binlog file, detected by the magic bytes at its start.
+///
+/// Gzip and xz files are only decompressed with the `gzip` and `xz` features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Xz,
+}
+
+impl Compression {
+    pub fn detect(magic: &[u8]) -> Self {
+        if magic.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else if magic.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Compression::Xz
+        } else {
+            Compression::None
+        }
+    }
+
+    /// File name extension of the compression, such as "gz".
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gz"),
+            Compression::Zstd => Some("zst"),
+            Compression::Xz => Some("xz"),
+        }
+    }
+
+    /// Strips the compression extension of a file name, `mysql-bin.000001.gz` -> `mysql-bin.000001`.
+    pub fn strip_extension(file_name: &str) -> &str {
+        [Compression::Gzip, Compression::Zstd, Compression::Xz]
+            .iter()
+            .filter_map(|c| file_name.strip_suffix(&format!(".{}", c.extension()?)))
+            .next()
+            .unwrap_or(file_name)
+    }
+}
+
+/// Splits a binlog file name into its basename and sequence number, to order files by,
+/// `mysql-bin.000001` -> (`mysql-bin`, 1). A name without a numeric extension is sequence 0.
+pub fn binlog_file_sequence(file_name: &str) -> (&str, u64) {
+    file_name
+        .rsplit_once('.')
+        .and_then(|(basename, extension)| Some((basename, extension.parse().ok()?)))
+        .unwrap_or((file_name, 0))
+}
+
+/// A binlog file which may be compressed by gzip, zstd or xz, read as the uncompressed binlog.
+///
+/// Positions are in uncompressed binlog coordinates. Compressed files are decompressed as a
+/// stream, so they only seek forward, by skipping the decompressed bytes.
+pub struct BinlogFile {
+    inner: Inner,
+    compression: Compression,
+    position: u64,
+}
+
+enum Inner {
+    Plain(BufReader<File>),
+    Decoder(BufReader<Box<dyn Read + Send>>),
+}
+
+impl BinlogFile {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, BinlogError> {
+        let mut file = File::open(path)?;
+        let mut magic = Vec::with_capacity(6);
+        (&mut file).take(6).read_to_end(&mut magic)?;
+        file.seek(SeekFrom::Start(0))?;
+
+        if magic.starts_with(&ENCRYPTED_MAGIC_VALUE) {
+            return Err(BinlogError::DecryptError(
+                "the binlog is encrypted, read it by EncryptedBinlogReader of the encryption feature".into(),
+            ));
+        }
+        let compression = Compression::detect(&magic);
+        let decoder: Box<dyn Read + Send> = match compression {
+            Compression::None => {
+                return Ok(Self {
+                    inner: Inner::Plain(BufReader::new(file)),
+                    compression,
+                    position: 0,
+                })
+            }
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Box::new(MultiGzDecoder::new(file)),
+            Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(file)?),
+            #[cfg(feature = "xz")]
+            Compression::Xz => Box::new(XzDecoder::new_multi_decoder(file)),
+            #[allow(unreachable_patterns)]
+            _ => {
+                return Err(BinlogError::UnexpectedData(format!(
+                    "the binlog is compressed by {:?}, enable the gzip or xz feature to read it",
+                    compression
+                )))
+            }
+        };
+        Ok(Self {
+            inner: Inner::Decoder(BufReader::new(decoder)),
+            compression,
+            position: 0,
+        })
+    }
+
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Whether all the (uncompressed) bytes are read.
+    pub fn is_eof(&mut self) -> Result<bool, BinlogError> {
+        Ok(self.fill_buf()?.is_empty())
+    }
+}
+
+impl Read for BinlogFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = match &mut self.inner {
+            Inner::Plain(reader) => reader.read(buf)?,
+            Inner::Decoder(reader) => reader.read(buf)?,
+        };
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+impl BufRead for BinlogFile {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match &mut self.inner {
+            Inner::Plain(reader) => reader.fill_buf(),
+            Inner::Decoder(reader) => reader.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match &mut self.inner {
+            Inner::Plain(reader) => reader.consume(amt),
+            Inner::Decoder(reader) => reader.consume(amt),
+        }
+        self.position += amt as u64;
+    }
+}
+
+impl Seek for BinlogFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match (pos, &mut self.inner) {
+            (SeekFrom::Start(target), _) => target,
+            (SeekFrom::Current(offset), _) => {
+                self.position.checked_add_signed(offset).ok_or_else(|| {
+                    io::Error::new(ErrorKind::InvalidInput, "seek to a negative position")
+                })?
+            }
+            (SeekFrom::End(_), Inner::Plain(reader)) => {
+                self.position = reader.seek(pos)?;
+                return Ok(self.position);
+            }
+            (SeekFrom::End(_), Inner::Decoder(_)) => {
+                return Err(io::Error::new(
+                    ErrorKind::Unsupported,
+                    "can not seek from the end of a compressed binlog",
+                ))
+            }
+        };
+
+        match &mut self.inner {
+            // seek_relative keeps the buffered bytes, unlike seek
+            Inner::Plain(reader) => {
+                reader.seek_relative(target as i64 - self.position as i64)?;
+            }
+            Inner::Decoder(_) if target < self.position => {
+                return Err(io::Error::new(
+                    ErrorKind::Unsupported,
+                    "can not seek backward in a compressed binlog",
+                ))
+            }
+            Inner::Decoder(reader) => {
+                let skip = target - self.position;
+                if io::copy(&mut reader.take(skip), &mut io::sink())? < skip {
+                    return Err(ErrorKind::UnexpectedEof.into());
+                }
+            }
+        }
+        self.position = target;
+        Ok(target)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.position)
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Cursor, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    binlog_client::StartPosition,
+    binlog_error::BinlogError,
+    binlog_parser::MAGIC_VALUE,
+    constants,
+    event::{
+        event_header::EventHeader, event_type::EventType,
+        format_description_event::FormatDescriptionEvent, query_event::QueryEvent,
+        rotate_event::RotateEvent,
+    },
+};
+
+/// Flag of the events generated by the server which are not in the binlog files,
+/// such as the fake RotateEvent sent at the start of a dump.
+const LOG_EVENT_ARTIFICIAL_F: u16 = 0x20;
+
+/// Mirrors the raw events received from the server into local binlog files, like
+/// `mysqlbinlog --read-from-remote-server --raw --stop-never`.
+///
+/// Local files are named after the server's binlog files and listed in an index file.
+/// A new file is started by each artificial RotateEvent sent by the server, and the files
+/// are fsynced at transaction boundaries. After a restart, the partial event at the end of
+/// the last file is truncated and streaming resumes from `start_position`.
+pub struct BinlogMirror {
+    pub dir: PathBuf,
+    pub index_filename: String,
+    binlog_filenames: Vec<String>,
+    current: Option<MirrorFile>,
+    /// Server position after the last complete event of the last file, found by open.
+    resume_position: Option<(String, u32)>,
+    /// Checksum length of the events, set by BinlogStream from the server's binlog_checksum
+    /// and updated by FormatDescriptionEvents.
+    pub checksum_length: u8,
+}
+
+struct MirrorFile {
+    binlog_filename: String,
+    writer: BufWriter<File>,
+    /// Local length of the file, including the buffered bytes.
+    length: u64,
+}
+
+impl BinlogMirror {
+    /// Opens a mirror directory with the default index file `binlog.index`.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, BinlogError> {
+        Self::open_with_index(dir, "binlog.index")
+    }
+
+    /// Opens a mirror directory, the directory is created if not exists.
+    pub fn open_with_index(
+        dir: impl AsRef<Path>,
+        index_filename: &str,
+    ) -> Result<Self, BinlogError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let index_path = dir.join(index_filename);
+        let binlog_filenames: Vec<String> = if index_path.exists() {
+            fs::read_to_string(&index_path)?
+                .lines()
+                .map(|line| line.trim().trim_start_matches("./").to_string())
+                .filter(|line| !line.is_empty())
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut mirror = Self {
+            dir,
+            index_filename: index_filename.to_string(),
+            binlog_filenames,
+            current: None,
+            resume_position: None,
+            checksum_length: 0,
+        };
+        if let Some(binlog_filename) = mirror.binlog_filenames.last().cloned() {
+            let position = mirror.recover(&binlog_filename)?;
+            mirror.resume_position = Some((binlog_filename, position));
+        }
+        Ok(mirror)
+    }
+
+    /// Where to start streaming from to continue the mirror, None if the mirror is empty.
+    pub fn start_position(&self) -> Option<StartPosition> {
+        self.resume_position
+            .as_ref()
+            .map(|(filename, position)| StartPosition::BinlogPosition(filename.clone(), *position))
+    }
+
+    /// Names of the mirrored binlog files, in the order of the index file.
+    pub fn binlog_filenames(&self) -> &[String] {
+        &self.binlog_filenames
+    }
+
+    /// Writes an event received from the server, without the OK byte of the packet.
+    pub fn write(&mut self, buf: &[u8]) -> Result<(), BinlogError> {
+        let header = EventHeader::parse(&mut Cursor::new(buf))?;
+        let event_length = header.event_length as usize;
+        if buf.len() < event_length || event_length < constants::EVENT_HEADER_LENGTH {
+            return Err(BinlogError::UnexpectedData(format!(
+                "invalid event length {}, packet length {}",
+                event_length,
+                buf.len()
+            )));
+        }
+        let buf = &buf[..event_length];
+
+        let event_type = EventType::from_code(header.event_type);
+        let is_boundary = match event_type {
+            EventType::HeartBeat => return Ok(()),
+
+            EventType::Rotate => {
+                let rotate_event = RotateEvent::parse(&mut Cursor::new(&self.body(buf)))?;
+                if header.event_flags & LOG_EVENT_ARTIFICIAL_F != 0 || header.timestamp == 0 {
+                    return self.switch(&rotate_event.binlog_filename);
+                }
+                // the real RotateEvent is the last event of the current file
+                true
+            }
+
+            EventType::FormatDescription => {
+                let body = buf[constants::EVENT_HEADER_LENGTH..].to_vec();
+                let event = FormatDescriptionEvent::parse(&mut Cursor::new(&body), body.len())?;
+                self.checksum_length = event.checksum_type.get_length();
+                // when resuming, the server resends the FormatDescriptionEvent which
+                // is already in the file
+                if self.current_file()?.length > MAGIC_VALUE.len() as u64 {
+                    return Ok(());
+                }
+                false
+            }
+
+            EventType::Query => {
+                let event = QueryEvent::parse(&mut Cursor::new(&self.body(buf)))?;
+                event.query != "BEGIN"
+            }
+
+            EventType::Xid | EventType::XaPrepare | EventType::TransactionPayload => true,
+
+            _ => false,
+        };
+
+        let file = self.current_file()?;
+        file.writer.write_all(buf)?;
+        file.length += buf.len() as u64;
+        if is_boundary {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    /// Flushes the buffered events and fsyncs the current file.
+    pub fn sync(&mut self) -> Result<(), BinlogError> {
+        if let Some(file) = self.current.as_mut() {
+            file.writer.flush()?;
+            file.writer.get_ref().sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// Name and local length of the file being written.
+    pub fn position(&self) -> Option<(&str, u64)> {
+        self.current
+            .as_ref()
+            .map(|file| (file.binlog_filename.as_str(), file.length))
+    }
+
+    fn current_file(&mut self) -> Result<&mut MirrorFile, BinlogError> {
+        self.current.as_mut().ok_or_else(|| {
+            BinlogError::UnexpectedData("no RotateEvent received before events".into())
+        })
+    }
+
+    /// Event data without the header and the checksum.
+    fn body(&self, buf: &[u8]) -> Vec<u8> {
+        let end = buf.len().saturating_sub(self.checksum_length as usize);
+        buf[constants::EVENT_HEADER_LENGTH..end.max(constants::EVENT_HEADER_LENGTH)].to_vec()
+    }
+
+    /// Switches to the file of an artificial RotateEvent, which is created if not exists.
+    fn switch(&mut self, binlog_filename: &str) -> Result<(), BinlogError> {
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|file| file.binlog_filename == binlog_filename)
+        {
+            return Ok(());
+        }
+        self.sync()?;
+        self.current = None;
+
+        let path = self.dir.join(binlog_filename);
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut length = file.metadata()?.len();
+        if length == 0 {
+            file.write_all(&MAGIC_VALUE)?;
+            length = MAGIC_VALUE.len() as u64;
+        }
+
+        if !self.binlog_filenames.iter().any(|f| f == binlog_filename) {
+            self.binlog_filenames.push(binlog_filename.to_string());
+            let mut index = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.dir.join(&self.index_filename))?;
+            writeln!(index, "./{}", binlog_filename)?;
+            index.sync_data()?;
+            // persist the new directory entries
+            #[cfg(unix)]
+            File::open(&self.dir)?.sync_all()?;
+        }
+
+        self.current = Some(MirrorFile {
+            binlog_filename: binlog_filename.to_string(),
+            writer: BufWriter::new(file),
+            length,
+        });
+        Ok(())
+    }
+
+    /// Truncates the partial event at the end of a local file,
+    /// returns the server position after the last complete event.
+    /// Only the event headers are read, the file may be as large as max_binlog_size.
+    fn recover(&self, binlog_filename: &str) -> Result<u32, BinlogError> {
+        let path = self.dir.join(binlog_filename);
+        let magic_length = MAGIC_VALUE.len();
+        if !path.exists() {
+            File::create(&path)?;
+            return Ok(magic_length as u32);
+        }
+
+        let mut file = OpenOptions::new().read(true).write(true).open(&path)?;
+        let file_length = file.metadata()?.len();
+        let mut magic = [0u8; MAGIC_VALUE.len()];
+        let has_magic = file_length >= magic_length as u64 && {
+            file.read_exact(&mut magic)?;
+            magic == MAGIC_VALUE
+        };
+        if !has_magic {
+            // nothing complete, the file is rewritten from the start
+            file.set_len(0)?;
+            return Ok(magic_length as u32);
+        }
+
+        let mut offset = magic_length as u64;
+        let mut position = offset as u32;
+        while offset + constants::EVENT_HEADER_LENGTH as u64 <= file_length {
+            file.seek(SeekFrom::Start(offset))?;
+            let header = EventHeader::parse(&mut file)?;
+            let event_length = header.event_length as u64;
+            if event_length < constants::EVENT_HEADER_LENGTH as u64
+                || offset + event_length > file_length
+            {
+                break;
+            }
+            offset += event_length;
+            // events resent by the server may have no position, the local offset is used then
+            position = match header.next_event_position {
+                0 => offset as u32,
+                next_event_position => next_event_position,
+            };
+        }
+
+        if offset < file_length {
+            file.set_len(offset)?;
+            file.sync_all()?;
+        }
+        Ok(position)
+    }
+}
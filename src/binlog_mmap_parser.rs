@@ -0,0 +1,213 @@
+use std::{collections::HashMap, fs::File, io::Cursor, ops::Range, path::Path, thread};
+
+use memmap2::Mmap;
+
+use crate::{
+    binlog_error::BinlogError,
+    binlog_parser::{BinlogParser, MAGIC_VALUE},
+    constants,
+    event::{
+        event_data::EventData, event_data_ref::EventDataRef, event_header::EventHeader,
+        event_type::EventType,
+    },
+};
+
+/// Parses a memory-mapped binlog file, events are walked in place over the mapped bytes
+/// and rows events are decoded by BinlogParser::next_ref without copying.
+///
+/// The file can be split into regions starting at transaction boundaries, which are
+/// independent (each transaction logs its own TableMapEvents) and decoded in parallel.
+pub struct BinlogMmapParser {
+    mmap: Mmap,
+    /// Checksum length of the events, from the FormatDescriptionEvent of the file.
+    pub checksum_length: u8,
+}
+
+impl BinlogMmapParser {
+    /// Maps a binlog file.
+    ///
+    /// # Safety
+    ///
+    /// The mapped part of the file must not be truncated or modified while the parser is
+    /// alive, by this process or another one, or reading it is undefined behavior (SIGBUS
+    /// on truncation). Closed binlogs of the server are safe to map, but not a file being
+    /// written by BinlogMirror, whose recover truncates the partial event at the end.
+    /// Read such files with BinlogParser instead.
+    pub unsafe fn open(path: impl AsRef<Path>) -> Result<Self, BinlogError> {
+        let file = File::open(path)?;
+        // SAFETY: the caller guarantees the file is not modified while mapped
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let mut parser = new_parser(0);
+        parser.check_magic(&mut Cursor::new(&mmap[..]))?;
+        let mut me = Self {
+            mmap,
+            checksum_length: 0,
+        };
+        let first_event = me.raw_events(me.events_range()).next().transpose()?;
+        if let Some((_, event)) = first_event {
+            if let (_, EventData::FormatDescription(event)) =
+                parser.next(&mut Cursor::new(event))?
+            {
+                parser.checksum_length = event.checksum_type.get_length();
+            }
+        }
+        me.checksum_length = parser.checksum_length;
+        Ok(me)
+    }
+
+    /// The mapped bytes of the file.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    /// Range of all the events, after the magic.
+    pub fn events_range(&self) -> Range<usize> {
+        self.start_offset()..self.mmap.len()
+    }
+
+    /// Iterates the complete events of a range without decoding them,
+    /// items are the offset and the bytes of each event.
+    ///
+    /// A partial event at the end of the file ends the iteration, an invalid event length
+    /// or an event crossing the end of the range is an error.
+    pub fn raw_events(&self, range: Range<usize>) -> RawEvents<'_> {
+        RawEvents {
+            buf: &self.mmap,
+            offset: range.start,
+            end: range.end.min(self.mmap.len()),
+        }
+    }
+
+    /// Splits the events into at most `count` regions of similar sizes,
+    /// each starting at the first transaction after its share of the file.
+    ///
+    /// A transaction starts at a GTID event (Gtid, AnonymousGtid or MariaDB Gtid),
+    /// or at the event following a XidEvent for binlogs without GTID events.
+    pub fn split_regions(&self, count: usize) -> Vec<Range<usize>> {
+        let range = self.events_range();
+        let count = count.max(1);
+        let region_length = range.len() / count;
+
+        let mut starts = vec![range.start];
+        let mut after_xid = false;
+        // a damaged event ends the scan, it is reported when the region is parsed
+        for (offset, event) in self.raw_events(range.clone()).map_while(Result::ok) {
+            let event_type = EventType::from_code(event[4]);
+            let is_transaction_start = after_xid
+                || matches!(
+                    event_type,
+                    EventType::Gtid | EventType::AnonymousGtid | EventType::MariadbGtid
+                );
+            after_xid = matches!(event_type, EventType::Xid);
+
+            let next_start = range.start + region_length * starts.len();
+            if is_transaction_start && offset >= next_start && offset > *starts.last().unwrap() {
+                starts.push(offset);
+                if starts.len() == count {
+                    break;
+                }
+            }
+        }
+
+        let mut ends: Vec<usize> = starts[1..].to_vec();
+        ends.push(range.end);
+        starts.into_iter().zip(ends).map(|(s, e)| s..e).collect()
+    }
+
+    /// Decodes the events of a region in order, `f` is called with the offset,
+    /// the header and the data of each event.
+    pub fn parse_region<F>(&self, range: Range<usize>, mut f: F) -> Result<(), BinlogError>
+    where
+        F: FnMut(usize, EventHeader, EventDataRef<'_>) -> Result<(), BinlogError>,
+    {
+        let mut parser = new_parser(self.checksum_length);
+        for raw_event in self.raw_events(range) {
+            let (offset, event) = raw_event?;
+            let (header, data) = parser.next_ref(event)?;
+            f(offset, header, data)?;
+        }
+        Ok(())
+    }
+
+    /// Decodes the regions in parallel, one thread per region. `f` is called from the threads,
+    /// events of a region are handled in order. The first error is returned.
+    pub fn parse_parallel<F>(&self, regions: &[Range<usize>], f: F) -> Result<(), BinlogError>
+    where
+        F: Fn(usize, EventHeader, EventDataRef<'_>) -> Result<(), BinlogError> + Sync,
+    {
+        thread::scope(|scope| {
+            let handles: Vec<_> = regions
+                .iter()
+                .map(|region| scope.spawn(|| self.parse_region(region.clone(), &f)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("region parsing panicked"))
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+        Ok(())
+    }
+
+    fn start_offset(&self) -> usize {
+        MAGIC_VALUE.len().min(self.mmap.len())
+    }
+}
+
+/// Iterator of the complete events in a binlog buffer, see BinlogMmapParser::raw_events.
+pub struct RawEvents<'a> {
+    /// The whole file.
+    buf: &'a [u8],
+    offset: usize,
+    /// End of the range.
+    end: usize,
+}
+
+impl<'a> Iterator for RawEvents<'a> {
+    type Item = Result<(usize, &'a [u8]), BinlogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.end {
+            return None;
+        }
+        // a partial event at the end of the file stops the iteration
+        let header_end = self.offset + constants::EVENT_HEADER_LENGTH;
+        if header_end > self.buf.len() {
+            return None;
+        }
+        let event_length = u32::from_le_bytes(
+            self.buf[self.offset + 9..self.offset + 13]
+                .try_into()
+                .unwrap(),
+        ) as usize;
+        let end = self.offset + event_length;
+        if event_length >= constants::EVENT_HEADER_LENGTH && end > self.buf.len() {
+            return None;
+        }
+
+        let offset = self.offset;
+        // nothing is read after a damaged event
+        self.offset = self.end;
+        if event_length < constants::EVENT_HEADER_LENGTH {
+            return Some(Err(BinlogError::UnexpectedData(format!(
+                "invalid event length {} at offset {}",
+                event_length, offset
+            ))));
+        }
+        if end > self.end {
+            return Some(Err(BinlogError::UnexpectedData(format!(
+                "event at offset {} with length {} crosses the end of the range at {}",
+                offset, event_length, self.end
+            ))));
+        }
+        self.offset = end;
+        Some(Ok((offset, &self.buf[offset..end])))
+    }
+}
+
+fn new_parser(checksum_length: u8) -> BinlogParser {
+    BinlogParser {
+        checksum_length,
+        table_map_event_by_table_id: HashMap::new(),
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Seek, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    binlog_bounded_reader::TransactionBoundary, binlog_error::BinlogError, binlog_file::BinlogFile,
+    binlog_parser::BinlogParser, event::event_data::EventData,
+};
+
+/// Extension of the sidecar file of an index, `mysql-bin.000001` -> `mysql-bin.000001.idx`.
+pub const INDEX_FILE_EXTENSION: &str = "idx";
+
+/// Start of a transaction in a binlog file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionOffset {
+    /// Position of the first event of the transaction, such as its GtidEvent.
+    pub position: u64,
+    /// Timestamp of the first event, in seconds.
+    pub timestamp: u32,
+    /// None for transactions without GtidEvent.
+    pub gtid: Option<String>,
+    /// Ids of the tables in the TableMapEvents of the transaction.
+    pub table_ids: Vec<u64>,
+}
+
+/// Offsets of the transactions in a binlog file, to seek to a GTID, a time or a position
+/// without scanning the file, see BinlogBoundedReader::seek_to_gtid.
+///
+/// The index is persisted as a JSON sidecar file next to the binlog.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BinlogOffsetIndex {
+    /// Size of the indexed file on disk, an index of another size is stale.
+    pub file_size: u64,
+    /// Transactions in the order of the file.
+    pub transactions: Vec<TransactionOffset>,
+}
+
+impl BinlogOffsetIndex {
+    /// Builds the index of a binlog file, which may be compressed, see BinlogFile.
+    pub fn build(path: impl AsRef<Path>) -> Result<Self, BinlogError> {
+        let file_size = std::fs::metadata(path.as_ref())?.len();
+        let mut index = Self::from_stream(BinlogFile::open(path)?)?;
+        index.file_size = file_size;
+        Ok(index)
+    }
+
+    /// Builds the index of a binlog stream, which starts with the magic.
+    pub fn from_stream<S: BufRead + Seek>(mut stream: S) -> Result<Self, BinlogError> {
+        let mut parser = BinlogParser {
+            checksum_length: 0,
+            table_map_event_by_table_id: HashMap::new(),
+        };
+        parser.check_magic(&mut stream)?;
+
+        let mut transactions: Vec<TransactionOffset> = Vec::new();
+        let mut in_transaction = false;
+        let mut boundary = TransactionBoundary::default();
+        while !stream.fill_buf()?.is_empty() {
+            let position = stream.stream_position()?;
+            let (header, data) = parser.next(&mut stream)?;
+            if matches!(
+                data,
+                EventData::FormatDescription(_)
+                    | EventData::PreviousGtids(_)
+                    | EventData::Rotate(_)
+                    | EventData::HeartBeat
+            ) {
+                continue;
+            }
+
+            if !in_transaction {
+                in_transaction = true;
+                transactions.push(TransactionOffset {
+                    position,
+                    timestamp: header.timestamp,
+                    gtid: None,
+                    table_ids: Vec::new(),
+                });
+            }
+
+            let transaction = transactions.last_mut().unwrap();
+            match &data {
+                EventData::Gtid(event) => transaction.gtid = Some(event.gtid.clone()),
+                EventData::TableMap(event) => add_table_id(transaction, event.table_id),
+                EventData::TransactionPayload(event) => {
+                    for (_, data) in &event.uncompressed_events {
+                        if let EventData::TableMap(event) = data {
+                            add_table_id(transaction, event.table_id);
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            if boundary.is_end(&data) {
+                in_transaction = false;
+            }
+        }
+
+        Ok(Self {
+            file_size: 0,
+            transactions,
+        })
+    }
+
+    /// Path of the sidecar file of a binlog file.
+    pub fn sidecar_path(binlog_path: impl AsRef<Path>) -> PathBuf {
+        let mut path = binlog_path.as_ref().as_os_str().to_owned();
+        path.push(".");
+        path.push(INDEX_FILE_EXTENSION);
+        PathBuf::from(path)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BinlogError> {
+        let reader = BufReader::new(File::open(path)?);
+        serde_json::from_reader(reader)
+            .map_err(|e| BinlogError::UnexpectedData(format!("invalid binlog index: {}", e)))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), BinlogError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self)
+            .map_err(|e| BinlogError::UnexpectedData(format!("failed to write index: {}", e)))?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Loads the sidecar index of a binlog file, it is built and saved if missing or stale.
+    pub fn load_or_build(binlog_path: impl AsRef<Path>) -> Result<Self, BinlogError> {
+        let binlog_path = binlog_path.as_ref();
+        let sidecar_path = Self::sidecar_path(binlog_path);
+        let file_size = std::fs::metadata(binlog_path)?.len();
+        if sidecar_path.exists() {
+            if let Ok(index) = Self::load(&sidecar_path) {
+                if index.file_size == file_size {
+                    return Ok(index);
+                }
+            }
+        }
+
+        let index = Self::build(binlog_path)?;
+        index.save(&sidecar_path)?;
+        Ok(index)
+    }
+
+    pub fn find_gtid(&self, gtid: &str) -> Option<&TransactionOffset> {
+        self.transactions
+            .iter()
+            .find(|t| t.gtid.as_deref() == Some(gtid))
+    }
+
+    /// The first transaction at or after a time in seconds.
+    pub fn find_time(&self, timestamp: u32) -> Option<&TransactionOffset> {
+        self.transactions.iter().find(|t| t.timestamp >= timestamp)
+    }
+
+    /// The transaction containing a position, the last one starting at or before it.
+    pub fn find_position(&self, position: u64) -> Option<&TransactionOffset> {
+        let count = self
+            .transactions
+            .partition_point(|t| t.position <= position);
+        count.checked_sub(1).map(|i| &self.transactions[i])
+    }
+}
+
+fn add_table_id(transaction: &mut TransactionOffset, table_id: u64) {
+    if !transaction.table_ids.contains(&table_id) {
+        transaction.table_ids.push(table_id);
+    }
+}
@@ -2,12 +2,21 @@ use crate::{
     binlog_error::BinlogError,
     constants,
     event::{
-        delete_rows_event::DeleteRowsEvent, event_data::*, event_header::EventHeader,
-        gtid_event::GtidEvent, previous_gtids_event::PreviousGtidsEvent, query_event::QueryEvent,
-        rotate_event::RotateEvent, rows_query_event::RowsQueryEvent,
-        table_map_event::TableMapEvent, transaction_payload_event::TransactionPayloadEvent,
-        update_rows_event::UpdateRowsEvent, write_rows_event::WriteRowsEvent,
-        xa_prepare_event::XaPrepareEvent, xid_event::XidEvent,
+        delete_rows_event::DeleteRowsEvent,
+        event_data::*,
+        event_data_ref::{EventDataRef, RowsEventRef, UpdateRowsEventRef},
+        event_header::EventHeader,
+        gtid_event::GtidEvent,
+        previous_gtids_event::PreviousGtidsEvent,
+        query_event::QueryEvent,
+        rotate_event::RotateEvent,
+        rows_query_event::RowsQueryEvent,
+        table_map_event::TableMapEvent,
+        transaction_payload_event::TransactionPayloadEvent,
+        update_rows_event::UpdateRowsEvent,
+        write_rows_event::WriteRowsEvent,
+        xa_prepare_event::XaPrepareEvent,
+        xid_event::XidEvent,
     },
     event::{event_type::EventType, format_description_event::FormatDescriptionEvent},
 };
@@ -22,7 +31,9 @@ pub struct BinlogParser {
     pub table_map_event_by_table_id: HashMap<u64, TableMapEvent>,
 }
 
-const MAGIC_VALUE: [u8; 4] = [0xfeu8, 0x62, 0x69, 0x6e];
+pub(crate) const MAGIC_VALUE: [u8; 4] = [0xfeu8, 0x62, 0x69, 0x6e];
+/// Magic of the binlog files encrypted with binlog_encryption=ON.
+pub const ENCRYPTED_MAGIC_VALUE: [u8; 4] = [0xfd, 0x62, 0x69, 0x6e];
 
 impl BinlogParser {
     pub fn check_magic<S: Read + Seek>(&mut self, stream: &mut S) -> Result<(), BinlogError> {
@@ -39,9 +50,7 @@ impl BinlogParser {
         stream: &mut S,
     ) -> Result<(EventHeader, EventData), BinlogError> {
         let header = EventHeader::parse(stream)?;
-        let data_length = header.event_length as usize
-            - constants::EVENT_HEADER_LENGTH
-            - self.checksum_length as usize;
+        let data_length = self.data_length(&header)?;
 
         let buf = self.read_event_data(stream, data_length)?;
         let mut cursor = Cursor::new(&buf);
@@ -125,6 +134,68 @@ impl BinlogParser {
         }
     }
 
+    /// Length of the event data between the header and the checksum.
+    fn data_length(&self, header: &EventHeader) -> Result<usize, BinlogError> {
+        (header.event_length as usize)
+            .checked_sub(constants::EVENT_HEADER_LENGTH + self.checksum_length as usize)
+            .ok_or_else(|| {
+                BinlogError::UnexpectedData(format!(
+                    "invalid event length: {}",
+                    header.event_length
+                ))
+            })
+    }
+
+    /// Parses an event from a buffer containing the complete event (header, data and checksum)
+    /// without copying it, rows events are returned as EventDataRef borrowing the buffer,
+    /// whose column values are decoded lazily.
+    pub fn next_ref<'a>(
+        &'a mut self,
+        buf: &'a [u8],
+    ) -> Result<(EventHeader, EventDataRef<'a>), BinlogError> {
+        let header = EventHeader::parse(&mut Cursor::new(buf))?;
+        let event_type = EventType::from_code(header.event_type);
+        let row_event_version = Self::get_row_event_version(&event_type);
+
+        let data_end = constants::EVENT_HEADER_LENGTH + self.data_length(&header)?;
+        if !matches!(
+            event_type,
+            EventType::WriteRows
+                | EventType::ExtWriteRows
+                | EventType::UpdateRows
+                | EventType::ExtUpdateRows
+                | EventType::DeleteRows
+                | EventType::ExtDeleteRows
+        ) || data_end > buf.len()
+        {
+            let (header, event_data) = self.next(&mut Cursor::new(buf))?;
+            return Ok((header, EventDataRef::Owned(event_data)));
+        }
+
+        let mut cursor = Cursor::new(&buf[constants::EVENT_HEADER_LENGTH..data_end]);
+        let table_map_event_by_table_id = &self.table_map_event_by_table_id;
+        let event_data = match event_type {
+            EventType::WriteRows | EventType::ExtWriteRows => EventDataRef::WriteRows(
+                RowsEventRef::parse(&mut cursor, table_map_event_by_table_id, row_event_version)?,
+            ),
+
+            EventType::UpdateRows | EventType::ExtUpdateRows => {
+                EventDataRef::UpdateRows(UpdateRowsEventRef::parse(
+                    &mut cursor,
+                    table_map_event_by_table_id,
+                    row_event_version,
+                )?)
+            }
+
+            _ => EventDataRef::DeleteRows(RowsEventRef::parse(
+                &mut cursor,
+                table_map_event_by_table_id,
+                row_event_version,
+            )?),
+        };
+        Ok((header, event_data))
+    }
+
     fn read_event_data<S: Read + Seek>(
         &mut self,
         stream: &mut S,
@@ -138,7 +209,7 @@ impl BinlogParser {
         Ok(buf)
     }
 
-    fn get_row_event_version(event_type: &EventType) -> u8 {
+    pub(crate) fn get_row_event_version(event_type: &EventType) -> u8 {
         match event_type {
             EventType::ExtWriteRows | EventType::ExtUpdateRows | EventType::ExtDeleteRows => 2,
             _ => 1,
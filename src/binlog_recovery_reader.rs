@@ -0,0 +1,309 @@
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use crate::{
+    binlog_error::BinlogError,
+    binlog_parser::{BinlogParser, MAGIC_VALUE},
+    constants,
+    event::{
+        checksum_type::ChecksumType, event_data::EventData, event_header::EventHeader,
+        event_type::EventType,
+    },
+};
+
+const SCAN_WINDOW_SIZE: u64 = 64 * 1024;
+/// Events are limited by max_allowed_packet, which is at most 1 GiB.
+const MAX_EVENT_LENGTH: u32 = 1024 * 1024 * 1024;
+
+/// A damaged byte range skipped by BinlogRecoveryReader, [start, end) in file positions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedRange {
+    pub start: u64,
+    pub end: u64,
+    pub reason: String,
+}
+
+/// Reads a truncated or damaged binlog file, skipping the bytes which can not be read as events.
+///
+/// An event is valid if its header is plausible (known type, sane length and a
+/// `next_event_position` chaining with the previous event), its CRC32 matches when the binlog
+/// has checksums, and it is parsed. At an invalid event, the reader scans forward for the next
+/// valid event, without checksums the event following it must chain too. Bytes inserted or lost
+/// in the damaged range shift the file positions from the logged ones: a candidate is first
+/// checked with the shift of the previous valid event, then with the shift taken from its own
+/// header, in which case the header following it must chain with that shift as well. Candidates
+/// longer than the rest of the stream are not read. The skipped ranges are recorded in
+/// `skipped_ranges`.
+pub struct BinlogRecoveryReader<S: Read + Seek> {
+    stream: S,
+    parser: BinlogParser,
+    pub skipped_ranges: Vec<SkippedRange>,
+    position: u64,
+    /// File position minus the logged position of the events, wrapping as the logged is u32.
+    position_shift: u32,
+    finished: bool,
+}
+
+enum RawEvent {
+    Valid(Vec<u8>),
+    Invalid(String),
+    /// The stream ends within the event.
+    Truncated,
+    Eof,
+}
+
+impl BinlogRecoveryReader<std::fs::File> {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, BinlogError> {
+        Self::new(std::fs::File::open(path)?)
+    }
+}
+
+impl<S: Read + Seek> BinlogRecoveryReader<S> {
+    /// Creates a reader of a binlog stream, which starts with the magic.
+    pub fn new(mut stream: S) -> Result<Self, BinlogError> {
+        let mut parser = BinlogParser {
+            checksum_length: 0,
+            table_map_event_by_table_id: HashMap::new(),
+        };
+        stream.seek(SeekFrom::Start(0))?;
+        parser.check_magic(&mut stream)?;
+        Ok(Self {
+            stream,
+            parser,
+            skipped_ranges: Vec::new(),
+            position: MAGIC_VALUE.len() as u64,
+            position_shift: 0,
+            finished: false,
+        })
+    }
+
+    /// Reads the next valid event, None at the end of the stream.
+    pub fn read(&mut self) -> Result<Option<(EventHeader, EventData)>, BinlogError> {
+        while !self.finished {
+            let start = self.position;
+            let reason = match self.read_raw(start, self.position_shift)? {
+                RawEvent::Eof => {
+                    self.finished = true;
+                    break;
+                }
+                RawEvent::Invalid(reason) => reason,
+                RawEvent::Truncated => "truncated event".to_string(),
+                RawEvent::Valid(buf) => {
+                    let end = start + buf.len() as u64;
+                    match self.parse(&buf) {
+                        Ok(event) => {
+                            self.position = end;
+                            return Ok(Some(event));
+                        }
+                        // the bounds of the event are valid, only the event is skipped
+                        Err(e) => {
+                            self.skip(start, end, e.to_string());
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let end = self.resync(start + 1)?;
+            self.skip(start, end, reason);
+        }
+        Ok(None)
+    }
+
+    /// Position of the next event.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    fn parse(&mut self, buf: &[u8]) -> Result<(EventHeader, EventData), BinlogError> {
+        self.parser.next(&mut Cursor::new(buf))
+    }
+
+    /// Reads the bytes of the event at a position, they are checked but not parsed.
+    fn read_raw(&mut self, position: u64, shift: u32) -> Result<RawEvent, BinlogError> {
+        self.stream.seek(SeekFrom::Start(position))?;
+        let mut buf = Vec::with_capacity(constants::EVENT_HEADER_LENGTH);
+        (&mut self.stream)
+            .take(constants::EVENT_HEADER_LENGTH as u64)
+            .read_to_end(&mut buf)?;
+        if buf.is_empty() {
+            return Ok(RawEvent::Eof);
+        }
+        if buf.len() < constants::EVENT_HEADER_LENGTH {
+            return Ok(RawEvent::Truncated);
+        }
+        if let Err(reason) = self.check_header(&buf, position, shift) {
+            return Ok(RawEvent::Invalid(reason));
+        }
+
+        let event_length = event_length(&buf) as usize;
+        (&mut self.stream)
+            .take((event_length - buf.len()) as u64)
+            .read_to_end(&mut buf)?;
+        if buf.len() < event_length {
+            return Ok(RawEvent::Truncated);
+        }
+        let checksum_valid = match EventType::from_code(buf[4]) {
+            EventType::FormatDescription => self.is_format_description_checksum_valid(&buf),
+            _ => self.is_checksum_valid(&buf),
+        };
+        if !checksum_valid {
+            return Ok(RawEvent::Invalid("checksum mismatch".into()));
+        }
+        Ok(RawEvent::Valid(buf))
+    }
+
+    fn check_header(&self, header: &[u8], position: u64, shift: u32) -> Result<(), String> {
+        let event_type = EventType::from_code(header[4]);
+        if matches!(event_type, EventType::Unknown) {
+            return Err(format!("unknown event type {}", header[4]));
+        }
+        let event_length = event_length(header);
+        let min_length =
+            (constants::EVENT_HEADER_LENGTH + self.parser.checksum_length as usize) as u32;
+        if !(min_length..=MAX_EVENT_LENGTH).contains(&event_length) {
+            return Err(format!("invalid event length {}", event_length));
+        }
+        let next_event_position = next_event_position(header);
+        if next_event_position.wrapping_add(shift) != (position + event_length as u64) as u32 {
+            return Err(format!(
+                "invalid next event position {}",
+                next_event_position
+            ));
+        }
+        Ok(())
+    }
+
+    fn is_checksum_valid(&self, buf: &[u8]) -> bool {
+        if self.parser.checksum_length != 4 {
+            return true;
+        }
+        let (data, checksum) = buf.split_at(buf.len() - 4);
+        crc32fast::hash(data) == u32::from_le_bytes(checksum.try_into().unwrap())
+    }
+
+    /// A FormatDescriptionEvent ends with its checksum algorithm and a 4 bytes checksum, which
+    /// is checked before the event is parsed.
+    fn is_format_description_checksum_valid(&self, buf: &[u8]) -> bool {
+        if buf.len() < constants::EVENT_HEADER_LENGTH + 5 {
+            return false;
+        }
+        let (data, checksum) = buf.split_at(buf.len() - 4);
+        match ChecksumType::from_code(data[data.len() - 1]) {
+            ChecksumType::CRC32 => {
+                crc32fast::hash(data) == u32::from_le_bytes(checksum.try_into().unwrap())
+            }
+            // once checksums are known to be on, a damaged algorithm can not disable them
+            ChecksumType::None => data[data.len() - 1] == 0 && self.parser.checksum_length == 0,
+        }
+    }
+
+    /// Scans forward for the next valid event, returns its position or the end of the stream.
+    fn resync(&mut self, from: u64) -> Result<u64, BinlogError> {
+        let stream_length = self.stream.seek(SeekFrom::End(0))?;
+        let mut window_start = from;
+        loop {
+            self.stream.seek(SeekFrom::Start(window_start))?;
+            let mut window = Vec::new();
+            (&mut self.stream)
+                .take(SCAN_WINDOW_SIZE)
+                .read_to_end(&mut window)?;
+            if window.len() < constants::EVENT_HEADER_LENGTH {
+                return Ok(window_start + window.len() as u64);
+            }
+
+            let candidate_count = window.len() - constants::EVENT_HEADER_LENGTH + 1;
+            for i in 0..candidate_count {
+                let position = window_start + i as u64;
+                let header = &window[i..i + constants::EVENT_HEADER_LENGTH];
+                if event_length(header) as u64 > stream_length - position {
+                    continue;
+                }
+
+                // the shift of the previous valid event, then the shift the header implies
+                let shift = self.position_shift;
+                if self.check_header(header, position, shift).is_ok()
+                    && self.is_resync_point(position, shift)?
+                {
+                    return Ok(position);
+                }
+                let shift = header_shift(header, position);
+                if shift != self.position_shift
+                    && self.check_header(header, position, shift).is_ok()
+                    && self.is_next_header_chained(position + event_length(header) as u64, shift)?
+                    && self.is_resync_point(position, shift)?
+                {
+                    self.position_shift = shift;
+                    return Ok(position);
+                }
+            }
+            window_start += candidate_count as u64;
+        }
+    }
+
+    fn is_resync_point(&mut self, position: u64, shift: u32) -> Result<bool, BinlogError> {
+        let RawEvent::Valid(buf) = self.read_raw(position, shift)? else {
+            return Ok(false);
+        };
+        if self.parser.checksum_length == 4 {
+            return Ok(true);
+        }
+        // without checksums, the next event must chain too
+        let next = self.read_raw(position + buf.len() as u64, shift)?;
+        Ok(!matches!(next, RawEvent::Invalid(_)))
+    }
+
+    /// Whether the header at a position chains with the shift, or the stream ends there.
+    fn is_next_header_chained(&mut self, position: u64, shift: u32) -> Result<bool, BinlogError> {
+        self.stream.seek(SeekFrom::Start(position))?;
+        let mut header = Vec::with_capacity(constants::EVENT_HEADER_LENGTH);
+        (&mut self.stream)
+            .take(constants::EVENT_HEADER_LENGTH as u64)
+            .read_to_end(&mut header)?;
+        if header.len() < constants::EVENT_HEADER_LENGTH {
+            // the end of the stream, or a truncated last event
+            return Ok(true);
+        }
+        Ok(self.check_header(&header, position, shift).is_ok())
+    }
+
+    fn skip(&mut self, start: u64, end: u64, reason: String) {
+        self.skipped_ranges
+            .push(SkippedRange { start, end, reason });
+        self.position = end;
+    }
+}
+
+impl<S: Read + Seek> Iterator for BinlogRecoveryReader<S> {
+    type Item = Result<(EventHeader, EventData), BinlogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = self.read().transpose();
+        if matches!(res, Some(Err(_))) {
+            self.finished = true;
+        }
+        res
+    }
+}
+
+fn event_length(header: &[u8]) -> u32 {
+    u32::from_le_bytes(header[9..13].try_into().unwrap())
+}
+
+fn next_event_position(header: &[u8]) -> u32 {
+    u32::from_le_bytes(header[13..17].try_into().unwrap())
+}
+
+/// The position shift making the header chain, logged positions are u32 and wrap in files
+/// over 4 GiB.
+fn header_shift(header: &[u8], position: u64) -> u32 {
+    let end = (position + event_length(header) as u64) as u32;
+    end.wrapping_sub(next_event_position(header))
+}
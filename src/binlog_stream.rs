@@ -4,19 +4,40 @@ use byteorder::ReadBytesExt;
 
 use crate::{
     binlog_error::BinlogError,
+    binlog_mirror::BinlogMirror,
     binlog_parser::BinlogParser,
     command::command_util::CommandUtil,
     constants::MysqlRespCode,
-    event::{event_data::EventData, event_header::EventHeader},
+    event::{event_data::EventData, event_data_ref::EventDataRef, event_header::EventHeader},
     network::packet_channel::PacketChannel,
 };
 
 pub struct BinlogStream {
     pub channel: PacketChannel,
     pub parser: BinlogParser,
+    /// The last packet received by read_ref, borrowed by the returned EventDataRef
+    buf: Vec<u8>,
+    /// Mirrors the raw events into local binlog files if set
+    pub mirror: Option<BinlogMirror>,
 }
 
 impl BinlogStream {
+    pub(crate) fn new(channel: PacketChannel, parser: BinlogParser) -> Self {
+        Self {
+            channel,
+            parser,
+            buf: Vec::new(),
+            mirror: None,
+        }
+    }
+
+    /// Writes the raw events received into a local mirror, see BinlogMirror.
+    pub fn with_mirror(mut self, mut mirror: BinlogMirror) -> Self {
+        mirror.checksum_length = self.parser.checksum_length;
+        self.mirror = Some(mirror);
+        self
+    }
+
     pub async fn read(&mut self) -> Result<(EventHeader, EventData), BinlogError> {
         let buf = self.channel.read().await?;
         let mut cursor = Cursor::new(&buf);
@@ -24,12 +45,37 @@ impl BinlogStream {
         if cursor.read_u8()? == MysqlRespCode::ERROR {
             CommandUtil::parse_result(&buf)?;
         }
+        if let Some(mirror) = self.mirror.as_mut() {
+            mirror.write(&buf[1..])?;
+        }
 
         // parse events, execute the callback
         self.parser.next(&mut cursor)
     }
 
+    /// Same as read, but rows events are returned as EventDataRef borrowing the packet buffer,
+    /// column values are only decoded when accessed.
+    pub async fn read_ref(&mut self) -> Result<(EventHeader, EventDataRef<'_>), BinlogError> {
+        self.buf = self.channel.read().await?;
+        let flag = self
+            .buf
+            .first()
+            .ok_or_else(|| BinlogError::UnexpectedData("empty binlog packet".into()))?;
+        if *flag == MysqlRespCode::ERROR {
+            CommandUtil::parse_result(&self.buf)?;
+        }
+        if let Some(mirror) = self.mirror.as_mut() {
+            mirror.write(&self.buf[1..])?;
+        }
+
+        // skip the OK byte
+        self.parser.next_ref(&self.buf[1..])
+    }
+
     pub async fn close(&mut self) -> Result<(), BinlogError> {
+        if let Some(mirror) = self.mirror.as_mut() {
+            mirror.sync()?;
+        }
         self.channel.close().await?;
         Ok(())
     }
@@ -0,0 +1,68 @@
+use std::io::Write;
+
+use crate::{
+    binlog_encoder::BinlogEncoder,
+    binlog_error::BinlogError,
+    binlog_parser::MAGIC_VALUE,
+    event::{
+        event_data::EventData, event_header::EventHeader,
+        format_description_event::FormatDescriptionEvent,
+    },
+};
+
+/// Writes events into a binlog file, which starts with the magic and a FormatDescriptionEvent.
+/// The next_event_position of each event header is set to the position after the event.
+pub struct BinlogWriter<W: Write> {
+    writer: W,
+    encoder: BinlogEncoder,
+    position: u64,
+}
+
+impl<W: Write> BinlogWriter<W> {
+    /// Creates a writer, the magic and the FormatDescriptionEvent are written immediately.
+    pub fn new(
+        writer: W,
+        header: &EventHeader,
+        format_description_event: &FormatDescriptionEvent,
+    ) -> Result<Self, BinlogError> {
+        let mut me = Self {
+            writer,
+            encoder: BinlogEncoder::new(),
+            position: 0,
+        };
+        me.writer.write_all(&MAGIC_VALUE)?;
+        me.position = MAGIC_VALUE.len() as u64;
+        me.write(
+            header,
+            &EventData::FormatDescription(format_description_event.clone()),
+        )?;
+        Ok(me)
+    }
+
+    /// Writes an event, returns the position after it.
+    pub fn write(&mut self, header: &EventHeader, data: &EventData) -> Result<u64, BinlogError> {
+        let buf = self.encoder.encode_at(header, data, self.position)?;
+        let next_event_position = self.position + buf.len() as u64;
+        self.writer.write_all(&buf)?;
+        self.position = next_event_position;
+        Ok(next_event_position)
+    }
+
+    /// Position of the next event to write.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn flush(&mut self) -> Result<(), BinlogError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
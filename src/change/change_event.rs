@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use crate::event::{row::Row, row_event::RowEvent, table_map_event::TableMapEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowOperation {
+    Insert,
+    Update,
+    Delete,
+    /// A row read by a snapshot instead of from the binlog.
+    Read,
+}
+
+/// Where a change comes from in the binlog.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeSource {
+    pub server_id: u32,
+    /// Event timestamp in seconds.
+    pub timestamp: u32,
+    pub binlog_filename: String,
+    /// Start position of the event in the binlog file.
+    pub binlog_position: u64,
+    /// Position of the next event in the binlog file.
+    pub next_binlog_position: u64,
+    /// GTID of the transaction, None if GTID mode is off.
+    pub gtid: Option<String>,
+    /// Thread id of the session, from the BEGIN query event.
+    pub thread_id: Option<u32>,
+    /// Original statement, from the rows query event if binlog_rows_query_log_events is on.
+    pub query: Option<String>,
+}
+
+/// A single row change, one rows event with N rows produces N RowChanges.
+#[derive(Debug, Clone)]
+pub struct RowChange {
+    pub operation: RowOperation,
+    pub source: ChangeSource,
+    /// Index of the row in the rows event, starting from 0.
+    pub row_index: usize,
+    pub table_map_event: Arc<TableMapEvent>,
+    pub before: Option<RowEvent>,
+    pub after: Option<RowEvent>,
+    /// Columns included in the before image (binlog_row_image), columns without an entry
+    /// are included.
+    pub included_columns_before: Vec<bool>,
+    /// Columns included in the after image, columns without an entry are included.
+    pub included_columns_after: Vec<bool>,
+}
+
+/// A DDL statement.
+#[derive(Debug, Clone)]
+pub struct SchemaChange {
+    pub source: ChangeSource,
+    /// Default database when the statement was executed.
+    pub database: String,
+    pub ddl: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum ChangeEvent {
+    Begin(ChangeSource),
+    Row(RowChange),
+    Schema(SchemaChange),
+    /// xid is None for transactions ending with a COMMIT query, e.g. for non-transactional tables.
+    Commit {
+        source: ChangeSource,
+        xid: Option<u64>,
+    },
+    /// A transaction ending with a ROLLBACK or XA ROLLBACK query.
+    Rollback(ChangeSource),
+}
+
+impl RowChange {
+    pub fn database_name(&self) -> &str {
+        &self.table_map_event.database_name
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_map_event.table_name
+    }
+
+    pub fn before_row(&self) -> Option<Row<'_>> {
+        self.before
+            .as_ref()
+            .map(|row_event| Row::new(row_event, &self.table_map_event))
+    }
+
+    pub fn after_row(&self) -> Option<Row<'_>> {
+        self.after
+            .as_ref()
+            .map(|row_event| Row::new(row_event, &self.table_map_event))
+    }
+
+    /// Gets the row identifying the changed record: the after image for inserts and updates,
+    /// the before image for deletes.
+    pub fn key_row(&self) -> Option<Row<'_>> {
+        match self.operation {
+            RowOperation::Delete => self.before_row(),
+            _ => self.after_row().or_else(|| self.before_row()),
+        }
+    }
+}
+
+impl SchemaChange {
+    /// Gets the statement keyword and the object type of the DDL in upper case,
+    /// e.g. ("CREATE", "TABLE") for CREATE TEMPORARY TABLE, the object type is empty if unknown.
+    pub fn statement_type(&self) -> (String, String) {
+        let tokens = self.tokens();
+        let statement = tokens.first().map(|t| t.to_ascii_uppercase());
+        let object = tokens
+            .iter()
+            .skip(1)
+            .map(|t| t.to_ascii_uppercase())
+            .find(|t| DDL_OBJECT_TYPES.contains(&t.as_str()));
+        (statement.unwrap_or_default(), object.unwrap_or_default())
+    }
+
+    /// Gets the (database, table) of a table DDL by a simple tokenization of the statement,
+    /// database is the default database if the table name is not qualified.
+    /// For statements on multiple tables, e.g. RENAME TABLE a TO b, c TO d, only the first one is returned.
+    pub fn table(&self) -> Option<(String, String)> {
+        let tokens = self.tokens();
+        let position = tokens
+            .iter()
+            .position(|t| t.eq_ignore_ascii_case("TABLE"))?;
+        let name = tokens
+            .iter()
+            .skip(position + 1)
+            .find(|t| !["IF", "NOT", "EXISTS"].contains(&t.to_ascii_uppercase().as_str()))?;
+        let name = name
+            .split('(')
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches([',', ';']);
+        if name.is_empty() {
+            return None;
+        }
+
+        let parts: Vec<String> = name.split('.').map(|p| p.replace('`', "")).collect();
+        match parts.as_slice() {
+            [table] => Some((self.database.clone(), table.clone())),
+            [database, table] => Some((database.clone(), table.clone())),
+            _ => None,
+        }
+    }
+
+    fn tokens(&self) -> Vec<&str> {
+        match skip_leading_comments(&self.ddl) {
+            Some(ddl) => ddl.split_whitespace().collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Skips the leading whitespace and comments of a statement, e.g.
+/// /* ApplicationName=... */ CREATE TABLE ..., None if a comment is not closed.
+pub(crate) fn skip_leading_comments(sql: &str) -> Option<&str> {
+    let mut sql = sql.trim_start();
+    while let Some(rest) = sql.strip_prefix("/*") {
+        let end = rest.find("*/")?;
+        sql = rest[end + 2..].trim_start();
+    }
+    Some(sql)
+}
+
+const DDL_OBJECT_TYPES: [&str; 11] = [
+    "TABLE",
+    "DATABASE",
+    "SCHEMA",
+    "INDEX",
+    "VIEW",
+    "TRIGGER",
+    "PROCEDURE",
+    "FUNCTION",
+    "EVENT",
+    "USER",
+    "TABLESPACE",
+];
+
+impl ChangeEvent {
+    pub fn source(&self) -> &ChangeSource {
+        match self {
+            Self::Begin(source) => source,
+            Self::Row(change) => &change.source,
+            Self::Schema(change) => &change.source,
+            Self::Commit { source, .. } => source,
+            Self::Rollback(source) => source,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema_change(ddl: &str) -> SchemaChange {
+        SchemaChange {
+            source: ChangeSource::default(),
+            database: "db_1".into(),
+            ddl: ddl.into(),
+        }
+    }
+
+    #[test]
+    fn test_schema_change_table() {
+        let change = schema_change("/* app */ create temporary table `tb_1`(id int)");
+        assert_eq!(
+            change.statement_type(),
+            ("CREATE".to_string(), "TABLE".to_string())
+        );
+        assert_eq!(change.table(), Some(("db_1".into(), "tb_1".into())));
+
+        let change = schema_change("ALTER TABLE db_2.tb_2 ADD COLUMN c INT");
+        assert_eq!(change.table(), Some(("db_2".into(), "tb_2".into())));
+
+        let change = schema_change("CREATE UNIQUE INDEX idx ON tb_1 (c)");
+        assert_eq!(
+            change.statement_type(),
+            ("CREATE".to_string(), "INDEX".to_string())
+        );
+        assert_eq!(change.table(), None);
+    }
+}
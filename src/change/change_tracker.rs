@@ -0,0 +1,434 @@
+use std::{collections::HashMap, sync::Arc};
+
+use log::warn;
+
+use crate::event::{
+    event_data::EventData, event_header::EventHeader, row_event::RowEvent,
+    table_map_event::TableMapEvent,
+};
+
+use super::change_event::{
+    skip_leading_comments, ChangeEvent, ChangeSource, RowChange, RowOperation, SchemaChange,
+};
+
+/// Turns parsed binlog events into ChangeEvents, keeping track of the binlog position,
+/// the current transaction (GTID, thread id, rows query) and the table map events.
+#[derive(Debug, Clone, Default)]
+pub struct ChangeTracker {
+    pub binlog_filename: String,
+    table_map_event_by_table_id: HashMap<u64, Arc<TableMapEvent>>,
+    gtid: Option<String>,
+    thread_id: Option<u32>,
+    query: Option<String>,
+}
+
+impl ChangeTracker {
+    pub fn new(binlog_filename: &str) -> Self {
+        Self {
+            binlog_filename: binlog_filename.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Handles a binlog event and returns the resulting ChangeEvents, which may be empty.
+    pub fn handle(&mut self, header: &EventHeader, data: EventData) -> Vec<ChangeEvent> {
+        let mut changes = Vec::new();
+        self.handle_event(header, data, &mut changes);
+        changes
+    }
+
+    fn handle_event(
+        &mut self,
+        header: &EventHeader,
+        data: EventData,
+        changes: &mut Vec<ChangeEvent>,
+    ) {
+        match data {
+            EventData::Rotate(event) => {
+                self.binlog_filename = event.binlog_filename;
+            }
+
+            EventData::Gtid(event) => {
+                self.gtid = Some(event.gtid);
+            }
+
+            EventData::Query(event) => match QueryKind::of(&event.query) {
+                QueryKind::Begin => {
+                    self.thread_id = Some(event.thread_id);
+                    changes.push(ChangeEvent::Begin(self.source(header)));
+                }
+                QueryKind::Commit => {
+                    changes.push(ChangeEvent::Commit {
+                        source: self.source(header),
+                        xid: None,
+                    });
+                    self.end_transaction();
+                }
+                QueryKind::Rollback => {
+                    changes.push(ChangeEvent::Rollback(self.source(header)));
+                    self.end_transaction();
+                }
+                QueryKind::Ddl => {
+                    self.thread_id = Some(event.thread_id);
+                    changes.push(ChangeEvent::Schema(SchemaChange {
+                        source: self.source(header),
+                        database: event.schema,
+                        ddl: event.query,
+                    }));
+                    // a DDL is a transaction by itself
+                    self.end_transaction();
+                }
+                QueryKind::XaStart | QueryKind::Other => {}
+            },
+
+            EventData::RowsQuery(event) => {
+                self.query = Some(event.query);
+            }
+
+            EventData::TableMap(event) => {
+                self.table_map_event_by_table_id
+                    .insert(event.table_id, Arc::new(event));
+            }
+
+            EventData::WriteRows(event) => {
+                for (i, row) in event.rows.into_iter().enumerate() {
+                    self.push_row_change(
+                        header,
+                        event.table_id,
+                        RowOperation::Insert,
+                        i,
+                        (None, &[]),
+                        (Some(row), &event.included_columns),
+                        changes,
+                    );
+                }
+            }
+
+            EventData::UpdateRows(event) => {
+                for (i, (before, after)) in event.rows.into_iter().enumerate() {
+                    self.push_row_change(
+                        header,
+                        event.table_id,
+                        RowOperation::Update,
+                        i,
+                        (Some(before), &event.included_columns_before),
+                        (Some(after), &event.included_columns_after),
+                        changes,
+                    );
+                }
+            }
+
+            EventData::DeleteRows(event) => {
+                for (i, row) in event.rows.into_iter().enumerate() {
+                    self.push_row_change(
+                        header,
+                        event.table_id,
+                        RowOperation::Delete,
+                        i,
+                        (Some(row), &event.included_columns),
+                        (None, &[]),
+                        changes,
+                    );
+                }
+            }
+
+            EventData::Xid(event) => {
+                changes.push(ChangeEvent::Commit {
+                    source: self.source(header),
+                    xid: Some(event.xid),
+                });
+                self.end_transaction();
+            }
+
+            EventData::TransactionPayload(event) => {
+                // events in a compressed transaction have no position of their own,
+                // use the position of the payload event
+                for (_, data) in event.uncompressed_events {
+                    self.handle_event(header, data, changes);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push_row_change(
+        &self,
+        header: &EventHeader,
+        table_id: u64,
+        operation: RowOperation,
+        row_index: usize,
+        (before, included_columns_before): (Option<RowEvent>, &[bool]),
+        (after, included_columns_after): (Option<RowEvent>, &[bool]),
+        changes: &mut Vec<ChangeEvent>,
+    ) {
+        // the parser fails on rows events without a table map event,
+        // so a missing one here can only be caused by events handled out of order
+        let source = self.source(header);
+        let Some(table_map_event) = self.table_map_event_by_table_id.get(&table_id) else {
+            warn!(
+                "skipping a row of table id {} without table map event at {}:{}",
+                table_id, source.binlog_filename, source.binlog_position
+            );
+            return;
+        };
+        changes.push(ChangeEvent::Row(RowChange {
+            operation,
+            source,
+            row_index,
+            table_map_event: table_map_event.clone(),
+            before,
+            after,
+            included_columns_before: included_columns_before.to_vec(),
+            included_columns_after: included_columns_after.to_vec(),
+        }));
+    }
+
+    fn source(&self, header: &EventHeader) -> ChangeSource {
+        let next_binlog_position = header.next_event_position as u64;
+        ChangeSource {
+            server_id: header.server_id,
+            timestamp: header.timestamp,
+            binlog_filename: self.binlog_filename.clone(),
+            binlog_position: next_binlog_position.saturating_sub(header.event_length as u64),
+            next_binlog_position,
+            gtid: self.gtid.clone(),
+            thread_id: self.thread_id,
+            query: self.query.clone(),
+        }
+    }
+
+    fn end_transaction(&mut self) {
+        self.gtid = None;
+        self.thread_id = None;
+        self.query = None;
+    }
+}
+
+/// Kind of a QueryEvent by its leading keywords, which decides the transaction boundaries
+/// of the ChangeTracker, the BinlogBoundedReader and the BinlogOffsetIndex.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum QueryKind {
+    Begin,
+    /// XA START, begins an XA transaction which ends with a XaPrepareEvent.
+    XaStart,
+    Commit,
+    Rollback,
+    Ddl,
+    Other,
+}
+
+impl QueryKind {
+    pub(crate) fn of(query: &str) -> Self {
+        let Some(query) = skip_leading_comments(query) else {
+            return Self::Other;
+        };
+        let (keyword, rest) = Self::split_keyword(query);
+        match keyword.to_ascii_uppercase().as_str() {
+            "BEGIN" => Self::Begin,
+            "COMMIT" => Self::Commit,
+            // ROLLBACK TO SAVEPOINT does not end the transaction
+            "ROLLBACK" if !Self::split_keyword(rest).0.eq_ignore_ascii_case("TO") => Self::Rollback,
+            // the second phase of an XA transaction, logged as a transaction by itself
+            "XA" => match Self::split_keyword(rest).0.to_ascii_uppercase().as_str() {
+                "COMMIT" => Self::Commit,
+                "ROLLBACK" => Self::Rollback,
+                "START" | "BEGIN" => Self::XaStart,
+                _ => Self::Other,
+            },
+            "CREATE" | "ALTER" | "DROP" | "RENAME" | "TRUNCATE" => Self::Ddl,
+            _ => Self::Other,
+        }
+    }
+
+    /// Splits the leading keyword of a query from the rest.
+    fn split_keyword(query: &str) -> (&str, &str) {
+        let query = query.trim_start();
+        let end = query
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(query.len());
+        query.split_at(end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        change::transaction::TransactionBuffer,
+        column::column_value::ColumnValue,
+        event::{
+            gtid_event::GtidEvent, query_event::QueryEvent, rows_query_event::RowsQueryEvent,
+            write_rows_event::WriteRowsEvent, xid_event::XidEvent,
+        },
+    };
+
+    const GTID: &str = "3e11fa47-71ca-11e1-9e33-c80aa9429562:23";
+
+    fn header(event_length: u32, next_event_position: u32) -> EventHeader {
+        EventHeader {
+            timestamp: 1700000000,
+            event_type: 0,
+            server_id: 1,
+            event_length,
+            next_event_position,
+            event_flags: 0,
+        }
+    }
+
+    fn query_event(query: &str) -> EventData {
+        EventData::Query(QueryEvent {
+            thread_id: 7,
+            exec_time: 0,
+            error_code: 0,
+            status_vars: Vec::new(),
+            schema: "db_1".into(),
+            query: query.into(),
+        })
+    }
+
+    fn write_rows_event() -> WriteRowsEvent {
+        WriteRowsEvent {
+            table_id: 100,
+            flags: 1,
+            extra_data: Vec::new(),
+            included_columns: vec![true],
+            rows: vec![
+                RowEvent {
+                    column_values: vec![ColumnValue::Long(1)],
+                },
+                RowEvent {
+                    column_values: vec![ColumnValue::Long(2)],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_query_kind() {
+        assert_eq!(QueryKind::of("BEGIN"), QueryKind::Begin);
+        assert_eq!(QueryKind::of("COMMIT"), QueryKind::Commit);
+        assert_eq!(QueryKind::of("create table t(id int)"), QueryKind::Ddl);
+        assert_eq!(
+            QueryKind::of("/* app */ ALTER TABLE t ADD c INT"),
+            QueryKind::Ddl
+        );
+        assert_eq!(QueryKind::of("INSERT INTO t VALUES (1)"), QueryKind::Other);
+        assert_eq!(QueryKind::of("/* unclosed"), QueryKind::Other);
+        assert_eq!(QueryKind::of("ROLLBACK"), QueryKind::Rollback);
+        assert_eq!(QueryKind::of("ROLLBACK TO sp_1"), QueryKind::Other);
+        assert_eq!(QueryKind::of("XA COMMIT X'31',X'',1"), QueryKind::Commit);
+        assert_eq!(
+            QueryKind::of("xa rollback X'31',X'',1"),
+            QueryKind::Rollback
+        );
+        assert_eq!(QueryKind::of("XA START X'31',X'',1"), QueryKind::XaStart);
+        assert_eq!(QueryKind::of("XA END X'31',X'',1"), QueryKind::Other);
+    }
+
+    #[test]
+    fn test_rollback() {
+        let mut tracker = ChangeTracker::new("mysql-bin.000001");
+        let mut buffer = TransactionBuffer::new();
+
+        for query in [
+            "ROLLBACK",
+            "XA ROLLBACK X'31',X'',1",
+            "XA COMMIT X'31',X'',1",
+        ] {
+            let gtid_event = GtidEvent {
+                gtid: GTID.into(),
+                ..Default::default()
+            };
+            tracker.handle(&header(60, 200), EventData::Gtid(gtid_event));
+            let changes = tracker.handle(&header(60, 260), query_event("BEGIN"));
+            assert!(buffer.push(changes[0].clone()).is_none());
+            let rows_query_event = RowsQueryEvent {
+                query: "INSERT INTO tb_1 VALUES (1)".into(),
+            };
+            tracker.handle(&header(40, 300), EventData::RowsQuery(rows_query_event));
+            let changes = tracker.handle(&header(60, 360), query_event(query));
+            assert_eq!(changes.len(), 1);
+            let source = changes[0].source();
+            assert_eq!(source.gtid.as_deref(), Some(GTID));
+            assert_eq!(source.thread_id, Some(7));
+            assert!(source.query.is_some());
+            let rolled_back = matches!(changes[0], ChangeEvent::Rollback(_));
+            assert_eq!(rolled_back, !query.starts_with("XA COMMIT"));
+
+            let transaction = buffer.push(changes[0].clone()).unwrap();
+            assert_eq!(transaction.rolled_back, rolled_back);
+            assert_eq!(transaction.begin.unwrap().binlog_position, 200);
+            assert_eq!(transaction.commit.binlog_position, 300);
+            assert!(buffer.is_empty());
+
+            // nothing of the transaction is carried over
+            let changes = tracker.handle(&header(31, 391), EventData::Xid(XidEvent { xid: 1 }));
+            let source = changes[0].source();
+            assert!(source.gtid.is_none() && source.thread_id.is_none() && source.query.is_none());
+        }
+    }
+
+    #[test]
+    fn test_missing_table_map() {
+        let mut tracker = ChangeTracker::new("mysql-bin.000001");
+        let changes = tracker.handle(&header(60, 300), EventData::WriteRows(write_rows_event()));
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_transaction() {
+        let mut tracker = ChangeTracker::new("mysql-bin.000001");
+        let table_map_event = TableMapEvent {
+            table_id: 100,
+            flags: 1,
+            database_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            column_types: vec![3],
+            column_metas: vec![0],
+            null_bits: vec![false],
+            table_metadata: None,
+        };
+
+        let changes = tracker.handle(&header(60, 200), query_event("BEGIN"));
+        assert!(matches!(&changes[..], [ChangeEvent::Begin(source)]
+            if source.binlog_position == 140 && source.thread_id == Some(7)));
+
+        tracker.handle(&header(40, 240), EventData::TableMap(table_map_event));
+        let changes = tracker.handle(&header(60, 300), EventData::WriteRows(write_rows_event()));
+        assert_eq!(changes.len(), 2);
+        match &changes[1] {
+            ChangeEvent::Row(change) => {
+                assert_eq!(change.operation, RowOperation::Insert);
+                assert_eq!(change.row_index, 1);
+                assert_eq!(change.table_name(), "tb_1");
+                assert_eq!(change.source.binlog_filename, "mysql-bin.000001");
+                assert_eq!(change.source.binlog_position, 240);
+                assert_eq!(change.source.thread_id, Some(7));
+                assert!(change.before.is_none());
+                assert_eq!(
+                    change.key_row().unwrap().columns[0].value,
+                    &ColumnValue::Long(2)
+                );
+            }
+            _ => panic!("expected a row change"),
+        }
+
+        let changes = tracker.handle(&header(31, 331), EventData::Xid(XidEvent { xid: 9 }));
+        assert!(matches!(
+            &changes[..],
+            [ChangeEvent::Commit { xid: Some(9), .. }]
+        ));
+
+        let changes = tracker.handle(&header(80, 411), query_event("DROP TABLE tb_1"));
+        match &changes[..] {
+            [ChangeEvent::Schema(change)] => {
+                assert_eq!(change.database, "db_1");
+                assert_eq!(change.ddl, "DROP TABLE tb_1");
+                assert_eq!(change.source.binlog_position, 331);
+            }
+            _ => panic!("expected a schema change"),
+        }
+    }
+}
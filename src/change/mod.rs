@@ -0,0 +1,3 @@
+pub mod change_event;
+pub mod change_tracker;
+pub mod transaction;
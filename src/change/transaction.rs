@@ -0,0 +1,82 @@
+use super::change_event::{ChangeEvent, ChangeSource};
+
+/// The changes of a committed transaction, a DDL is a transaction with a single SchemaChange.
+#[derive(Debug, Clone)]
+pub struct Transaction {
+    /// Source of the BEGIN event, None for DDLs.
+    pub begin: Option<ChangeSource>,
+    /// Row and schema changes in the transaction.
+    pub changes: Vec<ChangeEvent>,
+    /// Source of the event that ends the transaction.
+    pub commit: ChangeSource,
+    pub xid: Option<u64>,
+    /// True if the transaction ended with a ROLLBACK, MySQL only logs the changes of
+    /// non-transactional tables in such a transaction, they are not rolled back.
+    pub rolled_back: bool,
+}
+
+/// Buffers ChangeEvents until the end of each transaction, some formats need to know
+/// the whole transaction, e.g. Maxwell marks the last row with commit: true.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionBuffer {
+    begin: Option<ChangeSource>,
+    changes: Vec<ChangeEvent>,
+}
+
+impl TransactionBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pushes a ChangeEvent, returns the transaction if the event completes one.
+    pub fn push(&mut self, event: ChangeEvent) -> Option<Transaction> {
+        match event {
+            ChangeEvent::Begin(source) => {
+                self.begin = Some(source);
+                self.changes.clear();
+                None
+            }
+
+            ChangeEvent::Row(_) => {
+                self.changes.push(event);
+                None
+            }
+
+            ChangeEvent::Schema(ref change) => {
+                if self.begin.is_some() {
+                    // DDLs cause implicit commits, should never be inside a transaction
+                    self.changes.push(event);
+                    return None;
+                }
+                Some(Transaction {
+                    begin: None,
+                    commit: change.source.clone(),
+                    changes: vec![event],
+                    xid: None,
+                    rolled_back: false,
+                })
+            }
+
+            ChangeEvent::Commit { source, xid } => Some(Transaction {
+                begin: self.begin.take(),
+                changes: std::mem::take(&mut self.changes),
+                commit: source,
+                xid,
+                rolled_back: false,
+            }),
+
+            ChangeEvent::Rollback(source) => Some(Transaction {
+                begin: self.begin.take(),
+                changes: std::mem::take(&mut self.changes),
+                commit: source,
+                xid: None,
+                rolled_back: true,
+            }),
+        }
+    }
+
+    /// Whether there are buffered changes of an uncommitted transaction.
+    pub fn is_empty(&self) -> bool {
+        self.begin.is_none() && self.changes.is_empty()
+    }
+}
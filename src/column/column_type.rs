@@ -6,7 +6,9 @@ use crate::binlog_error::BinlogError;
 
 /// Refer to: https://dev.mysql.com/doc/dev/mysql-server/8.0.33/classbinary__log_1_1Table__map__event.html
 /// Refer to: https://github.com/mysql/mysql-server/blob/trunk/include/mysql.h.pp
-#[derive(Debug, Deserialize, Serialize, Clone, IntoPrimitive, TryFromPrimitive)]
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, IntoPrimitive, TryFromPrimitive,
+)]
 #[repr(i16)]
 pub enum ColumnType {
     #[num_enum(default)]
@@ -32,6 +34,8 @@ pub enum ColumnType {
     TimeStamp2 = 17,
     DateTime2 = 18,
     Time2 = 19,
+    // Introduced in MySQL 9.0.
+    Vector = 242,
     Json = 245,
     NewDecimal = 246,
     // This enumeration value is only used internally and cannot exist in a binlog.
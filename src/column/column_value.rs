@@ -1,8 +1,8 @@
-use super::column_type::ColumnType;
+use super::{column_type::ColumnType, column_value_ref::ColumnValueRef};
 use crate::{binlog_error::BinlogError, ext::cursor_ext::CursorExt};
-use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
 use serde::{Deserialize, Serialize};
-use std::io::{Cursor, Read};
+use std::io::{Cursor, Read, Write};
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub enum ColumnValue {
@@ -53,6 +53,9 @@ pub enum ColumnValue {
     // An ENUM column can have a maximum of 65,535 distinct elements.
     Enum(u32),
     Json(Vec<u8>),
+    // A vector of 32 bit floating point numbers, introduced in MySQL 9.0
+    // refer: https://dev.mysql.com/doc/refman/9.0/en/vector.html
+    Vector(Vec<f32>),
 }
 
 const DIG_PER_DEC: usize = 9;
@@ -66,84 +69,15 @@ impl ColumnValue {
         column_meta: u16,
         column_length: u16,
     ) -> Result<Self, BinlogError> {
-        let value = match column_type {
-            ColumnType::Bit => ColumnValue::Bit(Self::parse_bit(cursor, column_meta)?),
-
-            ColumnType::Tiny => ColumnValue::Tiny(cursor.read_i8()?),
-
-            ColumnType::Short => ColumnValue::Short(cursor.read_i16::<LittleEndian>()?),
-
-            ColumnType::Int24 => ColumnValue::Long(cursor.read_i24::<LittleEndian>()?),
-
-            ColumnType::Long => ColumnValue::Long(cursor.read_i32::<LittleEndian>()?),
-
-            ColumnType::LongLong => ColumnValue::LongLong(cursor.read_i64::<LittleEndian>()?),
-
-            ColumnType::Float => ColumnValue::Float(cursor.read_f32::<LittleEndian>()?),
-
-            ColumnType::Double => ColumnValue::Double(cursor.read_f64::<LittleEndian>()?),
-
-            ColumnType::NewDecimal => {
-                let precision = (column_meta & 0xFF) as usize;
-                let scale = (column_meta >> 8) as usize;
-                ColumnValue::Decimal(Self::parse_decimal(cursor, precision, scale)?)
-            }
-
-            ColumnType::Date => ColumnValue::Date(Self::parse_date(cursor)?),
-
-            ColumnType::Time => ColumnValue::Time(Self::parse_time(cursor)?),
-
-            ColumnType::Time2 => ColumnValue::Time(Self::parse_time2(cursor, column_meta)?),
-
-            ColumnType::TimeStamp => ColumnValue::Timestamp(Self::parse_timestamp(cursor)?),
-
-            ColumnType::TimeStamp2 => {
-                ColumnValue::Timestamp(Self::parse_timestamp2(cursor, column_meta)?)
-            }
-
-            ColumnType::DateTime => ColumnValue::DateTime(Self::parse_datetime(cursor)?),
-
-            ColumnType::DateTime2 => {
-                ColumnValue::DateTime(Self::parse_datetime2(cursor, column_meta)?)
-            }
-
-            ColumnType::Year => ColumnValue::Year(cursor.read_u8()? as u16 + 1900),
-
-            ColumnType::VarChar | ColumnType::VarString => {
-                ColumnValue::String(Self::parse_string(cursor, column_meta)?)
-            }
-
-            ColumnType::String => ColumnValue::String(Self::parse_string(cursor, column_length)?),
-
-            ColumnType::Blob
-            | ColumnType::Geometry
-            | ColumnType::TinyBlob
-            | ColumnType::MediumBlob
-            | ColumnType::LongBlob => ColumnValue::Blob(Self::parse_blob(cursor, column_meta)?),
-
-            ColumnType::Enum => {
-                ColumnValue::Enum(cursor.read_int::<LittleEndian>(column_length as usize)? as u32)
-            }
-
-            ColumnType::Set => {
-                ColumnValue::Set(cursor.read_int::<LittleEndian>(column_length as usize)? as u64)
-            }
-
-            ColumnType::Json => ColumnValue::Json(Self::parse_blob(cursor, column_meta)?),
-
-            _ => {
-                return Err(BinlogError::UnsupportedColumnType(format!(
-                    "{:?}",
-                    column_type
-                )))
-            }
-        };
-
-        Ok(value)
+        let value = ColumnValueRef::parse(cursor, column_type, column_meta, column_length)?;
+        Ok(value.into())
     }
 
     #[allow(clippy::needless_range_loop)]
-    fn parse_bit(cursor: &mut Cursor<&Vec<u8>>, column_meta: u16) -> Result<u64, BinlogError> {
+    pub(crate) fn parse_bit<T: AsRef<[u8]>>(
+        cursor: &mut Cursor<T>,
+        column_meta: u16,
+    ) -> Result<u64, BinlogError> {
         let bit_count = (column_meta >> 8) * 8 + (column_meta & 0xFF);
         let bytes = cursor.read_bits_as_bytes(bit_count as usize, true)?;
         let mut result = 0u64;
@@ -153,7 +87,9 @@ impl ColumnValue {
         Ok(result)
     }
 
-    fn parse_date(cursor: &mut Cursor<&Vec<u8>>) -> Result<String, BinlogError> {
+    pub(crate) fn parse_date<T: AsRef<[u8]>>(
+        cursor: &mut Cursor<T>,
+    ) -> Result<String, BinlogError> {
         // Stored as a 3 byte value where bits 1 to 5 store the day,
         // bits 6 to 9 store the month and the remaining bits store the year.
         let date_val = cursor.read_u24::<LittleEndian>()?;
@@ -163,7 +99,9 @@ impl ColumnValue {
         Ok(format!("{}-{:02}-{:02}", year, month, day))
     }
 
-    fn parse_time(cursor: &mut Cursor<&Vec<u8>>) -> Result<String, BinlogError> {
+    pub(crate) fn parse_time<T: AsRef<[u8]>>(
+        cursor: &mut Cursor<T>,
+    ) -> Result<String, BinlogError> {
         // refer: https://dev.mysql.com/doc/refman/8.0/en/time.html
         let time_val = cursor.read_u24::<LittleEndian>()?;
         let hour = (time_val / 100) / 100;
@@ -172,7 +110,10 @@ impl ColumnValue {
         Ok(format!("{:02}:{:02}:{:02}", hour, minute, second))
     }
 
-    fn parse_time2(cursor: &mut Cursor<&Vec<u8>>, column_meta: u16) -> Result<String, BinlogError> {
+    pub(crate) fn parse_time2<T: AsRef<[u8]>>(
+        cursor: &mut Cursor<T>,
+        column_meta: u16,
+    ) -> Result<String, BinlogError> {
         // (in big endian)
 
         // 1 bit sign (1= non-negative, 0= negative)
@@ -187,7 +128,7 @@ impl ColumnValue {
 
         // refer to: https://github.com/debezium/debezium/blob/main/debezium-connector-binlog/src/main/java/io/debezium/connector/binlog/event/RowDeserializers.java#L341
 
-        let fraction_bytes = ((column_meta + 1) / 2) as usize;
+        let fraction_bytes = column_meta.div_ceil(2) as usize;
         let payload_bytes = 3 + fraction_bytes;
         let payload_bits = payload_bytes * 8;
 
@@ -221,9 +162,12 @@ impl ColumnValue {
         }
     }
 
-    fn parse_fraction(cursor: &mut Cursor<&Vec<u8>>, column_meta: u16) -> Result<u32, BinlogError> {
+    fn parse_fraction<T: AsRef<[u8]>>(
+        cursor: &mut Cursor<T>,
+        column_meta: u16,
+    ) -> Result<u32, BinlogError> {
         let mut fraction = 0;
-        let length = ((column_meta + 1) / 2) as u32;
+        let length = column_meta.div_ceil(2) as u32;
         if length > 0 {
             fraction = cursor.read_uint::<BigEndian>(length as usize)?;
             fraction *= u64::pow(100, 3 - length);
@@ -231,13 +175,15 @@ impl ColumnValue {
         Ok(fraction as u32)
     }
 
-    fn parse_timestamp(cursor: &mut Cursor<&Vec<u8>>) -> Result<i64, BinlogError> {
+    pub(crate) fn parse_timestamp<T: AsRef<[u8]>>(
+        cursor: &mut Cursor<T>,
+    ) -> Result<i64, BinlogError> {
         // Stored as a 4 byte UNIX timestamp (number of seconds since 00:00, Jan 1 1970 UTC).
         Ok((cursor.read_u32::<LittleEndian>()?) as i64 * 1000000)
     }
 
-    fn parse_timestamp2(
-        cursor: &mut Cursor<&Vec<u8>>,
+    pub(crate) fn parse_timestamp2<T: AsRef<[u8]>>(
+        cursor: &mut Cursor<T>,
         column_meta: u16,
     ) -> Result<i64, BinlogError> {
         let second = cursor.read_u32::<BigEndian>()?;
@@ -245,7 +191,9 @@ impl ColumnValue {
         Ok(1000000 * second as i64 + micros as i64)
     }
 
-    fn parse_datetime(cursor: &mut Cursor<&Vec<u8>>) -> Result<String, BinlogError> {
+    pub(crate) fn parse_datetime<T: AsRef<[u8]>>(
+        cursor: &mut Cursor<T>,
+    ) -> Result<String, BinlogError> {
         let datetime_val = cursor.read_u64::<LittleEndian>()? * 1000;
         let date_val = datetime_val / 1000000;
         let time_val = datetime_val % 1000000;
@@ -261,8 +209,8 @@ impl ColumnValue {
         ))
     }
 
-    fn parse_datetime2(
-        cursor: &mut Cursor<&Vec<u8>>,
+    pub(crate) fn parse_datetime2<T: AsRef<[u8]>>(
+        cursor: &mut Cursor<T>,
         column_meta: u16,
     ) -> Result<String, BinlogError> {
         // Stored as 4-byte value,
@@ -285,27 +233,9 @@ impl ColumnValue {
         ))
     }
 
-    fn parse_string(
-        cursor: &mut Cursor<&Vec<u8>>,
-        column_meta: u16,
-    ) -> Result<Vec<u8>, BinlogError> {
-        let size = if column_meta < 256 {
-            cursor.read_u8()? as usize
-        } else {
-            cursor.read_u16::<LittleEndian>()? as usize
-        };
-        // charset is not present in the binary log, return byte[] instead of an actual String
-        cursor.read_bytes(size)
-    }
-
-    fn parse_blob(cursor: &mut Cursor<&Vec<u8>>, column_meta: u16) -> Result<Vec<u8>, BinlogError> {
-        let size = cursor.read_uint::<LittleEndian>(column_meta as usize)? as usize;
-        cursor.read_bytes(size)
-    }
-
     #[allow(clippy::needless_range_loop)]
-    pub fn parse_decimal(
-        cursor: &mut Cursor<&Vec<u8>>,
+    pub fn parse_decimal<T: AsRef<[u8]>>(
+        cursor: &mut Cursor<T>,
         precision: usize,
         scale: usize,
     ) -> Result<String, BinlogError> {
@@ -334,7 +264,7 @@ impl ColumnValue {
         let comp_frac_bytes = COMPRESSED_BYTES[comp_frac];
         let comp_intg_bytes = COMPRESSED_BYTES[comp_intg];
 
-        let total_bytes = 4 * uncomp_intg + 4 * uncomp_frac + comp_frac_bytes + comp_intg_bytes;
+        let total_bytes = Self::decimal_length(precision, scale);
         let mut buf = vec![0u8; total_bytes];
         cursor.read_exact(&mut buf)?;
 
@@ -401,7 +331,430 @@ impl ColumnValue {
         }
     }
 
+    /// A VECTOR value is stored as consecutive 4 byte little endian floats.
+    pub(crate) fn parse_vector(bytes: &[u8]) -> Result<Vec<f32>, BinlogError> {
+        if !bytes.len().is_multiple_of(4) {
+            return Err(BinlogError::UnexpectedData(format!(
+                "invalid vector length: {}",
+                bytes.len()
+            )));
+        }
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect())
+    }
+
+    /// Gets the number of bytes used to store a DECIMAL(precision, scale) in binlog.
+    pub(crate) fn decimal_length(precision: usize, scale: usize) -> usize {
+        let integral = precision - scale;
+        let uncomp_intg = integral / DIG_PER_DEC;
+        let uncomp_frac = scale / DIG_PER_DEC;
+        let comp_intg = integral - (uncomp_intg * DIG_PER_DEC);
+        let comp_frac = scale - (uncomp_frac * DIG_PER_DEC);
+        4 * uncomp_intg
+            + 4 * uncomp_frac
+            + COMPRESSED_BYTES[comp_frac]
+            + COMPRESSED_BYTES[comp_intg]
+    }
+
+    /// Writes the value in the binlog format of the column, the reverse of parse.
+    pub fn encode(
+        &self,
+        buf: &mut Vec<u8>,
+        column_type: ColumnType,
+        column_meta: u16,
+        column_length: u16,
+    ) -> Result<(), BinlogError> {
+        match (column_type, self) {
+            (ColumnType::Bit, Self::Bit(v)) => {
+                let bit_count = (column_meta >> 8) * 8 + (column_meta & 0xFF);
+                let byte_count = bit_count.div_ceil(8) as usize;
+                for i in (0..byte_count).rev() {
+                    buf.write_u8((v >> (i * 8)) as u8)?;
+                }
+            }
+
+            (ColumnType::Tiny, Self::Tiny(v)) => buf.write_i8(*v)?,
+
+            (ColumnType::Short, Self::Short(v)) => buf.write_i16::<LittleEndian>(*v)?,
+
+            (ColumnType::Int24, Self::Long(v)) => buf.write_i24::<LittleEndian>(*v)?,
+
+            (ColumnType::Long, Self::Long(v)) => buf.write_i32::<LittleEndian>(*v)?,
+
+            (ColumnType::LongLong, Self::LongLong(v)) => buf.write_i64::<LittleEndian>(*v)?,
+
+            (ColumnType::Float, Self::Float(v)) => buf.write_f32::<LittleEndian>(*v)?,
+
+            (ColumnType::Double, Self::Double(v)) => buf.write_f64::<LittleEndian>(*v)?,
+
+            (ColumnType::NewDecimal, Self::Decimal(v)) => {
+                let precision = (column_meta & 0xFF) as usize;
+                let scale = (column_meta >> 8) as usize;
+                Self::write_decimal(buf, v, precision, scale)?
+            }
+
+            (ColumnType::Date, Self::Date(v)) => {
+                let (_, parts, _) = Self::split_temporal(v, 3)?;
+                buf.write_u24::<LittleEndian>((parts[0] << 9 | parts[1] << 5 | parts[2]) as u32)?
+            }
+
+            (ColumnType::Time, Self::Time(v)) => {
+                let (_, parts, _) = Self::split_temporal(v, 3)?;
+                buf.write_u24::<LittleEndian>(
+                    (parts[0] * 10000 + parts[1] * 100 + parts[2]) as u32,
+                )?
+            }
+
+            (ColumnType::Time2, Self::Time(v)) => Self::write_time2(buf, v, column_meta)?,
+
+            (ColumnType::TimeStamp, Self::Timestamp(v)) => {
+                buf.write_u32::<LittleEndian>((v / 1000000) as u32)?
+            }
+
+            (ColumnType::TimeStamp2, Self::Timestamp(v)) => {
+                buf.write_u32::<BigEndian>((v / 1000000) as u32)?;
+                Self::write_fraction(buf, (v % 1000000) as u64, column_meta)?
+            }
+
+            (ColumnType::DateTime, Self::DateTime(v)) => {
+                let (_, parts, _) = Self::split_temporal(v, 6)?;
+                let date_val = parts[0] * 10000 + parts[1] * 100 + parts[2];
+                let time_val = parts[3] * 10000 + parts[4] * 100 + parts[5];
+                buf.write_u64::<LittleEndian>(date_val * 1000000 + time_val)?
+            }
+
+            (ColumnType::DateTime2, Self::DateTime(v)) => {
+                let (_, parts, micros) = Self::split_temporal(v, 6)?;
+                let d_val = (parts[0] * 13 + parts[1]) << 5 | parts[2];
+                let t_val = parts[3] << 12 | parts[4] << 6 | parts[5];
+                buf.write_uint::<BigEndian>((d_val << 17 | t_val) + 0x8000000000, 5)?;
+                Self::write_fraction(buf, micros, column_meta)?
+            }
+
+            (ColumnType::Year, Self::Year(v)) => buf.write_u8(v.wrapping_sub(1900) as u8)?,
+
+            (ColumnType::VarChar | ColumnType::VarString, Self::String(v)) => {
+                Self::write_string(buf, v, column_meta)?
+            }
+
+            (ColumnType::String, Self::String(v)) => Self::write_string(buf, v, column_length)?,
+
+            (
+                ColumnType::Blob
+                | ColumnType::Geometry
+                | ColumnType::TinyBlob
+                | ColumnType::MediumBlob
+                | ColumnType::LongBlob,
+                Self::Blob(v),
+            )
+            | (ColumnType::Json, Self::Json(v)) => Self::write_blob(buf, v, column_meta)?,
+
+            (ColumnType::Vector, Self::Vector(v)) => {
+                let bytes: Vec<u8> = v.iter().flat_map(|f| f.to_le_bytes()).collect();
+                Self::write_blob(buf, &bytes, column_meta)?
+            }
+
+            (ColumnType::Enum, Self::Enum(v)) => {
+                buf.write_uint::<LittleEndian>(*v as u64, column_length as usize)?
+            }
+
+            (ColumnType::Set, Self::Set(v)) => {
+                buf.write_uint::<LittleEndian>(*v, column_length as usize)?
+            }
+
+            _ => {
+                return Err(BinlogError::UnexpectedData(format!(
+                    "can not encode {:?} as {:?}",
+                    self, column_type
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// Splits a temporal string like "-838:59:59.000000" or "2024-06-10 06:13:20.123456"
+    /// into (is_negative, [parts], microseconds).
+    fn split_temporal(
+        value: &str,
+        part_count: usize,
+    ) -> Result<(bool, Vec<u64>, u64), BinlogError> {
+        let invalid = || BinlogError::UnexpectedData(format!("invalid temporal value: {}", value));
+        let (is_negative, value) = match value.strip_prefix('-') {
+            Some(value) => (true, value),
+            None => (false, value),
+        };
+        let (value, fraction) = value.split_once('.').unwrap_or((value, ""));
+
+        let parts = value
+            .split(['-', ':', ' '])
+            .map(|part| part.parse::<u64>())
+            .collect::<Result<Vec<u64>, _>>()
+            .map_err(|_| invalid())?;
+        if parts.len() != part_count {
+            return Err(invalid());
+        }
+
+        let mut micros = 0;
+        if !fraction.is_empty() {
+            let fraction = format!("{:0<6.6}", fraction);
+            micros = fraction.parse().map_err(|_| invalid())?;
+        }
+        Ok((is_negative, parts, micros))
+    }
+
+    fn write_time2(buf: &mut Vec<u8>, value: &str, column_meta: u16) -> Result<(), BinlogError> {
+        let (is_negative, parts, micros) = Self::split_temporal(value, 3)?;
+        let fraction_bytes = column_meta.div_ceil(2) as usize;
+        let payload_bytes = 3 + fraction_bytes;
+        let payload_bits = payload_bytes * 8;
+
+        let mut fraction = 0;
+        if fraction_bytes > 0 {
+            fraction = micros * u64::pow(100, fraction_bytes as u32 - 1) / 10_000;
+        }
+        let int_part = parts[0] << 12 | parts[1] << 6 | parts[2];
+        let time = 1 << (payload_bits - 1) | int_part << (fraction_bytes * 8) | fraction;
+        // negative values are stored as the two's complement, with the sign bit cleared
+        let time = if is_negative {
+            (1u64 << payload_bits).wrapping_sub(time)
+        } else {
+            time
+        };
+        buf.write_uint::<BigEndian>(time, payload_bytes)?;
+        Ok(())
+    }
+
+    fn write_fraction(buf: &mut Vec<u8>, micros: u64, column_meta: u16) -> Result<(), BinlogError> {
+        let length = column_meta.div_ceil(2) as u32;
+        if length > 0 {
+            let fraction = micros / u64::pow(100, 3 - length);
+            buf.write_uint::<BigEndian>(fraction, length as usize)?;
+        }
+        Ok(())
+    }
+
+    fn write_decimal(
+        buf: &mut Vec<u8>,
+        value: &str,
+        precision: usize,
+        scale: usize,
+    ) -> Result<(), BinlogError> {
+        let invalid = || BinlogError::UnexpectedData(format!("invalid decimal value: {}", value));
+        let integral = precision - scale;
+        let (is_negative, value) = match value.strip_prefix('-') {
+            Some(value) => (true, value),
+            None => (false, value),
+        };
+        let (intg_str, frac_str) = value.split_once('.').unwrap_or((value, ""));
+        let intg_str = intg_str.trim_start_matches('0');
+        if intg_str.len() > integral
+            || frac_str.len() > scale
+            || !intg_str
+                .bytes()
+                .chain(frac_str.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        // pad to the full digits of the integral and fractional parts
+        let digits = format!(
+            "{:0>integral$}{:0<scale$}",
+            intg_str,
+            frac_str,
+            integral = integral,
+            scale = scale
+        );
+        let (intg_digits, frac_digits) = digits.split_at(integral);
+
+        let comp_intg = integral % DIG_PER_DEC;
+        let comp_frac = scale % DIG_PER_DEC;
+        let mut bytes = Vec::with_capacity(Self::decimal_length(precision, scale));
+        let mut write_digits = |digits: &str, byte_count: usize| -> Result<(), BinlogError> {
+            if byte_count > 0 {
+                let value: u64 = digits.parse().map_err(|_| invalid())?;
+                bytes.write_uint::<BigEndian>(value, byte_count)?;
+            }
+            Ok(())
+        };
+
+        // compressed integral, uncompressed integral, uncompressed fractional, compressed fractional
+        write_digits(&intg_digits[..comp_intg], COMPRESSED_BYTES[comp_intg])?;
+        for chunk in intg_digits.as_bytes()[comp_intg..].chunks(DIG_PER_DEC) {
+            write_digits(std::str::from_utf8(chunk).unwrap(), 4)?;
+        }
+        let uncomp_frac_length = scale - comp_frac;
+        for chunk in frac_digits.as_bytes()[..uncomp_frac_length].chunks(DIG_PER_DEC) {
+            write_digits(std::str::from_utf8(chunk).unwrap(), 4)?;
+        }
+        write_digits(
+            &frac_digits[uncomp_frac_length..],
+            COMPRESSED_BYTES[comp_frac],
+        )?;
+
+        if is_negative {
+            for byte in bytes.iter_mut() {
+                *byte ^= 0xFF;
+            }
+        }
+        bytes[0] ^= 0x80;
+        buf.write_all(&bytes)?;
+        Ok(())
+    }
+
+    fn write_string(buf: &mut Vec<u8>, value: &[u8], column_meta: u16) -> Result<(), BinlogError> {
+        if column_meta < 256 {
+            buf.write_u8(value.len() as u8)?;
+        } else {
+            buf.write_u16::<LittleEndian>(value.len() as u16)?;
+        }
+        buf.write_all(value)?;
+        Ok(())
+    }
+
+    fn write_blob(buf: &mut Vec<u8>, value: &[u8], column_meta: u16) -> Result<(), BinlogError> {
+        buf.write_uint::<LittleEndian>(value.len() as u64, column_meta as usize)?;
+        buf.write_all(value)?;
+        Ok(())
+    }
+
     fn bit_slice(value: u64, bit_offset: usize, num_bits: usize, payload_size: usize) -> u64 {
         (value >> (payload_size - (bit_offset + num_bits))) & ((1 << num_bits) - 1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trip(
+        value: ColumnValue,
+        column_type: ColumnType,
+        column_meta: u16,
+        column_length: u16,
+    ) {
+        let mut buf = Vec::new();
+        value
+            .encode(&mut buf, column_type, column_meta, column_length)
+            .unwrap();
+        let mut cursor = Cursor::new(&buf);
+        let parsed = ColumnValue::parse(&mut cursor, column_type, column_meta, column_length);
+        assert_eq!(parsed.unwrap(), value);
+        assert_eq!(cursor.available(), 0);
+    }
+
+    #[test]
+    fn test_encode_decimal() {
+        // (precision, scale) in meta: precision | scale << 8
+        for (value, precision, scale) in [
+            ("1234567.8901", 13, 4),
+            ("-1234567.8901", 13, 4),
+            ("0.0000", 13, 4),
+            ("-0.5000", 13, 4),
+            ("123456789012345678.123456789", 27, 9),
+            ("-98765432109876543210987.6543210987654", 47, 13),
+            ("42", 10, 0),
+            ("0.12345", 5, 5),
+        ] {
+            assert_round_trip(
+                ColumnValue::Decimal(value.into()),
+                ColumnType::NewDecimal,
+                precision | scale << 8,
+                0,
+            );
+        }
+
+        let mut buf = Vec::new();
+        let res = ColumnValue::Decimal("123.45".into()).encode(
+            &mut buf,
+            ColumnType::NewDecimal,
+            4 | 2 << 8,
+            0,
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_encode_temporal() {
+        for fsp in 0..=6u16 {
+            let micros = 123456 / 10u32.pow(6 - fsp as u32) * 10u32.pow(6 - fsp as u32);
+            assert_round_trip(
+                ColumnValue::Time(format!("838:59:58.{:06}", micros)),
+                ColumnType::Time2,
+                fsp,
+                0,
+            );
+            assert_round_trip(
+                ColumnValue::Time(format!("-12:34:56.{:06}", micros)),
+                ColumnType::Time2,
+                fsp,
+                0,
+            );
+            assert_round_trip(
+                ColumnValue::DateTime(format!("2024-06-10 06:13:20.{:06}", micros)),
+                ColumnType::DateTime2,
+                fsp,
+                0,
+            );
+            assert_round_trip(
+                ColumnValue::Timestamp(1_718_000_000_000_000 + micros as i64),
+                ColumnType::TimeStamp2,
+                fsp,
+                0,
+            );
+        }
+        assert_round_trip(
+            ColumnValue::Date("2024-06-10".into()),
+            ColumnType::Date,
+            0,
+            0,
+        );
+        assert_round_trip(ColumnValue::Date("0-00-00".into()), ColumnType::Date, 0, 0);
+        assert_round_trip(ColumnValue::Time("12:34:56".into()), ColumnType::Time, 0, 0);
+        assert_round_trip(
+            ColumnValue::Timestamp(1_718_000_000_000_000),
+            ColumnType::TimeStamp,
+            0,
+            0,
+        );
+        assert_round_trip(ColumnValue::Year(2024), ColumnType::Year, 0, 0);
+    }
+
+    #[test]
+    fn test_encode_others() {
+        assert_round_trip(
+            ColumnValue::Bit(0b1_0110_0101),
+            ColumnType::Bit,
+            1 << 8 | 1,
+            0,
+        );
+        assert_round_trip(ColumnValue::Long(-8388608), ColumnType::Int24, 0, 0);
+        assert_round_trip(ColumnValue::Enum(300), ColumnType::Enum, 0, 2);
+        assert_round_trip(ColumnValue::Set(0b101), ColumnType::Set, 0, 1);
+        assert_round_trip(
+            ColumnValue::String(vec![b'a'; 300]),
+            ColumnType::VarChar,
+            1000,
+            0,
+        );
+        assert_round_trip(
+            ColumnValue::String(b"abc".to_vec()),
+            ColumnType::String,
+            0,
+            20,
+        );
+        assert_round_trip(ColumnValue::Blob(vec![0, 1, 2]), ColumnType::Blob, 2, 0);
+        assert_round_trip(
+            ColumnValue::Vector(vec![1.0, -0.5]),
+            ColumnType::Vector,
+            4,
+            0,
+        );
+
+        let mut buf = Vec::new();
+        let res = ColumnValue::Long(1).encode(&mut buf, ColumnType::VarChar, 10, 0);
+        assert!(res.is_err());
+    }
+}
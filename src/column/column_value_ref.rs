@@ -0,0 +1,257 @@
+use std::io::{Cursor, Seek, SeekFrom};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use super::{column_type::ColumnType, column_value::ColumnValue};
+use crate::{binlog_error::BinlogError, ext::cursor_ext::CursorExt};
+
+/// A column value borrowed from the event buffer, the same as ColumnValue except that
+/// String / Blob / Json values are slices of the buffer instead of owned bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValueRef<'a> {
+    None,
+    Tiny(i8),
+    Short(i16),
+    Long(i32),
+    LongLong(i64),
+    Float(f32),
+    Double(f64),
+    Decimal(String),
+    Time(String),
+    Date(String),
+    DateTime(String),
+    Timestamp(i64),
+    Year(u16),
+    String(&'a [u8]),
+    Blob(&'a [u8]),
+    Bit(u64),
+    Set(u64),
+    Enum(u32),
+    Json(&'a [u8]),
+    Vector(Vec<f32>),
+}
+
+impl<'a> ColumnValueRef<'a> {
+    // refer: https://mariadb.com/kb/en/rows_event_v1v2-rows_compressed_event_v1/
+    pub fn parse<B: AsRef<[u8]> + ?Sized>(
+        cursor: &mut Cursor<&'a B>,
+        column_type: ColumnType,
+        column_meta: u16,
+        column_length: u16,
+    ) -> Result<Self, BinlogError> {
+        let value = match column_type {
+            ColumnType::Bit => Self::Bit(ColumnValue::parse_bit(cursor, column_meta)?),
+
+            ColumnType::Tiny => Self::Tiny(cursor.read_i8()?),
+
+            ColumnType::Short => Self::Short(cursor.read_i16::<LittleEndian>()?),
+
+            ColumnType::Int24 => Self::Long(cursor.read_i24::<LittleEndian>()?),
+
+            ColumnType::Long => Self::Long(cursor.read_i32::<LittleEndian>()?),
+
+            ColumnType::LongLong => Self::LongLong(cursor.read_i64::<LittleEndian>()?),
+
+            ColumnType::Float => Self::Float(cursor.read_f32::<LittleEndian>()?),
+
+            ColumnType::Double => Self::Double(cursor.read_f64::<LittleEndian>()?),
+
+            ColumnType::NewDecimal => {
+                let precision = (column_meta & 0xFF) as usize;
+                let scale = (column_meta >> 8) as usize;
+                Self::Decimal(ColumnValue::parse_decimal(cursor, precision, scale)?)
+            }
+
+            ColumnType::Date => Self::Date(ColumnValue::parse_date(cursor)?),
+
+            ColumnType::Time => Self::Time(ColumnValue::parse_time(cursor)?),
+
+            ColumnType::Time2 => Self::Time(ColumnValue::parse_time2(cursor, column_meta)?),
+
+            ColumnType::TimeStamp => Self::Timestamp(ColumnValue::parse_timestamp(cursor)?),
+
+            ColumnType::TimeStamp2 => {
+                Self::Timestamp(ColumnValue::parse_timestamp2(cursor, column_meta)?)
+            }
+
+            ColumnType::DateTime => Self::DateTime(ColumnValue::parse_datetime(cursor)?),
+
+            ColumnType::DateTime2 => {
+                Self::DateTime(ColumnValue::parse_datetime2(cursor, column_meta)?)
+            }
+
+            ColumnType::Year => Self::Year(cursor.read_u8()? as u16 + 1900),
+
+            ColumnType::VarChar | ColumnType::VarString => {
+                Self::String(Self::parse_string(cursor, column_meta)?)
+            }
+
+            ColumnType::String => Self::String(Self::parse_string(cursor, column_length)?),
+
+            ColumnType::Blob
+            | ColumnType::Geometry
+            | ColumnType::TinyBlob
+            | ColumnType::MediumBlob
+            | ColumnType::LongBlob => Self::Blob(Self::parse_blob(cursor, column_meta)?),
+
+            ColumnType::Enum => {
+                Self::Enum(cursor.read_int::<LittleEndian>(column_length as usize)? as u32)
+            }
+
+            ColumnType::Set => {
+                Self::Set(cursor.read_int::<LittleEndian>(column_length as usize)? as u64)
+            }
+
+            ColumnType::Json => Self::Json(Self::parse_blob(cursor, column_meta)?),
+
+            ColumnType::Vector => Self::Vector(ColumnValue::parse_vector(Self::parse_blob(
+                cursor,
+                column_meta,
+            )?)?),
+
+            _ => {
+                return Err(BinlogError::UnsupportedColumnType(format!(
+                    "{:?}",
+                    column_type
+                )))
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// Moves the cursor over a column value without decoding it.
+    pub fn skip<B: AsRef<[u8]> + ?Sized>(
+        cursor: &mut Cursor<&'a B>,
+        column_type: ColumnType,
+        column_meta: u16,
+        column_length: u16,
+    ) -> Result<(), BinlogError> {
+        let fraction_length = column_meta.div_ceil(2) as usize;
+        let length = match column_type {
+            ColumnType::Bit => {
+                let bit_count = (column_meta >> 8) * 8 + (column_meta & 0xFF);
+                bit_count.div_ceil(8) as usize
+            }
+            ColumnType::Tiny | ColumnType::Year => 1,
+            ColumnType::Short => 2,
+            ColumnType::Int24 | ColumnType::Date | ColumnType::Time => 3,
+            ColumnType::Long | ColumnType::Float | ColumnType::TimeStamp => 4,
+            ColumnType::LongLong | ColumnType::Double | ColumnType::DateTime => 8,
+            ColumnType::NewDecimal => {
+                let precision = (column_meta & 0xFF) as usize;
+                let scale = (column_meta >> 8) as usize;
+                ColumnValue::decimal_length(precision, scale)
+            }
+            ColumnType::Time2 => 3 + fraction_length,
+            ColumnType::TimeStamp2 => 4 + fraction_length,
+            ColumnType::DateTime2 => 5 + fraction_length,
+            ColumnType::VarChar | ColumnType::VarString => {
+                Self::read_string_length(cursor, column_meta)?
+            }
+            ColumnType::String => Self::read_string_length(cursor, column_length)?,
+            ColumnType::Blob
+            | ColumnType::Geometry
+            | ColumnType::TinyBlob
+            | ColumnType::MediumBlob
+            | ColumnType::LongBlob
+            | ColumnType::Json
+            | ColumnType::Vector => {
+                cursor.read_uint::<LittleEndian>(column_meta as usize)? as usize
+            }
+            ColumnType::Enum | ColumnType::Set => column_length as usize,
+            _ => {
+                return Err(BinlogError::UnsupportedColumnType(format!(
+                    "{:?}",
+                    column_type
+                )))
+            }
+        };
+
+        if cursor.available() < length {
+            return Err(BinlogError::UnexpectedData(format!(
+                "column value of {} bytes exceeds the event data",
+                length
+            )));
+        }
+        cursor.seek(SeekFrom::Current(length as i64))?;
+        Ok(())
+    }
+
+    pub fn to_owned_value(&self) -> ColumnValue {
+        self.clone().into()
+    }
+
+    fn parse_string<B: AsRef<[u8]> + ?Sized>(
+        cursor: &mut Cursor<&'a B>,
+        column_meta: u16,
+    ) -> Result<&'a [u8], BinlogError> {
+        let size = Self::read_string_length(cursor, column_meta)?;
+        // charset is not present in the binary log, return byte[] instead of an actual String
+        Self::read_slice(cursor, size)
+    }
+
+    fn parse_blob<B: AsRef<[u8]> + ?Sized>(
+        cursor: &mut Cursor<&'a B>,
+        column_meta: u16,
+    ) -> Result<&'a [u8], BinlogError> {
+        let size = cursor.read_uint::<LittleEndian>(column_meta as usize)? as usize;
+        Self::read_slice(cursor, size)
+    }
+
+    fn read_string_length<B: AsRef<[u8]> + ?Sized>(
+        cursor: &mut Cursor<&'a B>,
+        column_meta: u16,
+    ) -> Result<usize, BinlogError> {
+        let size = if column_meta < 256 {
+            cursor.read_u8()? as usize
+        } else {
+            cursor.read_u16::<LittleEndian>()? as usize
+        };
+        Ok(size)
+    }
+
+    /// Borrow n bytes from the underlying buffer and move the cursor forward
+    fn read_slice<B: AsRef<[u8]> + ?Sized>(
+        cursor: &mut Cursor<&'a B>,
+        size: usize,
+    ) -> Result<&'a [u8], BinlogError> {
+        let buf: &'a [u8] = (*cursor.get_ref()).as_ref();
+        let start = cursor.position() as usize;
+        let end = start + size;
+        if end > buf.len() {
+            return Err(BinlogError::IoError(
+                std::io::ErrorKind::UnexpectedEof.into(),
+            ));
+        }
+        cursor.set_position(end as u64);
+        Ok(&buf[start..end])
+    }
+}
+
+impl From<ColumnValueRef<'_>> for ColumnValue {
+    fn from(value: ColumnValueRef<'_>) -> Self {
+        match value {
+            ColumnValueRef::None => ColumnValue::None,
+            ColumnValueRef::Tiny(v) => ColumnValue::Tiny(v),
+            ColumnValueRef::Short(v) => ColumnValue::Short(v),
+            ColumnValueRef::Long(v) => ColumnValue::Long(v),
+            ColumnValueRef::LongLong(v) => ColumnValue::LongLong(v),
+            ColumnValueRef::Float(v) => ColumnValue::Float(v),
+            ColumnValueRef::Double(v) => ColumnValue::Double(v),
+            ColumnValueRef::Decimal(v) => ColumnValue::Decimal(v),
+            ColumnValueRef::Time(v) => ColumnValue::Time(v),
+            ColumnValueRef::Date(v) => ColumnValue::Date(v),
+            ColumnValueRef::DateTime(v) => ColumnValue::DateTime(v),
+            ColumnValueRef::Timestamp(v) => ColumnValue::Timestamp(v),
+            ColumnValueRef::Year(v) => ColumnValue::Year(v),
+            ColumnValueRef::String(v) => ColumnValue::String(v.to_vec()),
+            ColumnValueRef::Blob(v) => ColumnValue::Blob(v.to_vec()),
+            ColumnValueRef::Bit(v) => ColumnValue::Bit(v),
+            ColumnValueRef::Set(v) => ColumnValue::Set(v),
+            ColumnValueRef::Enum(v) => ColumnValue::Enum(v),
+            ColumnValueRef::Json(v) => ColumnValue::Json(v.to_vec()),
+            ColumnValueRef::Vector(v) => ColumnValue::Vector(v),
+        }
+    }
+}
@@ -1,3 +1,4 @@
 pub mod column_type;
 pub mod column_value;
+pub mod column_value_ref;
 pub mod json;
@@ -5,6 +5,9 @@ pub enum AuthPlugin {
     Unsupported,
     MySqlNativePassword,
     CachingSha2Password,
+    Sha256Password,
+    MySqlClearPassword,
+    ClientEd25519,
 }
 
 impl AuthPlugin {
@@ -12,6 +15,9 @@ impl AuthPlugin {
         match self {
             AuthPlugin::MySqlNativePassword => "mysql_native_password",
             AuthPlugin::CachingSha2Password => "caching_sha2_password",
+            AuthPlugin::Sha256Password => "sha256_password",
+            AuthPlugin::MySqlClearPassword => "mysql_clear_password",
+            AuthPlugin::ClientEd25519 => "client_ed25519",
             _ => "unsupported",
         }
     }
@@ -20,6 +26,9 @@ impl AuthPlugin {
         match name.to_lowercase().as_str() {
             "mysql_native_password" => AuthPlugin::MySqlNativePassword,
             "caching_sha2_password" => AuthPlugin::CachingSha2Password,
+            "sha256_password" => AuthPlugin::Sha256Password,
+            "mysql_clear_password" => AuthPlugin::MySqlClearPassword,
+            "client_ed25519" => AuthPlugin::ClientEd25519,
             _ => AuthPlugin::Unsupported,
         }
     }
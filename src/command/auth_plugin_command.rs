@@ -0,0 +1,48 @@
+use std::io::Write;
+
+use byteorder::{LittleEndian, WriteBytesExt};
+
+use crate::{binlog_error::BinlogError, constants::ClientCapabilities, ext::buf_ext::BufExt};
+
+/// Handshake response of the plugins authenticated by an AuthPluginHandler.
+pub struct AuthPluginCommand {
+    pub schema: String,
+    pub username: String,
+    pub auth_response: Vec<u8>,
+    pub auth_plugin_name: String,
+    pub collation: u8,
+    pub client_capabilities: u32,
+}
+
+impl AuthPluginCommand {
+    pub fn to_bytes(&mut self) -> Result<Vec<u8>, BinlogError> {
+        let mut buf = Vec::new();
+
+        // the auth response is length encoded, tokens may be longer than 255 bytes
+        let client_capabilities = self.client_capabilities
+            | ClientCapabilities::PLUGIN_AUTH
+            | ClientCapabilities::PLUGIN_AUTH_LENENC_CLIENT_DATA;
+        buf.write_u32::<LittleEndian>(client_capabilities)?;
+
+        // maximum packet length
+        buf.write_u32::<LittleEndian>(0)?;
+        buf.write_u8(self.collation)?;
+
+        // reserved bytes
+        for _ in 0..23 {
+            buf.write_u8(0)?;
+        }
+
+        buf.write_null_terminated_string(&self.username)?;
+
+        buf.write_packed_number(self.auth_response.len())?;
+        buf.write_all(&self.auth_response)?;
+
+        if !self.schema.is_empty() {
+            buf.write_null_terminated_string(&self.schema)?;
+        }
+
+        buf.write_null_terminated_string(&self.auth_plugin_name)?;
+        Ok(buf)
+    }
+}
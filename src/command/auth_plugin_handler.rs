@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+#[cfg(feature = "ed25519")]
+use curve25519_dalek::{edwards::EdwardsPoint, scalar::Scalar};
+#[cfg(feature = "ed25519")]
+use sha2::{Digest, Sha512};
+
+use crate::{binlog_error::BinlogError, constants, ext::buf_ext::BufExt};
+
+use super::auth_plugin::AuthPlugin;
+
+/// Length of the scramble of mysql_native_password, caching_sha2_password and sha256_password.
+const SCRAMBLE_LENGTH: usize = 20;
+/// Length of the nonce signed by client_ed25519.
+#[cfg(feature = "ed25519")]
+const ED25519_NONCE_LENGTH: usize = 32;
+
+/// What an AuthPluginHandler knows of the connection.
+pub struct AuthContext<'a> {
+    pub username: &'a str,
+    pub password: &'a str,
+    /// Auth plugin data sent by the server, the scramble of the greeting or the raw data of
+    /// the auth switch request.
+    pub scramble: &'a [u8],
+    /// Whether the connection is over TLS or a Unix domain socket.
+    pub secure_transport: bool,
+}
+
+/// Client side of an authentication plugin, such as a cloud IAM token plugin.
+///
+/// Handlers are registered by BinlogClient::with_auth_plugin_handler and used when the server
+/// asks for the plugin of their name, in the greeting or an auth switch request.
+pub trait AuthPluginHandler: Send + Sync {
+    /// Name of the plugin, such as "mysql_clear_password".
+    fn name(&self) -> &str;
+
+    /// Auth data sent in the handshake response or after an auth switch request.
+    fn auth_response(&self, context: &AuthContext) -> Result<Vec<u8>, BinlogError>;
+
+    /// Answers an AuthMoreData packet (without its 0x01 prefix), None to send nothing and wait
+    /// for the next packet.
+    fn more_data(
+        &self,
+        _context: &AuthContext,
+        data: &[u8],
+    ) -> Result<Option<Vec<u8>>, BinlogError> {
+        Err(BinlogError::ConnectError(format!(
+            "unexpected auth more data for {}: {:?}",
+            self.name(),
+            data
+        )))
+    }
+}
+
+/// The handler of a built in plugin which is not handled by Authenticator itself.
+pub fn builtin_handler(auth_plugin: &AuthPlugin) -> Option<Arc<dyn AuthPluginHandler>> {
+    match auth_plugin {
+        AuthPlugin::Sha256Password => Some(Arc::new(Sha256PasswordHandler)),
+        AuthPlugin::MySqlClearPassword => Some(Arc::new(ClearPasswordHandler)),
+        #[cfg(feature = "ed25519")]
+        AuthPlugin::ClientEd25519 => Some(Arc::new(Ed25519PasswordHandler)),
+        _ => None,
+    }
+}
+
+/// sha256_password, the password is sent in clear over a secure transport, otherwise
+/// encrypted by the RSA public key retrieved from the server.
+pub struct Sha256PasswordHandler;
+
+impl AuthPluginHandler for Sha256PasswordHandler {
+    fn name(&self) -> &str {
+        AuthPlugin::Sha256Password.to_str()
+    }
+
+    fn auth_response(&self, context: &AuthContext) -> Result<Vec<u8>, BinlogError> {
+        if context.password.is_empty() {
+            return Ok(vec![constants::NULL_TERMINATOR]);
+        }
+        if context.secure_transport {
+            return Ok(null_terminated_password(context));
+        }
+        // request the public key
+        Ok(vec![0x01])
+    }
+
+    fn more_data(
+        &self,
+        context: &AuthContext,
+        data: &[u8],
+    ) -> Result<Option<Vec<u8>>, BinlogError> {
+        let scramble = &context.scramble[..SCRAMBLE_LENGTH.min(context.scramble.len())];
+        if scramble.is_empty() {
+            return Err(BinlogError::ConnectError(
+                "missing scramble for sha256_password".into(),
+            ));
+        }
+        let password = null_terminated_password(context).xor(scramble.to_vec());
+        Ok(Some(mysql_common::crypto::encrypt(&password, data)))
+    }
+}
+
+/// mysql_clear_password, the password is sent in clear so only over a secure transport.
+pub struct ClearPasswordHandler;
+
+impl AuthPluginHandler for ClearPasswordHandler {
+    fn name(&self) -> &str {
+        AuthPlugin::MySqlClearPassword.to_str()
+    }
+
+    fn auth_response(&self, context: &AuthContext) -> Result<Vec<u8>, BinlogError> {
+        if !context.secure_transport {
+            return Err(BinlogError::ConnectError(
+                "mysql_clear_password requires TLS or a unix domain socket".into(),
+            ));
+        }
+        Ok(null_terminated_password(context))
+    }
+}
+
+/// MariaDB client_ed25519, the nonce from the server is signed by a key derived from the
+/// password. Needs the `ed25519` feature.
+#[cfg(feature = "ed25519")]
+pub struct Ed25519PasswordHandler;
+
+#[cfg(feature = "ed25519")]
+impl AuthPluginHandler for Ed25519PasswordHandler {
+    fn name(&self) -> &str {
+        AuthPlugin::ClientEd25519.to_str()
+    }
+
+    fn auth_response(&self, context: &AuthContext) -> Result<Vec<u8>, BinlogError> {
+        // the greeting scramble is too short, the server sends the nonce in an auth switch
+        if context.scramble.len() < ED25519_NONCE_LENGTH {
+            return Ok(Vec::new());
+        }
+        let nonce = &context.scramble[..ED25519_NONCE_LENGTH];
+        Ok(ed25519_sign(context.password.as_bytes(), nonce).to_vec())
+    }
+
+    fn more_data(
+        &self,
+        context: &AuthContext,
+        data: &[u8],
+    ) -> Result<Option<Vec<u8>>, BinlogError> {
+        if data.len() != ED25519_NONCE_LENGTH {
+            return Err(BinlogError::ConnectError(format!(
+                "unexpected nonce length for client_ed25519: {}",
+                data.len()
+            )));
+        }
+        Ok(Some(
+            ed25519_sign(context.password.as_bytes(), data).to_vec(),
+        ))
+    }
+}
+
+fn null_terminated_password(context: &AuthContext) -> Vec<u8> {
+    let mut password = context.password.as_bytes().to_vec();
+    password.push(constants::NULL_TERMINATOR);
+    password
+}
+
+/// Ed25519 signature with the SHA-512 of the password in place of the SHA-512 of the seed,
+/// as MariaDB does.
+#[cfg(feature = "ed25519")]
+fn ed25519_sign(password: &[u8], message: &[u8]) -> [u8; 64] {
+    let az = Sha512::digest(password);
+    let mut secret = [0u8; 32];
+    secret.copy_from_slice(&az[..32]);
+    secret[0] &= 248;
+    secret[31] &= 63;
+    secret[31] |= 64;
+    let secret = Scalar::from_bytes_mod_order(secret);
+    let public_key = EdwardsPoint::mul_base(&secret).compress();
+
+    let r = hash_to_scalar(&[&az[32..], message]);
+    let big_r = EdwardsPoint::mul_base(&r).compress();
+    let k = hash_to_scalar(&[big_r.as_bytes(), public_key.as_bytes(), message]);
+    let s = k * secret + r;
+
+    let mut signature = [0u8; 64];
+    signature[..32].copy_from_slice(big_r.as_bytes());
+    signature[32..].copy_from_slice(s.as_bytes());
+    signature
+}
+
+#[cfg(feature = "ed25519")]
+fn hash_to_scalar(parts: &[&[u8]]) -> Scalar {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut wide = [0u8; 64];
+    wide.copy_from_slice(&hasher.finalize());
+    Scalar::from_bytes_mod_order_wide(&wide)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "ed25519")]
+    use super::ed25519_sign;
+    use super::{AuthContext, AuthPluginHandler, ClearPasswordHandler, Sha256PasswordHandler};
+
+    #[cfg(feature = "ed25519")]
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn test_ed25519_sign() {
+        // RFC 8032 test vectors, the seed hashed as the password gives the standard key
+        let seed = hex("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60");
+        let signature = hex("e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b");
+        assert_eq!(ed25519_sign(&seed, b"").to_vec(), signature);
+
+        let seed = hex("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb");
+        let signature = hex("92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00");
+        assert_eq!(ed25519_sign(&seed, &[0x72]).to_vec(), signature);
+    }
+
+    #[test]
+    fn test_password_auth_responses() {
+        let mut context = AuthContext {
+            username: "root",
+            password: "pwd",
+            scramble: b"abcdefghijklmnopqrst",
+            secure_transport: false,
+        };
+        assert_eq!(
+            Sha256PasswordHandler.auth_response(&context).unwrap(),
+            vec![0x01]
+        );
+        assert!(ClearPasswordHandler.auth_response(&context).is_err());
+
+        context.secure_transport = true;
+        assert_eq!(
+            Sha256PasswordHandler.auth_response(&context).unwrap(),
+            b"pwd\0"
+        );
+        assert_eq!(
+            ClearPasswordHandler.auth_response(&context).unwrap(),
+            b"pwd\0"
+        );
+    }
+}
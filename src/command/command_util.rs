@@ -137,8 +137,8 @@ impl CommandUtil {
         if buf[0] == MysqlRespCode::ERROR {
             let error_packet = ErrorPacket::new(buf)?;
             return Err(BinlogError::ConnectError(format!(
-                "connect mysql failed: {}",
-                error_packet.error_message
+                "connect mysql failed: ERROR {} ({}): {}",
+                error_packet.error_code, error_packet.sql_state, error_packet.error_message
             )));
         }
         Ok(())
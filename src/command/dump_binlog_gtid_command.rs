@@ -31,6 +31,14 @@ impl DumpBinlogGtidCommand {
         // binlog-pos
         buf.write_u64::<LittleEndian>(4)?;
 
+        // tagged GTIDs need the tagged encoding of the GTID set
+        if let Some(uuid) = self.gtid_set.map.keys().find(|uuid| uuid.contains(':')) {
+            return Err(BinlogError::InvalidGtid(format!(
+                "tagged GTIDs are not supported by binlog dump: {}",
+                uuid
+            )));
+        }
+
         let mut data_size = 8; // number of uuid_sets
         for uuid_set in self.gtid_set.map.values() {
             data_size += 16; // uuid
@@ -8,7 +8,7 @@ pub struct Interval {
     pub end: u64,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct GtidSet {
     pub map: HashMap<String, UuidSet>,
 }
@@ -151,7 +151,9 @@ impl GtidSet {
     pub fn new(gtid_set: &str) -> Result<Self, BinlogError> {
         let mut map: HashMap<String, UuidSet> = HashMap::new();
         // 6d3960f6-4b36-11ef-8614-0242ac110002:1-5:7-10:12,
-        // 787d08c4-4b36-11ef-8614-0242ac110006:1-5
+        // 787d08c4-4b36-11ef-8614-0242ac110006:1-5:tag_1:3-4
+        // intervals after a tag belong to the tagged GTIDs uuid:tag:N, kept as the source
+        // id uuid:tag
         let lines = gtid_set.replace('\n', "");
         let uuid_sets: Vec<&str> = lines.split(',').collect();
 
@@ -165,9 +167,20 @@ impl GtidSet {
                 return Err(BinlogError::InvalidGtid(uuid_set.to_string()));
             }
 
-            let source_id = parts[0].to_string();
+            let uuid = parts[0];
+            let mut source_id = uuid.to_string();
+            let mut intervals_by_source_id: Vec<(String, Vec<Interval>)> = vec![];
             let mut intervals = vec![];
             for interval_str in parts[1..].iter() {
+                if Self::is_tag(interval_str) {
+                    if !intervals.is_empty() {
+                        intervals_by_source_id.push((source_id, intervals));
+                        intervals = vec![];
+                    }
+                    source_id = format!("{}:{}", uuid, interval_str.to_lowercase());
+                    continue;
+                }
+
                 let interval_parts: Vec<&str> = interval_str.split('-').collect();
                 if interval_parts.is_empty() {
                     return Err(BinlogError::InvalidGtid(uuid_set.to_string()));
@@ -181,30 +194,48 @@ impl GtidSet {
                 };
                 intervals.push(Interval { start, end });
             }
-            map.insert(source_id.clone(), UuidSet::new(source_id, intervals));
+            // a tag must be followed by intervals
+            if intervals.is_empty() {
+                return Err(BinlogError::InvalidGtid(uuid_set.to_string()));
+            }
+            intervals_by_source_id.push((source_id, intervals));
+
+            for (source_id, intervals) in intervals_by_source_id {
+                map.insert(source_id.clone(), UuidSet::new(source_id, intervals));
+            }
         }
         Ok(GtidSet { map })
     }
 
+    /// Adds a single GTID like 6d3960f6-4b36-11ef-8614-0242ac110002:5, or a tagged one like
+    /// 6d3960f6-4b36-11ef-8614-0242ac110002:tag_1:5.
     pub fn add(&mut self, gtid: &str) -> Result<bool, BinlogError> {
-        let split: Vec<&str> = gtid.split(':').collect();
-        if split.len() != 2 {
-            return Err(BinlogError::InvalidGtid(gtid.to_string()));
-        }
+        let (source_id, transaction_id) = Self::parse_gtid(gtid)?;
+        let uuid_set = self
+            .map
+            .entry(source_id.clone())
+            .or_insert_with(|| UuidSet {
+                uuid: source_id,
+                intervals: vec![],
+            });
+        Ok(uuid_set.add(transaction_id))
+    }
 
-        let source_id = split[0];
-        if let Ok(transaction_id) = split[1].parse::<u64>() {
-            let uuid_set = self
-                .map
-                .entry(source_id.to_string())
-                .or_insert_with(|| UuidSet {
-                    uuid: source_id.to_string(),
-                    intervals: vec![],
-                });
-            Ok(uuid_set.add(transaction_id))
-        } else {
-            Err(BinlogError::InvalidGtid(gtid.to_string()))
-        }
+    /// Whether the set contains a single GTID like 6d3960f6-4b36-11ef-8614-0242ac110002:5,
+    /// or a tagged one like 6d3960f6-4b36-11ef-8614-0242ac110002:tag_1:5.
+    pub fn contains(&self, gtid: &str) -> Result<bool, BinlogError> {
+        let (source_id, transaction_id) = Self::parse_gtid(gtid)?;
+        Ok(self.contains_transaction(&source_id, transaction_id))
+    }
+
+    /// Same as contains, with a GTID already parsed by parse_gtid.
+    pub fn contains_transaction(&self, source_id: &str, transaction_id: u64) -> bool {
+        self.map.get(source_id).is_some_and(|uuid_set| {
+            uuid_set
+                .intervals
+                .iter()
+                .any(|i| i.start <= transaction_id && transaction_id <= i.end)
+        })
     }
 
     pub fn get_uuid_sets(&self) -> Vec<&UuidSet> {
@@ -228,6 +259,31 @@ impl GtidSet {
         true
     }
 
+    /// Parses a single GTID into its source id (uuid, or uuid:tag with the tag in lowercase)
+    /// and transaction id.
+    pub fn parse_gtid(gtid: &str) -> Result<(String, u64), BinlogError> {
+        let invalid = || BinlogError::InvalidGtid(gtid.to_string());
+        let (source_id, transaction_id) = gtid.trim().rsplit_once(':').ok_or_else(invalid)?;
+        let transaction_id = transaction_id.parse::<u64>().map_err(|_| invalid())?;
+        let source_id = match source_id.split_once(':') {
+            Some((uuid, tag)) if !uuid.is_empty() && Self::is_tag(tag) => {
+                format!("{}:{}", uuid, tag.to_lowercase())
+            }
+            None if !source_id.is_empty() => source_id.to_string(),
+            _ => return Err(invalid()),
+        };
+        Ok((source_id, transaction_id))
+    }
+
+    /// Whether a part of a GTID set is a tag: up to 32 letters, digits or underscores, not
+    /// starting with a digit.
+    fn is_tag(part: &str) -> bool {
+        !part.is_empty()
+            && part.len() <= 32
+            && !part.starts_with(|c: char| c.is_ascii_digit())
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+
     fn parse_interval_num(interval_num_str: &str, uuid_set: &str) -> Result<u64, BinlogError> {
         if let Ok(num) = interval_num_str.parse::<u64>() {
             Ok(num)
@@ -258,7 +314,7 @@ mod tests {
 
     use super::GtidSet;
 
-    const UUID: &'static str = "24bc7850-2c16-11e6-a073-0242ac110002";
+    const UUID: &str = "24bc7850-2c16-11e6-a073-0242ac110002";
 
     #[test]
     fn test_add() {
@@ -337,7 +393,7 @@ mod tests {
 
     #[test]
     fn test_subset_of() {
-        let set = vec![
+        let set = [
             GtidSet::new("").unwrap(),
             GtidSet::new(&format!("{}:1-191", UUID)).unwrap(),
             GtidSet::new(&format!("{}:192-199", UUID)).unwrap(),
@@ -372,16 +428,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_gtid() {
+        assert_eq!(
+            GtidSet::parse_gtid(&format!("{}:5", UUID)).unwrap(),
+            (UUID.to_string(), 5)
+        );
+        assert_eq!(
+            GtidSet::parse_gtid(&format!("{}:Tag_1:5", UUID)).unwrap(),
+            (format!("{}:tag_1", UUID), 5)
+        );
+        assert!(GtidSet::parse_gtid(UUID).is_err());
+        assert!(GtidSet::parse_gtid(":5").is_err());
+        assert!(GtidSet::parse_gtid(&format!("{}:1-2", UUID)).is_err());
+        assert!(GtidSet::parse_gtid(&format!("{}:1tag:5", UUID)).is_err());
+    }
+
+    #[test]
+    fn test_contains() {
+        let gtid_set = GtidSet::new(&format!("{}:1-191:193-199", UUID)).unwrap();
+        assert!(gtid_set.contains(&format!("{}:1", UUID)).unwrap());
+        assert!(gtid_set.contains(&format!("{}:199", UUID)).unwrap());
+        assert!(!gtid_set.contains(&format!("{}:192", UUID)).unwrap());
+        assert!(!gtid_set
+            .contains("24bc7850-2c16-11e6-a073-0242ac110003:1")
+            .unwrap());
+        assert!(gtid_set.contains(UUID).is_err());
+        assert!(gtid_set.contains(&format!("{}:1-2", UUID)).is_err());
+    }
+
+    #[test]
+    fn test_tagged_gtids() {
+        let mut gtid_set = GtidSet::new(&format!("{}:1-3:Tag_1:5-6:7:tag_2:1", UUID)).unwrap();
+        assert!(gtid_set.contains(&format!("{}:2", UUID)).unwrap());
+        assert!(!gtid_set.contains(&format!("{}:5", UUID)).unwrap());
+        assert!(gtid_set.contains(&format!("{}:tag_1:5", UUID)).unwrap());
+        assert!(gtid_set.contains(&format!("{}:TAG_1:7", UUID)).unwrap());
+        assert!(!gtid_set.contains(&format!("{}:tag_1:1", UUID)).unwrap());
+        assert!(gtid_set.contains(&format!("{}:tag_2:1", UUID)).unwrap());
+        assert!(gtid_set.contains(&format!("{}:tag_1", UUID)).is_err());
+
+        assert!(gtid_set.add(&format!("{}:tag_2:2", UUID)).unwrap());
+        assert_eq!(
+            gtid_set.to_string(),
+            format!("{0}:1-3,{0}:tag_1:5-7,{0}:tag_2:1-2", UUID)
+        );
+        assert_eq!(GtidSet::new(&gtid_set.to_string()).unwrap(), gtid_set);
+
+        assert!(GtidSet::new(&format!("{}:1-3:tag_1", UUID)).is_err());
+        assert!(GtidSet::new(&format!("{}:1-3:1tag", UUID)).is_err());
+    }
+
     #[test]
     fn test_single_interval() {
         let gtid_set = GtidSet::new(&format!("{}:1-191", UUID)).unwrap();
         let uuid_set = gtid_set.map.get(UUID).unwrap();
         assert_eq!(uuid_set.intervals.len(), 1);
         assert!(uuid_set.intervals.contains(&Interval::new(1, 191)));
-        assert_eq!(
-            uuid_set.intervals.iter().next(),
-            Some(&Interval::new(1, 191))
-        );
+        assert_eq!(uuid_set.intervals.first(), Some(&Interval::new(1, 191)));
         assert_eq!(uuid_set.intervals.last(), Some(&Interval::new(1, 191)));
         assert_eq!(gtid_set.to_string(), format!("{}:1-191", UUID));
     }
@@ -392,10 +496,7 @@ mod tests {
         let uuid_set = gtid_set.map.get(UUID).unwrap();
         assert_eq!(uuid_set.intervals.len(), 1);
         assert!(uuid_set.intervals.contains(&Interval::new(1, 199)));
-        assert_eq!(
-            uuid_set.intervals.iter().next(),
-            Some(&Interval::new(1, 199))
-        );
+        assert_eq!(uuid_set.intervals.first(), Some(&Interval::new(1, 199)));
         assert_eq!(uuid_set.intervals.last(), Some(&Interval::new(1, 199)));
         assert_eq!(gtid_set.to_string(), format!("{}:1-199", UUID));
     }
@@ -405,10 +506,7 @@ mod tests {
         let gtid_set = GtidSet::new(&format!("{}:1-191:193-199", UUID)).unwrap();
         let uuid_set = gtid_set.map.get(UUID).unwrap();
         assert_eq!(uuid_set.intervals.len(), 2);
-        assert_eq!(
-            uuid_set.intervals.iter().next(),
-            Some(&Interval::new(1, 191))
-        );
+        assert_eq!(uuid_set.intervals.first(), Some(&Interval::new(1, 191)));
         assert_eq!(uuid_set.intervals.last(), Some(&Interval::new(193, 199)));
         assert_eq!(gtid_set.to_string(), format!("{}:1-191:193-199", UUID));
     }
@@ -1,5 +1,7 @@
 pub mod auth_native_password_command;
 pub mod auth_plugin;
+pub mod auth_plugin_command;
+pub mod auth_plugin_handler;
 pub mod auth_sha2_password_command;
 pub mod auth_sha2_rsa_password_command;
 pub mod authenticator;
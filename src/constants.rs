@@ -23,6 +23,7 @@ impl ClientCapabilities {
     pub const PS_MULTI_RESULTS: u32 = 1 << 18;
     pub const PLUGIN_AUTH: u32 = 1 << 19;
     pub const PLUGIN_AUTH_LENENC_CLIENT_DATA: u32 = 1 << 21;
+    pub const ZSTD_COMPRESSION_ALGORITHM: u32 = 1 << 26;
     pub const SSL_VERIFY_SERVER_CERT: u32 = 1 << 30;
     pub const REMEMBER_OPTIONS: u32 = 1 << 31;
 }
@@ -34,6 +35,7 @@ impl MysqlRespCode {
     pub const ERROR: u8 = 0xFF;
     pub const EOF: u8 = 0xFE;
     pub const AUTH_PLUGIN_SWITCH: u8 = 0xFE;
+    pub const AUTH_MORE_DATA: u8 = 0x01;
 }
 
 pub const EVENT_HEADER_LENGTH: usize = 19;
@@ -14,6 +14,13 @@ impl ChecksumType {
         }
     }
 
+    pub fn to_code(&self) -> u8 {
+        match self {
+            ChecksumType::CRC32 => 0x01,
+            _ => 0x00,
+        }
+    }
+
     pub fn from_name(name: &str) -> Self {
         match name {
             "CRC32" => ChecksumType::CRC32,
@@ -0,0 +1,95 @@
+use std::{collections::HashMap, io::Cursor};
+
+use crate::{binlog_error::BinlogError, ext::cursor_ext::CursorExt};
+
+use super::{
+    event_data::EventData, event_header::EventHeader, row_event_ref::RowEventRef,
+    table_map_event::TableMapEvent,
+};
+
+/// Event data borrowed from the event buffer, rows events are decoded lazily,
+/// other events are decoded into owned EventData.
+#[derive(Debug, Clone)]
+pub enum EventDataRef<'a> {
+    WriteRows(RowsEventRef<'a>),
+    UpdateRows(UpdateRowsEventRef<'a>),
+    DeleteRows(RowsEventRef<'a>),
+    Owned(EventData),
+}
+
+/// Borrowed WriteRows / DeleteRows event.
+#[derive(Debug, Clone)]
+pub struct RowsEventRef<'a> {
+    pub table_id: u64,
+    pub included_columns: Vec<bool>,
+    pub rows: Vec<RowEventRef<'a>>,
+}
+
+/// Borrowed UpdateRows event.
+#[derive(Debug, Clone)]
+pub struct UpdateRowsEventRef<'a> {
+    pub table_id: u64,
+    pub included_columns_before: Vec<bool>,
+    pub included_columns_after: Vec<bool>,
+    pub rows: Vec<(RowEventRef<'a>, RowEventRef<'a>)>,
+}
+
+impl<'a> RowsEventRef<'a> {
+    pub fn parse(
+        cursor: &mut Cursor<&'a [u8]>,
+        table_map_event_by_table_id: &'a HashMap<u64, TableMapEvent>,
+        row_event_version: u8,
+    ) -> Result<Self, BinlogError> {
+        let (table_id, _flags, _extra_data, _column_count, included_columns) =
+            EventHeader::parse_rows_event_common_header(cursor, row_event_version)?;
+        let table_map_event = get_table_map_event(table_map_event_by_table_id, table_id)?;
+
+        let mut rows = Vec::new();
+        while cursor.available() > 0 {
+            let row = RowEventRef::parse(cursor, table_map_event, &included_columns)?;
+            rows.push(row);
+        }
+
+        Ok(Self {
+            table_id,
+            included_columns,
+            rows,
+        })
+    }
+}
+
+impl<'a> UpdateRowsEventRef<'a> {
+    pub fn parse(
+        cursor: &mut Cursor<&'a [u8]>,
+        table_map_event_by_table_id: &'a HashMap<u64, TableMapEvent>,
+        row_event_version: u8,
+    ) -> Result<Self, BinlogError> {
+        let (table_id, _flags, _extra_data, column_count, included_columns_before) =
+            EventHeader::parse_rows_event_common_header(cursor, row_event_version)?;
+        let included_columns_after = cursor.read_bits(column_count, false)?;
+        let table_map_event = get_table_map_event(table_map_event_by_table_id, table_id)?;
+
+        let mut rows = Vec::new();
+        while cursor.available() > 0 {
+            let before = RowEventRef::parse(cursor, table_map_event, &included_columns_before)?;
+            let after = RowEventRef::parse(cursor, table_map_event, &included_columns_after)?;
+            rows.push((before, after));
+        }
+
+        Ok(Self {
+            table_id,
+            included_columns_before,
+            included_columns_after,
+            rows,
+        })
+    }
+}
+
+fn get_table_map_event(
+    table_map_event_by_table_id: &HashMap<u64, TableMapEvent>,
+    table_id: u64,
+) -> Result<&TableMapEvent, BinlogError> {
+    table_map_event_by_table_id.get(&table_id).ok_or_else(|| {
+        BinlogError::UnexpectedData(format!("no table map event for table_id: {}", table_id))
+    })
+}
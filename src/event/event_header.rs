@@ -1,9 +1,13 @@
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::io::{Cursor, Read, Seek, Write};
 
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use serde::{Deserialize, Serialize};
 
-use crate::{binlog_error::BinlogError, constants, ext::cursor_ext::CursorExt};
+use crate::{
+    binlog_error::BinlogError,
+    constants,
+    ext::{buf_ext::BufExt, cursor_ext::CursorExt},
+};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct EventHeader {
@@ -32,25 +36,62 @@ impl EventHeader {
         })
     }
 
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), BinlogError> {
+        buf.write_u32::<LittleEndian>(self.timestamp)?;
+        buf.write_u8(self.event_type)?;
+        buf.write_u32::<LittleEndian>(self.server_id)?;
+        buf.write_u32::<LittleEndian>(self.event_length)?;
+        buf.write_u32::<LittleEndian>(self.next_event_position)?;
+        buf.write_u16::<LittleEndian>(self.event_flags)?;
+        Ok(())
+    }
+
     // Parse the common header for rows events:
     // WriteRows / UpdateRows / DeleteRows
     // ExtWriteRows / ExtUpdateRows / ExtDeleteRows
-    pub fn parse_rows_event_common_header(
-        cursor: &mut Cursor<&Vec<u8>>,
+    // returns (table_id, flags, extra_data, column_count, included_columns)
+    #[allow(clippy::type_complexity)]
+    pub fn parse_rows_event_common_header<T: AsRef<[u8]>>(
+        cursor: &mut Cursor<T>,
         row_event_version: u8,
-    ) -> Result<(u64, usize, Vec<bool>), BinlogError> {
+    ) -> Result<(u64, u16, Vec<u8>, usize, Vec<bool>), BinlogError> {
         let table_id = cursor.read_u48::<LittleEndian>()?;
-        let _flags = cursor.read_u16::<LittleEndian>()?;
+        let flags = cursor.read_u16::<LittleEndian>()?;
 
         // ExtWriteRows/ExtUpdateRows/ExtDeleteRows, version 2, MySQL only
+        let mut extra_data = Vec::new();
         if row_event_version == 2 {
-            let extra_data_length = cursor.read_u16::<LittleEndian>()? as i64;
-            cursor.seek(SeekFrom::Current(extra_data_length - 2))?;
+            // the length includes the 2 bytes of itself
+            let extra_data_length = cursor.read_u16::<LittleEndian>()? as usize;
+            extra_data = vec![0u8; extra_data_length.saturating_sub(2)];
+            cursor.read_exact(&mut extra_data)?;
         }
 
         let column_count = cursor.read_packed_number()?;
         let included_columns = cursor.read_bits(column_count, false)?;
 
-        Ok((table_id, column_count, included_columns))
+        Ok((table_id, flags, extra_data, column_count, included_columns))
+    }
+
+    pub fn encode_rows_event_common_header(
+        buf: &mut Vec<u8>,
+        table_id: u64,
+        flags: u16,
+        extra_data: &[u8],
+        row_event_version: u8,
+        included_columns: &[bool],
+        legacy_bitmap_padding: bool,
+    ) -> Result<(), BinlogError> {
+        buf.write_u48::<LittleEndian>(table_id)?;
+        buf.write_u16::<LittleEndian>(flags)?;
+
+        if row_event_version == 2 {
+            buf.write_u16::<LittleEndian>(extra_data.len() as u16 + 2)?;
+            buf.write_all(extra_data)?;
+        }
+
+        buf.write_packed_number(included_columns.len())?;
+        buf.write_bits_with_padding(included_columns, legacy_bitmap_padding)?;
+        Ok(())
     }
 }
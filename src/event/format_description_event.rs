@@ -1,7 +1,7 @@
-use crate::{binlog_error::BinlogError, event::event_type::EventType};
-use byteorder::{LittleEndian, ReadBytesExt};
+use crate::{binlog_error::BinlogError, event::event_type::EventType, ext::buf_ext::BufExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use serde::{Deserialize, Serialize};
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::io::{Cursor, Read, Write};
 
 use super::checksum_type::ChecksumType;
 
@@ -11,9 +11,14 @@ pub struct FormatDescriptionEvent {
     pub server_version: String,
     pub create_timestamp: u32,
     pub header_length: u8,
+    /// Post-header length of each event type, indexed by event type code - 1.
+    pub event_type_header_lengths: Vec<u8>,
     pub checksum_type: ChecksumType,
 }
 
+// binlog_version + server_version + create_timestamp + header_length
+const FIXED_PART_LENGTH: usize = 2 + 50 + 4 + 1;
+
 impl FormatDescriptionEvent {
     pub fn parse(cursor: &mut Cursor<&Vec<u8>>, data_length: usize) -> Result<Self, BinlogError> {
         // refer: https://dev.mysql.com/doc/dev/mysql-server/latest/classbinary__log_1_1Format__description__event.html
@@ -23,9 +28,7 @@ impl FormatDescriptionEvent {
         // server_version: 50 bytes
         let mut server_version_buf = [0u8; 50];
         cursor.read_exact(&mut server_version_buf)?;
-        let server_version = std::str::from_utf8(&server_version_buf)
-            .unwrap()
-            .to_string();
+        let server_version = server_version_buf.to_vec().to_utf8_string();
 
         // create_timestamp: 4 bytes
         let create_timestamp = cursor.read_u32::<LittleEndian>()?;
@@ -37,28 +40,81 @@ impl FormatDescriptionEvent {
         // post-header (76 : n), it is an array of n bytes,
         // one byte per event type that the server knows about, n = count of all event types,
         // the 14th (EventType::FormatDescription - 1) byte contains the payload length of FormatDescription,
-        cursor.seek(SeekFrom::Current(EventType::FormatDescription as i64 - 1))?;
-        let payload_length = cursor.read_u8()? as usize;
+        let rest_length = data_length.checked_sub(FIXED_PART_LENGTH).ok_or_else(|| {
+            BinlogError::UnexpectedData(format!(
+                "format description event too short: {}",
+                data_length
+            ))
+        })?;
+        let mut rest = vec![0u8; rest_length];
+        cursor.read_exact(&mut rest)?;
+        let payload_length = *rest
+            .get(EventType::FormatDescription as usize - 1)
+            .ok_or_else(|| {
+                BinlogError::UnexpectedData(format!(
+                    "format description event without its post-header length: {}",
+                    data_length
+                ))
+            })? as usize;
+        let header_lengths_length = payload_length
+            .saturating_sub(FIXED_PART_LENGTH)
+            .min(rest.len());
+        let event_type_header_lengths = rest[..header_lengths_length].to_vec();
 
         // after the header and payload, it is the checksum type, 1 byte
-        let mut checksum_type = 0;
-        let checksum_block_length = data_length - payload_length;
-        if checksum_block_length > 0 {
-            // seek to the end of payload
-            let current_pos = 2 + 50 + 4 + 1 + EventType::FormatDescription as u8;
-            cursor.seek(SeekFrom::Current(
-                payload_length as i64 - current_pos as i64,
-            ))?;
-            // read checksum type, refer: https://mariadb.com/kb/en/format_description_event/
-            checksum_type = cursor.read_u8()?;
-        }
+        // read checksum type, refer: https://mariadb.com/kb/en/format_description_event/
+        let checksum_type = rest
+            .get(event_type_header_lengths.len())
+            .copied()
+            .unwrap_or_default();
 
         Ok(Self {
             binlog_version,
             server_version,
             create_timestamp,
             header_length,
+            event_type_header_lengths,
             checksum_type: ChecksumType::from_code(checksum_type),
         })
     }
+
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), BinlogError> {
+        buf.write_u16::<LittleEndian>(self.binlog_version)?;
+        let mut server_version = self.server_version.as_bytes().to_vec();
+        server_version.resize(50, 0);
+        buf.write_all(&server_version)?;
+        buf.write_u32::<LittleEndian>(self.create_timestamp)?;
+        buf.write_u8(self.header_length)?;
+        buf.write_all(&self.event_type_header_lengths)?;
+        buf.write_u8(self.checksum_type.to_code())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::FormatDescriptionEvent;
+
+    #[test]
+    fn test_parse_damaged_event() {
+        // too short for the post-header length of FormatDescription
+        let mut body = vec![4, 0];
+        body.extend_from_slice(&[0xff; 50]);
+        body.extend_from_slice(&[0; 5]);
+        body.extend_from_slice(&[0; 8]);
+        let res = FormatDescriptionEvent::parse(&mut Cursor::new(&body), body.len());
+        assert!(res.is_err());
+
+        let res = FormatDescriptionEvent::parse(&mut Cursor::new(&body), 20);
+        assert!(res.is_err());
+
+        // the invalid server version is kept lossily
+        body.extend_from_slice(&[0; 8]);
+        body[57 + 14] = 57 + 15;
+        let event = FormatDescriptionEvent::parse(&mut Cursor::new(&body), body.len()).unwrap();
+        assert!(event.server_version.starts_with('\u{fffd}'));
+        assert_eq!(event.event_type_header_lengths.len(), 15);
+    }
 }
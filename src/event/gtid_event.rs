@@ -1,15 +1,35 @@
-use crate::binlog_error::BinlogError;
-use byteorder::{LittleEndian, ReadBytesExt};
+use crate::{
+    binlog_error::BinlogError,
+    ext::{buf_ext::BufExt, cursor_ext::CursorExt},
+};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use serde::{Deserialize, Serialize};
 use std::fmt::Write;
 use std::io::Cursor;
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct GtidEvent {
     pub flags: u8,
     pub gtid: String,
+    /// Logical clock of the transaction (MySQL 5.7+).
+    pub last_committed: Option<i64>,
+    pub sequence_number: Option<i64>,
+    /// Commit time on the immediate / original source in microseconds (MySQL 8.0.1+),
+    /// the original one is only logged if it differs from the immediate one.
+    pub immediate_commit_timestamp: Option<u64>,
+    pub original_commit_timestamp: Option<u64>,
+    /// Length of the transaction in bytes, including the GTID event (MySQL 8.0.2+).
+    pub transaction_length: Option<u64>,
+    /// Version of the immediate / original source server (MySQL 8.0.14+),
+    /// the original one is only logged if it differs from the immediate one.
+    pub immediate_server_version: Option<u32>,
+    pub original_server_version: Option<u32>,
 }
 
+const LOGICAL_TIMESTAMP_TYPE_CODE: u8 = 2;
+const ORIGINAL_COMMIT_TIMESTAMP_FLAG: u64 = 1 << 55;
+const ORIGINAL_SERVER_VERSION_FLAG: u32 = 1 << 31;
+
 impl GtidEvent {
     pub fn parse(cursor: &mut Cursor<&Vec<u8>>) -> Result<Self, BinlogError> {
         // refer: https://dev.mysql.com/doc/refman/8.0/en/replication-gtids-concepts.html
@@ -17,11 +37,96 @@ impl GtidEvent {
         let flags = cursor.read_u8()?;
         let sid = Self::read_uuid(cursor)?;
         let gno = cursor.read_u64::<LittleEndian>()?;
-
-        Ok(GtidEvent {
+        let mut event = GtidEvent {
             flags,
             gtid: format!("{}:{}", sid, gno),
-        })
+            ..Default::default()
+        };
+
+        // each of the following parts is only present in the events of servers supporting it
+        if cursor.available() < 17 || cursor.read_u8()? != LOGICAL_TIMESTAMP_TYPE_CODE {
+            return Ok(event);
+        }
+        event.last_committed = Some(cursor.read_i64::<LittleEndian>()?);
+        event.sequence_number = Some(cursor.read_i64::<LittleEndian>()?);
+
+        if cursor.available() < 7 {
+            return Ok(event);
+        }
+        let immediate_commit_timestamp = cursor.read_uint::<LittleEndian>(7)?;
+        event.immediate_commit_timestamp =
+            Some(immediate_commit_timestamp & !ORIGINAL_COMMIT_TIMESTAMP_FLAG);
+        if immediate_commit_timestamp & ORIGINAL_COMMIT_TIMESTAMP_FLAG != 0 {
+            event.original_commit_timestamp = Some(cursor.read_uint::<LittleEndian>(7)?);
+        }
+
+        if cursor.available() == 0 {
+            return Ok(event);
+        }
+        event.transaction_length = Some(cursor.read_packed_number()? as u64);
+
+        if cursor.available() < 4 {
+            return Ok(event);
+        }
+        let immediate_server_version = cursor.read_u32::<LittleEndian>()?;
+        event.immediate_server_version =
+            Some(immediate_server_version & !ORIGINAL_SERVER_VERSION_FLAG);
+        if immediate_server_version & ORIGINAL_SERVER_VERSION_FLAG != 0 {
+            event.original_server_version = Some(cursor.read_u32::<LittleEndian>()?);
+        }
+        Ok(event)
+    }
+
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), BinlogError> {
+        let (sid, gno) = self
+            .gtid
+            .split_once(':')
+            .ok_or_else(|| BinlogError::InvalidGtid(self.gtid.clone()))?;
+        buf.write_u8(self.flags)?;
+        Self::write_uuid(buf, sid)?;
+        buf.write_u64::<LittleEndian>(gno.parse()?)?;
+
+        let (Some(last_committed), Some(sequence_number)) =
+            (self.last_committed, self.sequence_number)
+        else {
+            return Ok(());
+        };
+        buf.write_u8(LOGICAL_TIMESTAMP_TYPE_CODE)?;
+        buf.write_i64::<LittleEndian>(last_committed)?;
+        buf.write_i64::<LittleEndian>(sequence_number)?;
+
+        let Some(immediate_commit_timestamp) = self.immediate_commit_timestamp else {
+            return Ok(());
+        };
+        match self.original_commit_timestamp {
+            Some(original_commit_timestamp) => {
+                buf.write_uint::<LittleEndian>(
+                    immediate_commit_timestamp | ORIGINAL_COMMIT_TIMESTAMP_FLAG,
+                    7,
+                )?;
+                buf.write_uint::<LittleEndian>(original_commit_timestamp, 7)?;
+            }
+            None => buf.write_uint::<LittleEndian>(immediate_commit_timestamp, 7)?,
+        }
+
+        let Some(transaction_length) = self.transaction_length else {
+            return Ok(());
+        };
+        buf.write_packed_number(transaction_length as usize)?;
+
+        let Some(immediate_server_version) = self.immediate_server_version else {
+            return Ok(());
+        };
+        match self.original_server_version {
+            Some(original_server_version) => {
+                buf.write_u32::<LittleEndian>(
+                    immediate_server_version | ORIGINAL_SERVER_VERSION_FLAG,
+                )?;
+                buf.write_u32::<LittleEndian>(original_server_version)?;
+            }
+            None => buf.write_u32::<LittleEndian>(immediate_server_version)?,
+        }
+        Ok(())
     }
 
     pub fn read_uuid(cursor: &mut Cursor<&Vec<u8>>) -> Result<String, BinlogError> {
@@ -35,6 +140,17 @@ impl GtidEvent {
         ))
     }
 
+    pub fn write_uuid(buf: &mut Vec<u8>, uuid: &str) -> Result<(), BinlogError> {
+        let hex = uuid.replace('-', "");
+        if hex.len() != 32 || !hex.is_ascii() {
+            return Err(BinlogError::InvalidGtid(uuid.to_string()));
+        }
+        for i in (0..hex.len()).step_by(2) {
+            buf.write_u8(u8::from_str_radix(&hex[i..i + 2], 16)?)?;
+        }
+        Ok(())
+    }
+
     fn bytes_to_hex_string(
         cursor: &mut Cursor<&Vec<u8>>,
         byte_count: u8,
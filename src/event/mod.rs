@@ -1,14 +1,18 @@
 pub mod checksum_type;
 pub mod delete_rows_event;
 pub mod event_data;
+pub mod event_data_ref;
 pub mod event_header;
 pub mod event_type;
 pub mod format_description_event;
 pub mod gtid_event;
 pub mod previous_gtids_event;
 pub mod query_event;
+pub mod query_status_vars;
 pub mod rotate_event;
+pub mod row;
 pub mod row_event;
+pub mod row_event_ref;
 pub mod rows_query_event;
 pub mod table_map;
 pub mod table_map_event;
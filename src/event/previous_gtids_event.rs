@@ -1,6 +1,6 @@
 use super::gtid_event::GtidEvent;
 use crate::binlog_error::BinlogError;
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use serde::{Deserialize, Serialize};
 use std::io::Cursor;
 
@@ -25,6 +25,30 @@ impl PreviousGtidsEvent {
         })
     }
 
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), BinlogError> {
+        let uuid_sets: Vec<&str> = self
+            .gtid_set
+            .split(',')
+            .map(|uuid_set| uuid_set.trim())
+            .filter(|uuid_set| !uuid_set.is_empty())
+            .collect();
+        buf.write_u64::<LittleEndian>(uuid_sets.len() as u64)?;
+
+        for uuid_set in uuid_sets {
+            let mut parts = uuid_set.split(':');
+            GtidEvent::write_uuid(buf, parts.next().unwrap_or_default())?;
+            let intervals: Vec<&str> = parts.collect();
+            buf.write_u64::<LittleEndian>(intervals.len() as u64)?;
+            for interval in intervals {
+                let (start, end) = interval.split_once('-').unwrap_or((interval, interval));
+                buf.write_u64::<LittleEndian>(start.parse()?)?;
+                // the end is exclusive in binlog
+                buf.write_u64::<LittleEndian>(end.parse::<u64>()? + 1)?;
+            }
+        }
+        Ok(())
+    }
+
     fn read_interval(cursor: &mut Cursor<&Vec<u8>>) -> Result<String, BinlogError> {
         let interval_count = cursor.read_u64::<LittleEndian>()?;
         let mut intervals = Vec::with_capacity(interval_count as usize);
@@ -1,15 +1,22 @@
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::io::{Cursor, Read, Write};
 
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use serde::{Deserialize, Serialize};
 
-use crate::{binlog_error::BinlogError, ext::cursor_ext::CursorExt};
+use crate::{
+    binlog_error::BinlogError,
+    ext::{buf_ext::BufExt, cursor_ext::CursorExt},
+};
+
+use super::query_status_vars::QueryStatusVars;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct QueryEvent {
     pub thread_id: u32,
     pub exec_time: u32,
     pub error_code: u16,
+    /// Raw status variables (sql_mode, charset, time_zone ...) of the session.
+    pub status_vars: Vec<u8>,
     pub schema: String,
     pub query: String,
 }
@@ -22,10 +29,11 @@ impl QueryEvent {
         let exec_time = cursor.read_u32::<LittleEndian>()?;
         let schema_length = cursor.read_u8()?;
         let error_code = cursor.read_u16::<LittleEndian>()?;
-        let status_vars_length = cursor.read_u16::<LittleEndian>()? as i64;
+        let status_vars_length = cursor.read_u16::<LittleEndian>()? as usize;
 
-        // skip, Body for Query_event
-        cursor.seek(SeekFrom::Current(status_vars_length))?;
+        // Body for Query_event
+        let mut status_vars = vec![0u8; status_vars_length];
+        cursor.read_exact(&mut status_vars)?;
 
         // Format: schema_length + 1, The currently selected database, as a null-terminated string.
         let schema = cursor.read_string_without_terminator(schema_length as usize)?;
@@ -37,8 +45,26 @@ impl QueryEvent {
             thread_id,
             exec_time,
             error_code,
+            status_vars,
             schema,
             query,
         })
     }
+
+    /// Decodes the session variables of the status block.
+    pub fn parse_status_vars(&self) -> Result<QueryStatusVars, BinlogError> {
+        QueryStatusVars::parse(&self.status_vars)
+    }
+
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), BinlogError> {
+        buf.write_u32::<LittleEndian>(self.thread_id)?;
+        buf.write_u32::<LittleEndian>(self.exec_time)?;
+        buf.write_u8(self.schema.len() as u8)?;
+        buf.write_u16::<LittleEndian>(self.error_code)?;
+        buf.write_u16::<LittleEndian>(self.status_vars.len() as u16)?;
+        buf.write_all(&self.status_vars)?;
+        buf.write_null_terminated_string(&self.schema)?;
+        buf.write_all(self.query.as_bytes())?;
+        Ok(())
+    }
 }
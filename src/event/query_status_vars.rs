@@ -0,0 +1,213 @@
+use std::io::{Cursor, Seek, SeekFrom};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::{binlog_error::BinlogError, ext::cursor_ext::CursorExt};
+
+const Q_FLAGS2_CODE: u8 = 0;
+const Q_SQL_MODE_CODE: u8 = 1;
+const Q_CATALOG_CODE: u8 = 2;
+const Q_AUTO_INCREMENT: u8 = 3;
+const Q_CHARSET_CODE: u8 = 4;
+const Q_TIME_ZONE_CODE: u8 = 5;
+const Q_CATALOG_NZ_CODE: u8 = 6;
+const Q_LC_TIME_NAMES_CODE: u8 = 7;
+const Q_CHARSET_DATABASE_CODE: u8 = 8;
+const Q_TABLE_MAP_FOR_UPDATE_CODE: u8 = 9;
+const Q_MASTER_DATA_WRITTEN_CODE: u8 = 10;
+const Q_INVOKER: u8 = 11;
+const Q_UPDATED_DB_NAMES: u8 = 12;
+const Q_MICROSECONDS: u8 = 13;
+const Q_EXPLICIT_DEFAULTS_FOR_TIMESTAMP: u8 = 16;
+const Q_DDL_LOGGED_WITH_XID: u8 = 17;
+const Q_DEFAULT_COLLATION_FOR_UTF8MB4: u8 = 18;
+const Q_SQL_REQUIRE_PRIMARY_KEY: u8 = 19;
+const Q_DEFAULT_TABLE_ENCRYPTION: u8 = 20;
+/// MariaDB
+const Q_HRNOW: u8 = 128;
+/// MariaDB
+const Q_XID: u8 = 129;
+
+/// Database count of Q_UPDATED_DB_NAMES when the names are not logged.
+const OVER_MAX_DBS_IN_EVENT_MTS: u8 = 254;
+
+/// Session variables of the status block of a QueryEvent, None if not logged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryStatusVars {
+    /// Session options, such as foreign_key_checks and autocommit.
+    pub flags2: Option<u32>,
+    pub sql_mode: Option<u64>,
+    pub auto_increment_increment: Option<u16>,
+    pub auto_increment_offset: Option<u16>,
+    pub character_set_client: Option<u16>,
+    pub collation_connection: Option<u16>,
+    pub collation_server: Option<u16>,
+    pub time_zone: Option<String>,
+    pub lc_time_names: Option<u16>,
+    pub collation_database: Option<u16>,
+    pub updated_db_names: Option<Vec<String>>,
+    /// Fraction of the event timestamp.
+    pub microseconds: Option<u32>,
+    pub explicit_defaults_for_timestamp: Option<bool>,
+    pub ddl_xid: Option<u64>,
+    pub default_collation_for_utf8mb4: Option<u16>,
+    pub sql_require_primary_key: Option<u8>,
+    pub default_table_encryption: Option<u8>,
+}
+
+impl QueryStatusVars {
+    pub fn parse(status_vars: &[u8]) -> Result<Self, BinlogError> {
+        // refer: https://dev.mysql.com/doc/dev/mysql-server/latest/classbinary__log_1_1Query__event.html
+        let mut cursor = Cursor::new(status_vars);
+        let mut vars = Self::default();
+        while cursor.available() > 0 {
+            match cursor.read_u8()? {
+                Q_FLAGS2_CODE => vars.flags2 = Some(cursor.read_u32::<LittleEndian>()?),
+
+                Q_SQL_MODE_CODE => vars.sql_mode = Some(cursor.read_u64::<LittleEndian>()?),
+
+                Q_CATALOG_CODE => {
+                    // the catalog is followed by a null terminator
+                    let length = cursor.read_u8()? as i64;
+                    cursor.seek(SeekFrom::Current(length + 1))?;
+                }
+
+                Q_AUTO_INCREMENT => {
+                    vars.auto_increment_increment = Some(cursor.read_u16::<LittleEndian>()?);
+                    vars.auto_increment_offset = Some(cursor.read_u16::<LittleEndian>()?);
+                }
+
+                Q_CHARSET_CODE => {
+                    vars.character_set_client = Some(cursor.read_u16::<LittleEndian>()?);
+                    vars.collation_connection = Some(cursor.read_u16::<LittleEndian>()?);
+                    vars.collation_server = Some(cursor.read_u16::<LittleEndian>()?);
+                }
+
+                Q_TIME_ZONE_CODE => {
+                    let length = cursor.read_u8()? as usize;
+                    vars.time_zone = Some(cursor.read_string(length)?);
+                }
+
+                Q_CATALOG_NZ_CODE => {
+                    let length = cursor.read_u8()? as i64;
+                    cursor.seek(SeekFrom::Current(length))?;
+                }
+
+                Q_LC_TIME_NAMES_CODE => {
+                    vars.lc_time_names = Some(cursor.read_u16::<LittleEndian>()?)
+                }
+
+                Q_CHARSET_DATABASE_CODE => {
+                    vars.collation_database = Some(cursor.read_u16::<LittleEndian>()?)
+                }
+
+                Q_TABLE_MAP_FOR_UPDATE_CODE => {
+                    cursor.read_u64::<LittleEndian>()?;
+                }
+
+                Q_MASTER_DATA_WRITTEN_CODE => {
+                    cursor.read_u32::<LittleEndian>()?;
+                }
+
+                Q_INVOKER => {
+                    // user and host
+                    for _ in 0..2 {
+                        let length = cursor.read_u8()? as i64;
+                        cursor.seek(SeekFrom::Current(length))?;
+                    }
+                }
+
+                Q_UPDATED_DB_NAMES => {
+                    let count = cursor.read_u8()?;
+                    let mut names = Vec::new();
+                    if count != OVER_MAX_DBS_IN_EVENT_MTS {
+                        for _ in 0..count {
+                            names.push(cursor.read_null_terminated_string()?);
+                        }
+                    }
+                    vars.updated_db_names = Some(names);
+                }
+
+                Q_MICROSECONDS => vars.microseconds = Some(cursor.read_u24::<LittleEndian>()?),
+
+                Q_EXPLICIT_DEFAULTS_FOR_TIMESTAMP => {
+                    vars.explicit_defaults_for_timestamp = Some(cursor.read_u8()? != 0)
+                }
+
+                Q_DDL_LOGGED_WITH_XID => vars.ddl_xid = Some(cursor.read_u64::<LittleEndian>()?),
+
+                Q_DEFAULT_COLLATION_FOR_UTF8MB4 => {
+                    vars.default_collation_for_utf8mb4 = Some(cursor.read_u16::<LittleEndian>()?)
+                }
+
+                Q_SQL_REQUIRE_PRIMARY_KEY => vars.sql_require_primary_key = Some(cursor.read_u8()?),
+
+                Q_DEFAULT_TABLE_ENCRYPTION => {
+                    vars.default_table_encryption = Some(cursor.read_u8()?)
+                }
+
+                Q_HRNOW => {
+                    cursor.read_u24::<LittleEndian>()?;
+                }
+
+                Q_XID => {
+                    cursor.read_u64::<LittleEndian>()?;
+                }
+
+                // the length of an unknown variable is unknown, the server stops parsing too
+                _ => break,
+            }
+        }
+        Ok(vars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryStatusVars;
+
+    #[test]
+    fn test_parse() {
+        let mut buf = vec![0, 0, 0, 0, 0];
+        buf.extend_from_slice(&[1, 0x20, 0, 0xa0, 0x45, 0, 0, 0, 0]);
+        buf.extend_from_slice(&[6, 3, b'd', b'e', b'f']);
+        buf.extend_from_slice(&[3, 2, 0, 1, 0]);
+        buf.extend_from_slice(&[4, 0xff, 0, 0xff, 0, 0x21, 0]);
+        buf.extend_from_slice(&[5, 6, b'+', b'0', b'8', b':', b'0', b'0']);
+        buf.extend_from_slice(&[11, 4, b'r', b'o', b'o', b't', 1, b'%']);
+        buf.extend_from_slice(&[12, 2, b'd', b'b', b'1', 0, b'd', b'b', b'2', 0]);
+        buf.extend_from_slice(&[13, 0x40, 0xe2, 0x01]);
+        buf.extend_from_slice(&[16, 1]);
+        buf.extend_from_slice(&[17, 9, 0, 0, 0, 0, 0, 0, 0]);
+        buf.extend_from_slice(&[18, 0xff, 0]);
+        buf.extend_from_slice(&[19, 0]);
+        // an unknown variable stops the parsing
+        buf.extend_from_slice(&[99, 8, 0, 7, 0]);
+
+        let vars = QueryStatusVars::parse(&buf).unwrap();
+        assert_eq!(
+            vars,
+            QueryStatusVars {
+                flags2: Some(0),
+                sql_mode: Some(1168113696),
+                auto_increment_increment: Some(2),
+                auto_increment_offset: Some(1),
+                character_set_client: Some(255),
+                collation_connection: Some(255),
+                collation_server: Some(33),
+                time_zone: Some("+08:00".into()),
+                lc_time_names: None,
+                collation_database: None,
+                updated_db_names: Some(vec!["db1".into(), "db2".into()]),
+                microseconds: Some(123456),
+                explicit_defaults_for_timestamp: Some(true),
+                ddl_xid: Some(9),
+                default_collation_for_utf8mb4: Some(255),
+                sql_require_primary_key: Some(0),
+                default_table_encryption: None,
+            }
+        );
+
+        assert!(QueryStatusVars::parse(&[1, 0x20]).is_err());
+    }
+}
@@ -1,6 +1,6 @@
-use std::io::Cursor;
+use std::io::{Cursor, Write};
 
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use serde::{Deserialize, Serialize};
 
 use crate::{binlog_error::BinlogError, ext::cursor_ext::CursorExt};
@@ -20,4 +20,10 @@ impl RotateEvent {
             binlog_position,
         })
     }
+
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), BinlogError> {
+        buf.write_u64::<LittleEndian>(self.binlog_position)?;
+        buf.write_all(self.binlog_filename.as_bytes())?;
+        Ok(())
+    }
 }
@@ -0,0 +1,397 @@
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+use crate::column::{column_type::ColumnType, column_value::ColumnValue};
+
+use super::{
+    row_event::RowEvent, table_map::table_metadata::ColumnMetadata, table_map_event::TableMapEvent,
+};
+
+/// A view of a decoded row that joins each column value with its column metadata
+/// from the TableMapEvent, so columns can be accessed by name.
+#[derive(Debug, Clone)]
+pub struct Row<'a> {
+    pub database_name: &'a str,
+    pub table_name: &'a str,
+    pub columns: Vec<RowColumn<'a>>,
+}
+
+/// A single column value of a Row with its metadata.
+#[derive(Debug, Clone)]
+pub struct RowColumn<'a> {
+    /// Position of the column in the table, starting from 0.
+    pub index: usize,
+    /// Real column type, ENUM and SET columns are resolved from the column meta.
+    pub column_type: ColumnType,
+    /// Column meta from the TableMapEvent, e.g. precision and scale for DECIMAL.
+    pub column_meta: u16,
+    /// Whether the column is nullable.
+    pub is_nullable: bool,
+    /// Column metadata, only available for MySQL 8.0.1+ with binlog_row_metadata=FULL.
+    pub metadata: Option<&'a ColumnMetadata>,
+    /// Charset collation for character columns, resolved from the column charset or the
+    /// default charset of the table.
+    pub charset_collation: Option<u32>,
+    pub value: &'a ColumnValue,
+}
+
+impl<'a> Row<'a> {
+    pub fn new(row_event: &'a RowEvent, table_map_event: &'a TableMapEvent) -> Self {
+        let mut columns = Vec::with_capacity(row_event.column_values.len());
+        let mut character_column_index = 0;
+        for (i, value) in row_event.column_values.iter().enumerate() {
+            let column_type = table_map_event.get_column_type(i);
+            let metadata = table_map_event.get_column_metadata(i);
+
+            let mut charset_collation = None;
+            if is_character_type(column_type) {
+                charset_collation =
+                    Self::get_column_charset(table_map_event, character_column_index).or_else(
+                        || Self::get_default_charset(table_map_event, character_column_index),
+                    );
+                character_column_index += 1;
+            }
+
+            columns.push(RowColumn {
+                index: i,
+                column_type,
+                column_meta: table_map_event.column_metas[i],
+                is_nullable: table_map_event.null_bits.get(i).copied().unwrap_or(true),
+                metadata,
+                charset_collation,
+                value,
+            });
+        }
+
+        Self {
+            database_name: &table_map_event.database_name,
+            table_name: &table_map_event.table_name,
+            columns,
+        }
+    }
+
+    /// Gets the value of the column by name, returns None if no column has the name,
+    /// which is always the case if the TableMapEvent contains no column names.
+    pub fn get(&self, name: &str) -> Option<&'a ColumnValue> {
+        self.get_column(name).map(|column| column.value)
+    }
+
+    pub fn get_column(&self, name: &str) -> Option<&RowColumn<'a>> {
+        self.columns
+            .iter()
+            .find(|column| column.name() == Some(name))
+    }
+
+    /// Gets the primary key columns, empty if the table has no primary key
+    /// or the TableMapEvent contains no primary key metadata.
+    pub fn primary_key(&self) -> Vec<&RowColumn<'a>> {
+        self.columns
+            .iter()
+            .filter(|column| column.is_primary_key())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    fn get_column_charset(
+        table_map_event: &TableMapEvent,
+        character_column_index: u32,
+    ) -> Option<u32> {
+        // COLUMN_CHARSET lists the charsets of character columns only, in column order,
+        // while they are stored from the first column of the table metadata
+        table_map_event
+            .table_metadata
+            .as_ref()?
+            .columns
+            .get(character_column_index as usize)?
+            .charset_collation
+    }
+
+    fn get_default_charset(
+        table_map_event: &TableMapEvent,
+        character_column_index: u32,
+    ) -> Option<u32> {
+        let default_charset = table_map_event
+            .table_metadata
+            .as_ref()?
+            .default_charset
+            .as_ref()?;
+        let charset_collation = default_charset
+            .charset_collations
+            .iter()
+            .find(|(index, _)| *index == character_column_index)
+            .map(|(_, collation)| *collation)
+            .unwrap_or(default_charset.default_charset_collation);
+        Some(charset_collation)
+    }
+}
+
+impl<'a> RowColumn<'a> {
+    pub fn name(&self) -> Option<&'a str> {
+        self.metadata.and_then(|m| m.column_name.as_deref())
+    }
+
+    /// Gets the column name, or the positional name like mysqlbinlog (@1, @2...)
+    /// if the TableMapEvent contains no column names.
+    pub fn name_or_position(&self) -> String {
+        match self.name() {
+            Some(name) => name.to_string(),
+            None => format!("@{}", self.index + 1),
+        }
+    }
+
+    pub fn is_signed(&self) -> Option<bool> {
+        self.metadata.and_then(|m| m.is_signed)
+    }
+
+    pub fn is_primary_key(&self) -> bool {
+        self.metadata.is_some_and(|m| {
+            m.is_simple_primary_key == Some(true) || m.primary_key_prefix.is_some()
+        })
+    }
+
+    /// Whether the value is binary data rather than text, decided by the binary collation (63),
+    /// if the collation is unknown, BLOB / GEOMETRY columns are considered binary.
+    pub fn is_binary(&self) -> bool {
+        match self.charset_collation {
+            Some(collation) => collation == BINARY_COLLATION,
+            None => matches!(
+                self.column_type,
+                ColumnType::Blob
+                    | ColumnType::TinyBlob
+                    | ColumnType::MediumBlob
+                    | ColumnType::LongBlob
+                    | ColumnType::Geometry
+            ),
+        }
+    }
+
+    /// Gets the string value of an ENUM column, requires ENUM string values in column metadata.
+    pub fn enum_string(&self) -> Option<&'a str> {
+        let index = match self.value {
+            ColumnValue::Enum(index) => *index as usize,
+            _ => return None,
+        };
+        let values = self.metadata?.enum_string_values.as_ref()?;
+        // index 0 is reserved for the empty string of invalid values
+        if index == 0 {
+            return Some("");
+        }
+        values.get(index - 1).map(|v| v.as_str())
+    }
+
+    /// Gets the string values of a SET column, requires SET string values in column metadata.
+    pub fn set_strings(&self) -> Option<Vec<&'a str>> {
+        let bits = match self.value {
+            ColumnValue::Set(bits) => *bits,
+            _ => return None,
+        };
+        let values = self.metadata?.set_string_values.as_ref()?;
+        Some(
+            values
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i < 64 && bits & (1 << i) != 0)
+                .map(|(_, v)| v.as_str())
+                .collect(),
+        )
+    }
+}
+
+const BINARY_COLLATION: u32 = 63;
+
+impl Serialize for Row<'_> {
+    /// Serializes the row as a map of column name -> column value.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+        for column in self.columns.iter() {
+            map.serialize_entry(&column.name_or_position(), column.value)?;
+        }
+        map.end()
+    }
+}
+
+fn is_character_type(column_type: ColumnType) -> bool {
+    // refer: https://github.com/mysql/mysql-server/blob/8.0/libbinlogevents/include/rows_event.h
+    matches!(
+        column_type,
+        ColumnType::String
+            | ColumnType::VarString
+            | ColumnType::VarChar
+            | ColumnType::Blob
+            | ColumnType::TinyBlob
+            | ColumnType::MediumBlob
+            | ColumnType::LongBlob
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::table_map::{default_charset::DefaultCharset, table_metadata::TableMetadata};
+
+    fn create_table_map_event(with_metadata: bool) -> TableMapEvent {
+        let column_metadata = |name: &str, is_primary_key: bool| ColumnMetadata {
+            column_name: Some(name.to_string()),
+            is_simple_primary_key: is_primary_key.then_some(true),
+            ..Default::default()
+        };
+
+        let table_metadata = TableMetadata {
+            default_charset: Some(DefaultCharset::new(255, vec![(1, 33)])),
+            enum_and_set_default_charset: None,
+            columns: vec![
+                column_metadata("id", true),
+                column_metadata("name", false),
+                column_metadata("email", false),
+                column_metadata("status", false),
+            ],
+        };
+
+        TableMapEvent {
+            table_id: 1,
+            flags: 1,
+            database_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            // LONG, VARCHAR, VARCHAR, STRING(ENUM)
+            column_types: vec![3, 15, 15, 254],
+            column_metas: vec![0, 80, 400, (247 << 8) | 1],
+            null_bits: vec![false, true, true, true],
+            table_metadata: with_metadata.then_some(table_metadata),
+        }
+    }
+
+    fn create_row_event() -> RowEvent {
+        RowEvent {
+            column_values: vec![
+                ColumnValue::Long(1),
+                ColumnValue::String(b"abc".to_vec()),
+                ColumnValue::String(b"abc@example.com".to_vec()),
+                ColumnValue::Enum(1),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_get_by_name() {
+        let table_map_event = create_table_map_event(true);
+        let row_event = create_row_event();
+        let row = Row::new(&row_event, &table_map_event);
+
+        assert_eq!(row.len(), 4);
+        assert_eq!(row.database_name, "db_1");
+        assert_eq!(row.table_name, "tb_1");
+        assert_eq!(
+            row.get("email"),
+            Some(&ColumnValue::String(b"abc@example.com".to_vec()))
+        );
+        assert_eq!(row.get("not_exist"), None);
+
+        let status = row.get_column("status").unwrap();
+        assert_eq!(status.column_type, ColumnType::Enum);
+        assert!(status.is_nullable);
+
+        let id = row.get_column("id").unwrap();
+        assert!(!id.is_nullable);
+        assert!(id.is_primary_key());
+        let primary_key: Vec<&str> = row
+            .primary_key()
+            .iter()
+            .map(|column| column.name().unwrap())
+            .collect();
+        assert_eq!(primary_key, vec!["id"]);
+    }
+
+    #[test]
+    fn test_charset_collation() {
+        let table_map_event = create_table_map_event(true);
+        let row_event = create_row_event();
+        let row = Row::new(&row_event, &table_map_event);
+
+        assert_eq!(row.columns[0].charset_collation, None);
+        assert_eq!(row.columns[1].charset_collation, Some(255));
+        assert_eq!(row.columns[2].charset_collation, Some(33));
+        assert_eq!(row.columns[3].charset_collation, None);
+    }
+
+    #[test]
+    fn test_column_charset_collation() {
+        let mut table_map_event = create_table_map_event(true);
+        let table_metadata = table_map_event.table_metadata.as_mut().unwrap();
+        table_metadata.default_charset = None;
+        // COLUMN_CHARSET of (id INT, name VARCHAR, email VARCHAR, status ENUM)
+        // only has the charsets of name and email
+        table_metadata.columns[0].charset_collation = Some(63);
+        table_metadata.columns[1].charset_collation = Some(45);
+        let row_event = create_row_event();
+        let row = Row::new(&row_event, &table_map_event);
+
+        assert_eq!(row.columns[0].charset_collation, None);
+        assert_eq!(row.columns[1].charset_collation, Some(63));
+        assert!(row.columns[1].is_binary());
+        assert_eq!(row.columns[2].charset_collation, Some(45));
+        assert!(!row.columns[2].is_binary());
+        assert_eq!(row.columns[3].charset_collation, None);
+    }
+
+    #[test]
+    fn test_enum_and_set_strings() {
+        let mut table_map_event = create_table_map_event(true);
+        let table_metadata = table_map_event.table_metadata.as_mut().unwrap();
+        table_metadata.columns[3].enum_string_values = Some(vec!["on".into(), "off".into()]);
+        let row_event = create_row_event();
+        let row = Row::new(&row_event, &table_map_event);
+        assert_eq!(row.columns[3].enum_string(), Some("on"));
+        assert_eq!(row.columns[0].enum_string(), None);
+
+        let set_metadata = ColumnMetadata {
+            set_string_values: Some(vec!["a".into(), "b".into(), "c".into()]),
+            ..Default::default()
+        };
+        let value = ColumnValue::Set(0b101);
+        let column = RowColumn {
+            index: 0,
+            column_type: ColumnType::Set,
+            column_meta: 0,
+            is_nullable: true,
+            metadata: Some(&set_metadata),
+            charset_collation: None,
+            value: &value,
+        };
+        assert_eq!(column.set_strings(), Some(vec!["a", "c"]));
+    }
+
+    #[test]
+    fn test_without_metadata() {
+        let table_map_event = create_table_map_event(false);
+        let row_event = create_row_event();
+        let row = Row::new(&row_event, &table_map_event);
+
+        assert_eq!(row.get("id"), None);
+        assert!(row.primary_key().is_empty());
+        assert_eq!(row.columns[0].name_or_position(), "@1");
+        assert_eq!(row.columns[3].name_or_position(), "@4");
+    }
+
+    #[test]
+    fn test_serialize() {
+        let table_map_event = create_table_map_event(true);
+        let row_event = create_row_event();
+        let row = Row::new(&row_event, &table_map_event);
+        assert_eq!(
+            serde_json::to_string(&row).unwrap(),
+            r#"{"id":{"Long":1},"name":{"String":[97,98,99]},"email":{"String":[97,98,99,64,101,120,97,109,112,108,101,46,99,111,109]},"status":{"Enum":1}}"#
+        );
+
+        let table_map_event = create_table_map_event(false);
+        let row = Row::new(&row_event, &table_map_event);
+        assert!(serde_json::to_string(&row)
+            .unwrap()
+            .starts_with(r#"{"@1":{"Long":1},"@2":"#));
+    }
+}
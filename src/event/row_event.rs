@@ -4,8 +4,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     binlog_error::BinlogError,
-    column::{column_type::ColumnType, column_value::ColumnValue},
-    ext::cursor_ext::CursorExt,
+    column::column_value::ColumnValue,
+    ext::{buf_ext::BufExt, cursor_ext::CursorExt},
 };
 
 use super::table_map_event::TableMapEvent;
@@ -38,24 +38,44 @@ impl RowEvent {
                 continue;
             }
 
-            let column_meta = table_map_event.column_metas[i];
-            let mut column_type = table_map_event.column_types[i];
-            let mut column_length = column_meta;
+            let (column_type, column_meta, column_length) =
+                table_map_event.get_column_decode_info(i)?;
+            let col_value = ColumnValue::parse(cursor, column_type, column_meta, column_length)?;
+            column_values.push(col_value);
+        }
+
+        Ok(Self { column_values })
+    }
 
-            if column_type == ColumnType::String as u8 && column_meta >= 256 {
-                (column_type, column_length) =
-                    ColumnType::parse_string_column_meta(column_meta, column_type)?;
+    pub fn encode(
+        &self,
+        buf: &mut Vec<u8>,
+        table_map_event: &TableMapEvent,
+        included_columns: &[bool],
+        legacy_bitmap_padding: bool,
+    ) -> Result<(), BinlogError> {
+        // the null bitmap is indexed by the included columns, the same as parse
+        let mut null_columns = vec![false; included_columns.len()];
+        let mut values = Vec::new();
+        let mut skipped_column_count = 0;
+        for i in 0..table_map_event.column_types.len() {
+            if !included_columns[i] {
+                skipped_column_count += 1;
+                continue;
             }
 
-            let col_value = ColumnValue::parse(
-                cursor,
-                ColumnType::from_code(column_type),
-                column_meta,
-                column_length,
-            )?;
-            column_values.push(col_value);
+            match self.column_values.get(i) {
+                None | Some(ColumnValue::None) => null_columns[i - skipped_column_count] = true,
+                Some(column_value) => values.push((i, column_value)),
+            }
         }
 
-        Ok(Self { column_values })
+        buf.write_bits_with_padding(&null_columns, legacy_bitmap_padding)?;
+        for (i, column_value) in values {
+            let (column_type, column_meta, column_length) =
+                table_map_event.get_column_decode_info(i)?;
+            column_value.encode(buf, column_type, column_meta, column_length)?;
+        }
+        Ok(())
     }
 }
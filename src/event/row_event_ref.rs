@@ -0,0 +1,118 @@
+use std::io::Cursor;
+
+use crate::{
+    binlog_error::BinlogError,
+    column::{column_value::ColumnValue, column_value_ref::ColumnValueRef},
+    ext::cursor_ext::CursorExt,
+};
+
+use super::{row_event::RowEvent, table_map_event::TableMapEvent};
+
+/// A row borrowed from the event buffer, only the offsets of column values are
+/// located when parsing, each column value is decoded when it is accessed.
+#[derive(Debug, Clone)]
+pub struct RowEventRef<'a> {
+    buf: &'a [u8],
+    table_map_event: &'a TableMapEvent,
+    // offsets of column values in buf, None for NULL and excluded columns
+    offsets: Vec<Option<usize>>,
+}
+
+impl<'a> RowEventRef<'a> {
+    #[allow(clippy::needless_range_loop)]
+    pub fn parse(
+        cursor: &mut Cursor<&'a [u8]>,
+        table_map_event: &'a TableMapEvent,
+        included_columns: &[bool],
+    ) -> Result<Self, BinlogError> {
+        let null_columns = cursor.read_bits(included_columns.len(), false)?;
+        let mut offsets = Vec::with_capacity(table_map_event.column_types.len());
+        let mut skipped_column_count = 0;
+        for i in 0..table_map_event.column_types.len() {
+            if !included_columns[i] {
+                skipped_column_count += 1;
+                offsets.push(None);
+                continue;
+            }
+
+            let index = i - skipped_column_count;
+            if null_columns[index] {
+                offsets.push(None);
+                continue;
+            }
+
+            offsets.push(Some(cursor.position() as usize));
+            let (column_type, column_meta, column_length) =
+                table_map_event.get_column_decode_info(i)?;
+            ColumnValueRef::skip(cursor, column_type, column_meta, column_length)?;
+        }
+
+        Ok(Self {
+            buf: cursor.get_ref(),
+            table_map_event,
+            offsets,
+        })
+    }
+
+    /// Decodes the value of the column at `index`.
+    pub fn get(&self, index: usize) -> Result<ColumnValueRef<'a>, BinlogError> {
+        let offset = match self.offsets.get(index) {
+            Some(Some(offset)) => *offset,
+            Some(None) => return Ok(ColumnValueRef::None),
+            None => {
+                return Err(BinlogError::UnexpectedData(format!(
+                    "column index {} out of range, column count: {}",
+                    index,
+                    self.offsets.len()
+                )))
+            }
+        };
+
+        let (column_type, column_meta, column_length) =
+            self.table_map_event.get_column_decode_info(index)?;
+        let mut cursor = Cursor::new(self.buf);
+        cursor.set_position(offset as u64);
+        ColumnValueRef::parse(&mut cursor, column_type, column_meta, column_length)
+    }
+
+    /// Decodes the value of the column by name, requires column names in TableMetadata.
+    pub fn get_by_name(&self, name: &str) -> Result<Option<ColumnValueRef<'a>>, BinlogError> {
+        let table_metadata = match &self.table_map_event.table_metadata {
+            Some(table_metadata) => table_metadata,
+            None => return Ok(None),
+        };
+
+        let index = table_metadata
+            .columns
+            .iter()
+            .position(|column| column.column_name.as_deref() == Some(name));
+        match index {
+            Some(index) => Ok(Some(self.get(index)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Decodes all column values.
+    pub fn values(&self) -> Result<Vec<ColumnValueRef<'a>>, BinlogError> {
+        (0..self.len()).map(|i| self.get(i)).collect()
+    }
+
+    pub fn to_row_event(&self) -> Result<RowEvent, BinlogError> {
+        let column_values = (0..self.len())
+            .map(|i| self.get(i).map(ColumnValue::from))
+            .collect::<Result<Vec<ColumnValue>, BinlogError>>()?;
+        Ok(RowEvent { column_values })
+    }
+
+    pub fn table_map_event(&self) -> &'a TableMapEvent {
+        self.table_map_event
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+}
@@ -1,6 +1,7 @@
+use byteorder::WriteBytesExt;
 use serde::{Deserialize, Serialize};
 
-use std::io::{Cursor, Seek, SeekFrom};
+use std::io::{Cursor, Seek, SeekFrom, Write};
 
 use crate::{binlog_error::BinlogError, ext::cursor_ext::CursorExt};
 
@@ -18,4 +19,10 @@ impl RowsQueryEvent {
         let query = cursor.read_string(cursor.get_ref().len() - 1)?;
         Ok(Self { query })
     }
+
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), BinlogError> {
+        buf.write_u8(self.query.len().min(u8::MAX as usize) as u8)?;
+        buf.write_all(self.query.as_bytes())?;
+        Ok(())
+    }
 }
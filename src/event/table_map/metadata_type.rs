@@ -14,6 +14,7 @@ pub(super) enum MetadataType {
     EnumAndSetDefaultCharset = 10,
     EnumAndSetColumnCharset = 11,
     ColumnVisibility = 12,
+    VectorDimensionality = 13,
 }
 
 impl MetadataType {
@@ -31,6 +32,7 @@ impl MetadataType {
             10 => MetadataType::EnumAndSetDefaultCharset,
             11 => MetadataType::EnumAndSetColumnCharset,
             12 => MetadataType::ColumnVisibility,
+            13 => MetadataType::VectorDimensionality,
             _ => {
                 return Err(BinlogError::UnexpectedData(format!(
                     "Table metadata type {} is not supported",
@@ -1,10 +1,12 @@
-use std::io::{Cursor, Read};
+use std::io::{Cursor, Read, Write};
 
-use byteorder::ReadBytesExt;
+use byteorder::{ReadBytesExt, WriteBytesExt};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    binlog_error::BinlogError, column::column_type::ColumnType, ext::cursor_ext::CursorExt,
+    binlog_error::BinlogError,
+    column::column_type::ColumnType,
+    ext::{buf_ext::BufExt, cursor_ext::CursorExt},
 };
 
 use super::{default_charset::DefaultCharset, metadata_type::MetadataType};
@@ -41,6 +43,9 @@ pub struct ColumnMetadata {
 
     /// Column visibility (for MySQL 8.0+ invisible columns).
     pub is_visible: Option<bool>,
+
+    /// Dimensionality of VECTOR column (for MySQL 9.0+).
+    pub vector_dimensionality: Option<u32>,
 }
 
 /// Contains metadata for table columns.
@@ -123,6 +128,9 @@ impl TableMetadata {
                     let visibility = read_bitmap_reverted(&mut buffer, column_types.len())?;
                     apply_column_visibility(&mut columns, &visibility);
                 }
+                MetadataType::VectorDimensionality => {
+                    parse_vector_dimensionalities(&mut columns, &mut buffer, column_types)?;
+                }
             }
         }
 
@@ -132,6 +140,165 @@ impl TableMetadata {
             columns,
         })
     }
+
+    /// Writes the metadata fields in the ascending order of their types as MySQL does,
+    /// primary key columns are written in the column order since the key order is not kept.
+    pub fn encode(
+        &self,
+        buf: &mut Vec<u8>,
+        column_types: &[u8],
+        column_metas: &[u16],
+    ) -> Result<(), BinlogError> {
+        let columns = &self.columns;
+        let mut field = Vec::new();
+
+        let signedness: Vec<bool> = column_types
+            .iter()
+            .zip(columns)
+            .filter(|(column_type, _)| is_numeric_type(ColumnType::from_code(**column_type)))
+            .filter_map(|(_, column)| column.is_signed)
+            .collect();
+        if !signedness.is_empty() {
+            write_bitmap_reverted(&mut field, &signedness)?;
+            write_field(buf, MetadataType::Signedness, &mut field)?;
+        }
+
+        if let Some(default_charset) = &self.default_charset {
+            write_default_charset(&mut field, default_charset)?;
+            write_field(buf, MetadataType::DefaultCharset, &mut field)?;
+        }
+
+        for charset in columns.iter().map_while(|column| column.charset_collation) {
+            field.write_packed_number(charset as usize)?;
+        }
+        write_field(buf, MetadataType::ColumnCharset, &mut field)?;
+
+        for name in columns
+            .iter()
+            .map_while(|column| column.column_name.as_ref())
+        {
+            field.write_packed_number(name.len())?;
+            field.write_all(name.as_bytes())?;
+        }
+        write_field(buf, MetadataType::ColumnName, &mut field)?;
+
+        for (i, column) in columns.iter().enumerate() {
+            if let Some(values) = &column.set_string_values {
+                if is_set_column(column_types[i], column_metas[i]) {
+                    write_string_values(&mut field, values)?;
+                }
+            }
+        }
+        write_field(buf, MetadataType::SetStrValue, &mut field)?;
+
+        for (i, column) in columns.iter().enumerate() {
+            if let Some(values) = &column.enum_string_values {
+                if is_enum_column(column_types[i], column_metas[i]) {
+                    write_string_values(&mut field, values)?;
+                }
+            }
+        }
+        write_field(buf, MetadataType::EnumStrValue, &mut field)?;
+
+        for geometry_type in columns.iter().map_while(|column| column.geometry_type) {
+            field.write_packed_number(geometry_type as usize)?;
+        }
+        write_field(buf, MetadataType::GeometryType, &mut field)?;
+
+        for (i, column) in columns.iter().enumerate() {
+            if column.is_simple_primary_key == Some(true) && column.primary_key_prefix.is_none() {
+                field.write_packed_number(i)?;
+            }
+        }
+        write_field(buf, MetadataType::SimplePrimaryKey, &mut field)?;
+
+        for (i, column) in columns.iter().enumerate() {
+            if let Some(prefix) = column.primary_key_prefix {
+                field.write_packed_number(i)?;
+                field.write_packed_number(prefix as usize)?;
+            }
+        }
+        write_field(buf, MetadataType::PrimaryKeyWithPrefix, &mut field)?;
+
+        if let Some(default_charset) = &self.enum_and_set_default_charset {
+            write_default_charset(&mut field, default_charset)?;
+            write_field(buf, MetadataType::EnumAndSetDefaultCharset, &mut field)?;
+        }
+
+        for charset in columns
+            .iter()
+            .map_while(|column| column.enum_and_set_charset_collation)
+        {
+            field.write_packed_number(charset as usize)?;
+        }
+        write_field(buf, MetadataType::EnumAndSetColumnCharset, &mut field)?;
+
+        if columns.iter().any(|column| column.is_visible.is_some()) {
+            let visibility: Vec<bool> = columns
+                .iter()
+                .map(|column| column.is_visible.unwrap_or(true))
+                .collect();
+            write_bitmap_reverted(&mut field, &visibility)?;
+            write_field(buf, MetadataType::ColumnVisibility, &mut field)?;
+        }
+
+        for (i, column) in columns.iter().enumerate() {
+            if ColumnType::from_code(column_types[i]) == ColumnType::Vector {
+                if let Some(dimensionality) = column.vector_dimensionality {
+                    field.write_packed_number(dimensionality as usize)?;
+                }
+            }
+        }
+        write_field(buf, MetadataType::VectorDimensionality, &mut field)?;
+        Ok(())
+    }
+}
+
+/// Writes a metadata field and clears its content, empty fields are omitted.
+fn write_field(
+    buf: &mut Vec<u8>,
+    metadata_type: MetadataType,
+    field: &mut Vec<u8>,
+) -> Result<(), BinlogError> {
+    if field.is_empty() {
+        return Ok(());
+    }
+    buf.write_u8(metadata_type as u8)?;
+    buf.write_packed_number(field.len())?;
+    buf.append(field);
+    Ok(())
+}
+
+fn write_default_charset(
+    buf: &mut Vec<u8>,
+    default_charset: &DefaultCharset,
+) -> Result<(), BinlogError> {
+    buf.write_packed_number(default_charset.default_charset_collation as usize)?;
+    for (key, value) in &default_charset.charset_collations {
+        buf.write_packed_number(*key as usize)?;
+        buf.write_packed_number(*value as usize)?;
+    }
+    Ok(())
+}
+
+fn write_string_values(buf: &mut Vec<u8>, values: &[String]) -> Result<(), BinlogError> {
+    buf.write_packed_number(values.len())?;
+    for value in values {
+        buf.write_packed_number(value.len())?;
+        buf.write_all(value.as_bytes())?;
+    }
+    Ok(())
+}
+
+fn write_bitmap_reverted(buf: &mut Vec<u8>, bits: &[bool]) -> Result<(), BinlogError> {
+    let mut bytes = vec![0u8; bits.len().div_ceil(8)];
+    for (i, bit) in bits.iter().enumerate() {
+        if *bit {
+            bytes[i >> 3] |= 1 << (7 - i % 8);
+        }
+    }
+    buf.write_all(&bytes)?;
+    Ok(())
 }
 
 fn parse_default_charset(cursor: &mut Cursor<&Vec<u8>>) -> Result<DefaultCharset, BinlogError> {
@@ -153,7 +320,7 @@ pub(crate) fn read_bitmap_reverted(
     bits_number: usize,
 ) -> Result<Vec<bool>, BinlogError> {
     let mut result = vec![false; bits_number];
-    let bytes_number = (bits_number + 7) / 8;
+    let bytes_number = bits_number.div_ceil(8);
     for i in 0..bytes_number {
         let value = cursor.read_u8()?;
         for y in 0..8 {
@@ -348,6 +515,23 @@ fn apply_column_visibility(columns: &mut [ColumnMetadata], visibility: &[bool])
     }
 }
 
+fn parse_vector_dimensionalities(
+    columns: &mut [ColumnMetadata],
+    cursor: &mut Cursor<&Vec<u8>>,
+    column_types: &[u8],
+) -> Result<(), BinlogError> {
+    // VectorDimensionality metadata is provided in the order of VECTOR columns
+    for (i, &column_type_code) in column_types.iter().enumerate() {
+        if cursor.available() == 0 {
+            break;
+        }
+        if ColumnType::from_code(column_type_code) == ColumnType::Vector {
+            columns[i].vector_dimensionality = Some(cursor.read_packed_number()? as u32);
+        }
+    }
+    Ok(())
+}
+
 fn is_numeric_type(column_type: ColumnType) -> bool {
     matches!(
         column_type,
@@ -604,9 +788,31 @@ mod tests {
             assert_eq!(column.primary_key_prefix, None);
             assert_eq!(column.enum_and_set_charset_collation, None);
             assert_eq!(column.is_visible, None);
+            assert_eq!(column.vector_dimensionality, None);
         }
     }
 
+    #[test]
+    fn test_parse_vector_dimensionality_metadata() {
+        let test_data = vec![
+            13, // MetadataType::VectorDimensionality
+            4,  // Length of metadata
+            3,  // Dimensionality of the first VECTOR column
+            252, 0, 4, // Dimensionality of the second VECTOR column: 1024 (packed number)
+        ];
+
+        // LONG, VECTOR, VARCHAR, VECTOR
+        let column_types = vec![3, 242, 15, 242];
+        let column_metas = vec![0, 4, 80, 4];
+        let mut cursor = Cursor::new(&test_data);
+        let result = TableMetadata::parse(&mut cursor, &column_types, &column_metas).unwrap();
+
+        assert_eq!(result.columns[0].vector_dimensionality, None);
+        assert_eq!(result.columns[1].vector_dimensionality, Some(3));
+        assert_eq!(result.columns[2].vector_dimensionality, None);
+        assert_eq!(result.columns[3].vector_dimensionality, Some(1024));
+    }
+
     #[test]
     fn test_read_bitmap_reverted() {
         // Test the bitmap reading with reverted bits
@@ -615,11 +821,11 @@ mod tests {
         let result = read_bitmap_reverted(&mut cursor, 5).unwrap();
 
         assert_eq!(result.len(), 5);
-        assert_eq!(result[0], true); // bit 7
-        assert_eq!(result[1], true); // bit 6
-        assert_eq!(result[2], false); // bit 5
-        assert_eq!(result[3], true); // bit 4
-        assert_eq!(result[4], false); // bit 3
+        assert!(result[0]); // bit 7
+        assert!(result[1]); // bit 6
+        assert!(!result[2]); // bit 5
+        assert!(result[3]); // bit 4
+        assert!(!result[4]); // bit 3
     }
 
     #[test]
@@ -635,5 +841,6 @@ mod tests {
         assert_eq!(column.primary_key_prefix, None);
         assert_eq!(column.enum_and_set_charset_collation, None);
         assert_eq!(column.is_visible, None);
+        assert_eq!(column.vector_dimensionality, None);
     }
 }
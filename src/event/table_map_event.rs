@@ -1,16 +1,19 @@
-use std::io::{Cursor, Read};
+use std::io::{Cursor, Read, Write};
 
-use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    binlog_error::BinlogError, column::column_type::ColumnType,
-    event::table_map::table_metadata::TableMetadata, ext::cursor_ext::CursorExt,
+    binlog_error::BinlogError,
+    column::column_type::ColumnType,
+    event::table_map::table_metadata::{ColumnMetadata, TableMetadata},
+    ext::{buf_ext::BufExt, cursor_ext::CursorExt},
 };
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct TableMapEvent {
     pub table_id: u64,
+    pub flags: u16,
     pub database_name: String,
     pub table_name: String,
     pub column_types: Vec<u8>,
@@ -26,8 +29,8 @@ impl TableMapEvent {
         // table_id
         let table_id = cursor.read_u48::<LittleEndian>()?;
 
-        // flags, Reserved for future use; currently always 0.
-        let _flags = cursor.read_u16::<LittleEndian>();
+        // flags
+        let flags = cursor.read_u16::<LittleEndian>()?;
 
         // database_name
         let database_name_length = cursor.read_u8()?;
@@ -61,6 +64,7 @@ impl TableMapEvent {
 
         Ok(Self {
             table_id,
+            flags,
             database_name,
             table_name,
             column_types,
@@ -70,6 +74,67 @@ impl TableMapEvent {
         })
     }
 
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), BinlogError> {
+        buf.write_u48::<LittleEndian>(self.table_id)?;
+        buf.write_u16::<LittleEndian>(self.flags)?;
+        buf.write_u8(self.database_name.len() as u8)?;
+        buf.write_null_terminated_string(&self.database_name)?;
+        buf.write_u8(self.table_name.len() as u8)?;
+        buf.write_null_terminated_string(&self.table_name)?;
+        buf.write_packed_number(self.column_types.len())?;
+        buf.write_all(&self.column_types)?;
+
+        let mut metadatas = Vec::new();
+        Self::write_metadatas(&mut metadatas, &self.column_types, &self.column_metas)?;
+        buf.write_packed_number(metadatas.len())?;
+        buf.write_all(&metadatas)?;
+
+        buf.write_bits(&self.null_bits, false)?;
+        if let Some(table_metadata) = &self.table_metadata {
+            table_metadata.encode(buf, &self.column_types, &self.column_metas)?;
+        }
+        Ok(())
+    }
+
+    /// Gets the real type of the column at `index`, ENUM and SET columns are logged as
+    /// STRING in binlog and are resolved from the column meta.
+    pub fn get_column_type(&self, index: usize) -> ColumnType {
+        let column_type = self.column_types[index];
+        let column_meta = self.column_metas[index];
+        match ColumnType::parse_string_column_meta(column_meta, column_type) {
+            Ok((real_column_type, _)) => ColumnType::from_code(real_column_type),
+            Err(_) => ColumnType::from_code(column_type),
+        }
+    }
+
+    /// Gets the (column_type, column_meta, column_length) used to decode values of the column at `index`.
+    pub(crate) fn get_column_decode_info(
+        &self,
+        index: usize,
+    ) -> Result<(ColumnType, u16, u16), BinlogError> {
+        let column_meta = self.column_metas[index];
+        let mut column_type = self.column_types[index];
+        let mut column_length = column_meta;
+
+        if column_type == ColumnType::String as u8 && column_meta >= 256 {
+            (column_type, column_length) =
+                ColumnType::parse_string_column_meta(column_meta, column_type)?;
+        }
+        Ok((
+            ColumnType::from_code(column_type),
+            column_meta,
+            column_length,
+        ))
+    }
+
+    /// Gets the metadata of the column at `index`, only available for MySQL 8.0.1+
+    /// with binlog_row_metadata=FULL (or MINIMAL for a subset of fields).
+    pub fn get_column_metadata(&self, index: usize) -> Option<&ColumnMetadata> {
+        self.table_metadata
+            .as_ref()
+            .and_then(|table_metadata| table_metadata.columns.get(index))
+    }
+
     fn read_metadatas(
         cursor: &mut Cursor<&Vec<u8>>,
         column_types: &Vec<u8>,
@@ -84,6 +149,7 @@ impl TableMapEvent {
                 | ColumnType::MediumBlob
                 | ColumnType::LongBlob
                 | ColumnType::Json
+                | ColumnType::Vector
                 | ColumnType::Geometry
                 | ColumnType::Time2
                 | ColumnType::DateTime2
@@ -104,4 +170,38 @@ impl TableMapEvent {
 
         Ok(column_metadatas)
     }
+
+    fn write_metadatas(
+        buf: &mut Vec<u8>,
+        column_types: &[u8],
+        column_metas: &[u16],
+    ) -> Result<(), BinlogError> {
+        for (column_type, column_meta) in column_types.iter().zip(column_metas) {
+            match ColumnType::from_code(*column_type) {
+                ColumnType::Float
+                | ColumnType::Double
+                | ColumnType::Blob
+                | ColumnType::TinyBlob
+                | ColumnType::MediumBlob
+                | ColumnType::LongBlob
+                | ColumnType::Json
+                | ColumnType::Vector
+                | ColumnType::Geometry
+                | ColumnType::Time2
+                | ColumnType::DateTime2
+                | ColumnType::TimeStamp2 => buf.write_u8(*column_meta as u8)?,
+
+                ColumnType::Bit | ColumnType::VarChar | ColumnType::NewDecimal => {
+                    buf.write_u16::<LittleEndian>(*column_meta)?
+                }
+
+                ColumnType::Set | ColumnType::Enum | ColumnType::String => {
+                    buf.write_u16::<BigEndian>(*column_meta)?
+                }
+
+                _ => {}
+            }
+        }
+        Ok(())
+    }
 }
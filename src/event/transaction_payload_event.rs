@@ -1,11 +1,16 @@
 use std::{
     collections::HashMap,
-    io::{Cursor, Seek, SeekFrom},
+    io::{Cursor, Read, Seek, SeekFrom, Write},
 };
 
 use serde::{Deserialize, Serialize};
 
-use crate::{binlog_error::BinlogError, binlog_parser::BinlogParser, ext::cursor_ext::CursorExt};
+use crate::{
+    binlog_encoder::BinlogEncoder,
+    binlog_error::BinlogError,
+    binlog_parser::BinlogParser,
+    ext::{buf_ext::BufExt, cursor_ext::CursorExt},
+};
 
 use super::{event_data::EventData, event_header::EventHeader};
 
@@ -13,16 +18,30 @@ use super::{event_data::EventData, event_header::EventHeader};
 pub struct TransactionPayloadEvent {
     pub uncompressed_size: u32,
     pub uncompressed_events: Vec<(EventHeader, EventData)>,
+    /// 0: ZSTD, 255: NONE
+    pub compression_type: u32,
+    /// The compressed payload as read, it is written back as is when encoding since compressing
+    /// the events again is not guaranteed to produce the same bytes. Leave it empty to compress
+    /// uncompressed_events instead.
+    #[serde(skip)]
+    pub payload: Vec<u8>,
 }
 
+const PAYLOAD_SIZE_FIELD: usize = 1;
+const COMPRESSION_TYPE_FIELD: usize = 2;
+const UNCOMPRESSED_SIZE_FIELD: usize = 3;
+const ZSTD_COMPRESSION_TYPE: u32 = 0;
+
 impl TransactionPayloadEvent {
     pub fn parse(cursor: &mut Cursor<&Vec<u8>>) -> Result<Self, BinlogError> {
         // refer: https://dev.mysql.com/doc/refman/8.0/en/binary-log-transaction-compression.html
-        let (_compress_type, uncompressed_size) = Self::parse_meta(cursor)?;
+        let (compress_type, uncompressed_size) = Self::parse_meta(cursor)?;
 
         // read the rest data as payload and decompress it, currently only support zstd
+        let mut payload = Vec::new();
+        cursor.read_to_end(&mut payload)?;
         let mut uncompressed_payload: Vec<u8> = Vec::new();
-        zstd::stream::copy_decode(cursor, &mut uncompressed_payload)?;
+        zstd::stream::copy_decode(payload.as_slice(), &mut uncompressed_payload)?;
 
         // construct a new parser from the payload
         let mut payload_cursor = Cursor::new(uncompressed_payload);
@@ -40,9 +59,51 @@ impl TransactionPayloadEvent {
         Ok(Self {
             uncompressed_size: uncompressed_size as u32,
             uncompressed_events,
+            compression_type: compress_type as u32,
+            payload,
         })
     }
 
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), BinlogError> {
+        let (uncompressed_size, payload) = if self.payload.is_empty() {
+            // events in payload have no checksum
+            let mut encoder = BinlogEncoder::new();
+            let mut uncompressed_payload = Vec::new();
+            for (header, data) in &self.uncompressed_events {
+                uncompressed_payload.extend(encoder.encode(header, data)?);
+            }
+
+            let payload = if self.compression_type == ZSTD_COMPRESSION_TYPE {
+                zstd::stream::encode_all(uncompressed_payload.as_slice(), 0)?
+            } else {
+                uncompressed_payload.clone()
+            };
+            (uncompressed_payload.len(), payload)
+        } else {
+            (self.uncompressed_size as usize, self.payload.clone())
+        };
+
+        Self::write_meta_field(buf, COMPRESSION_TYPE_FIELD, self.compression_type as usize)?;
+        Self::write_meta_field(buf, UNCOMPRESSED_SIZE_FIELD, uncompressed_size)?;
+        Self::write_meta_field(buf, PAYLOAD_SIZE_FIELD, payload.len())?;
+        buf.write_packed_number(0)?;
+        buf.write_all(&payload)?;
+        Ok(())
+    }
+
+    fn write_meta_field(
+        buf: &mut Vec<u8>,
+        field_type: usize,
+        value: usize,
+    ) -> Result<(), BinlogError> {
+        let mut field = Vec::new();
+        field.write_packed_number(value)?;
+        buf.write_packed_number(field_type)?;
+        buf.write_packed_number(field.len())?;
+        buf.append(&mut field);
+        Ok(())
+    }
+
     fn parse_meta(cursor: &mut Cursor<&Vec<u8>>) -> Result<(usize, usize), BinlogError> {
         let mut payload_size = 0;
         let mut compress_type = 0;
@@ -67,11 +128,11 @@ impl TransactionPayloadEvent {
             };
 
             match field_type {
-                1 => payload_size = cursor.read_packed_number()?,
+                PAYLOAD_SIZE_FIELD => payload_size = cursor.read_packed_number()?,
 
-                2 => compress_type = cursor.read_packed_number()?,
+                COMPRESSION_TYPE_FIELD => compress_type = cursor.read_packed_number()?,
 
-                3 => uncompressed_size = cursor.read_packed_number()?,
+                UNCOMPRESSED_SIZE_FIELD => uncompressed_size = cursor.read_packed_number()?,
 
                 _ => {
                     cursor.seek(SeekFrom::Current(field_length as i64))?;
@@ -2,13 +2,20 @@ use std::{collections::HashMap, io::Cursor};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{binlog_error::BinlogError, ext::cursor_ext::CursorExt};
+use crate::{
+    binlog_error::BinlogError,
+    ext::{buf_ext::BufExt, cursor_ext::CursorExt},
+};
 
 use super::{event_header::EventHeader, row_event::RowEvent, table_map_event::TableMapEvent};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct UpdateRowsEvent {
     pub table_id: u64,
+    /// Rows event flags, STMT_END_F (0x1) marks the last rows event of a statement.
+    pub flags: u16,
+    /// Extra row info of version 2 rows events, without the length.
+    pub extra_data: Vec<u8>,
     pub included_columns_before: Vec<bool>,
     pub included_columns_after: Vec<bool>,
     pub rows: Vec<(RowEvent, RowEvent)>,
@@ -20,10 +27,12 @@ impl UpdateRowsEvent {
         table_map_event_by_table_id: &mut HashMap<u64, TableMapEvent>,
         row_event_version: u8,
     ) -> Result<Self, BinlogError> {
-        let (table_id, column_count, included_columns_before) =
+        let (table_id, flags, extra_data, column_count, included_columns_before) =
             EventHeader::parse_rows_event_common_header(cursor, row_event_version)?;
         let included_columns_after = cursor.read_bits(column_count, false)?;
-        let table_map_event = table_map_event_by_table_id.get(&table_id).unwrap();
+        let table_map_event = table_map_event_by_table_id.get(&table_id).ok_or_else(|| {
+            BinlogError::UnexpectedData(format!("no table map event for table_id: {}", table_id))
+        })?;
 
         let mut rows: Vec<(RowEvent, RowEvent)> = Vec::new();
         while cursor.available() > 0 {
@@ -34,9 +43,45 @@ impl UpdateRowsEvent {
 
         Ok(Self {
             table_id,
+            flags,
+            extra_data,
             included_columns_before,
             included_columns_after,
             rows,
         })
     }
+
+    pub fn encode(
+        &self,
+        buf: &mut Vec<u8>,
+        table_map_event: &TableMapEvent,
+        row_event_version: u8,
+        legacy_bitmap_padding: bool,
+    ) -> Result<(), BinlogError> {
+        EventHeader::encode_rows_event_common_header(
+            buf,
+            self.table_id,
+            self.flags,
+            &self.extra_data,
+            row_event_version,
+            &self.included_columns_before,
+            legacy_bitmap_padding,
+        )?;
+        buf.write_bits_with_padding(&self.included_columns_after, legacy_bitmap_padding)?;
+        for (before, after) in &self.rows {
+            before.encode(
+                buf,
+                table_map_event,
+                &self.included_columns_before,
+                legacy_bitmap_padding,
+            )?;
+            after.encode(
+                buf,
+                table_map_event,
+                &self.included_columns_after,
+                legacy_bitmap_padding,
+            )?;
+        }
+        Ok(())
+    }
 }
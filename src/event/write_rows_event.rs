@@ -9,6 +9,10 @@ use super::{event_header::EventHeader, row_event::RowEvent, table_map_event::Tab
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct WriteRowsEvent {
     pub table_id: u64,
+    /// Rows event flags, STMT_END_F (0x1) marks the last rows event of a statement.
+    pub flags: u16,
+    /// Extra row info of version 2 rows events, without the length.
+    pub extra_data: Vec<u8>,
     pub included_columns: Vec<bool>,
     pub rows: Vec<RowEvent>,
 }
@@ -20,9 +24,11 @@ impl WriteRowsEvent {
         row_event_version: u8,
     ) -> Result<Self, BinlogError> {
         // refer: https://mariadb.com/kb/en/rows_event_v1v2-rows_compressed_event_v1/
-        let (table_id, _column_count, included_columns) =
+        let (table_id, flags, extra_data, _column_count, included_columns) =
             EventHeader::parse_rows_event_common_header(cursor, row_event_version)?;
-        let table_map_event = table_map_event_by_table_id.get(&table_id).unwrap();
+        let table_map_event = table_map_event_by_table_id.get(&table_id).ok_or_else(|| {
+            BinlogError::UnexpectedData(format!("no table map event for table_id: {}", table_id))
+        })?;
 
         let mut rows: Vec<RowEvent> = Vec::new();
         while cursor.available() > 0 {
@@ -32,8 +38,37 @@ impl WriteRowsEvent {
 
         Ok(Self {
             table_id,
+            flags,
+            extra_data,
             included_columns,
             rows,
         })
     }
+
+    pub fn encode(
+        &self,
+        buf: &mut Vec<u8>,
+        table_map_event: &TableMapEvent,
+        row_event_version: u8,
+        legacy_bitmap_padding: bool,
+    ) -> Result<(), BinlogError> {
+        EventHeader::encode_rows_event_common_header(
+            buf,
+            self.table_id,
+            self.flags,
+            &self.extra_data,
+            row_event_version,
+            &self.included_columns,
+            legacy_bitmap_padding,
+        )?;
+        for row in &self.rows {
+            row.encode(
+                buf,
+                table_map_event,
+                &self.included_columns,
+                legacy_bitmap_padding,
+            )?;
+        }
+        Ok(())
+    }
 }
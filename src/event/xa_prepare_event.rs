@@ -1,6 +1,6 @@
-use std::io::Cursor;
+use std::io::{Cursor, Write};
 
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use serde::{Deserialize, Serialize};
 
 use crate::{binlog_error::BinlogError, ext::cursor_ext::CursorExt};
@@ -30,4 +30,14 @@ impl XaPrepareEvent {
             bqual,
         })
     }
+
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), BinlogError> {
+        buf.write_u8(if self.one_phase { 0 } else { 1 })?;
+        buf.write_u32::<LittleEndian>(self.format_id)?;
+        buf.write_u32::<LittleEndian>(self.gtrid.len() as u32)?;
+        buf.write_u32::<LittleEndian>(self.bqual.len() as u32)?;
+        buf.write_all(self.gtrid.as_bytes())?;
+        buf.write_all(self.bqual.as_bytes())?;
+        Ok(())
+    }
 }
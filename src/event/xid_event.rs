@@ -1,6 +1,6 @@
 use std::io::Cursor;
 
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use serde::{Deserialize, Serialize};
 
 use crate::binlog_error::BinlogError;
@@ -16,4 +16,9 @@ impl XidEvent {
             xid: cursor.read_u64::<LittleEndian>()?,
         })
     }
+
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), BinlogError> {
+        buf.write_u64::<LittleEndian>(self.xid)?;
+        Ok(())
+    }
 }
@@ -1,12 +1,18 @@
 use std::io::Write;
 
-use byteorder::WriteBytesExt;
+use byteorder::{LittleEndian, WriteBytesExt};
 
 use crate::{binlog_error::BinlogError, constants};
 
 pub trait BufExt {
     fn write_null_terminated_string(&mut self, to_write: &str) -> Result<(), BinlogError>;
 
+    fn write_packed_number(&mut self, number: usize) -> Result<(), BinlogError>;
+
+    fn write_bits(&mut self, bits: &[bool], big_endian: bool) -> Result<(), BinlogError>;
+
+    fn write_bits_with_padding(&mut self, bits: &[bool], padding: bool) -> Result<(), BinlogError>;
+
     fn reverse(&mut self);
 
     fn xor(&mut self, buf2: Vec<u8>) -> Vec<u8>;
@@ -22,6 +28,46 @@ impl BufExt for Vec<u8> {
         Ok(())
     }
 
+    /// Write a number in the packed format read by CursorExt::read_packed_number
+    fn write_packed_number(&mut self, number: usize) -> Result<(), BinlogError> {
+        if number < 0xfb {
+            self.write_u8(number as u8)?;
+        } else if number <= 0xffff {
+            self.write_u8(0xfc)?;
+            self.write_u16::<LittleEndian>(number as u16)?;
+        } else if number <= 0xffffff {
+            self.write_u8(0xfd)?;
+            self.write_u24::<LittleEndian>(number as u32)?;
+        } else {
+            self.write_u8(0xfe)?;
+            self.write_u64::<LittleEndian>(number as u64)?;
+        }
+        Ok(())
+    }
+
+    /// Write bits as read by CursorExt::read_bits, if the data is encoded in BigEndian, reverse the order of bytes
+    fn write_bits(&mut self, bits: &[bool], big_endian: bool) -> Result<(), BinlogError> {
+        let mut bytes = vec![0u8; (bits.len() + 7) >> 3];
+        for (i, bit) in bits.iter().enumerate() {
+            if *bit {
+                bytes[i >> 3] |= 1 << (i % 8);
+            }
+        }
+
+        if big_endian {
+            bytes.reverse();
+        }
+        self.write_all(&bytes)?;
+        Ok(())
+    }
+
+    /// Write bits in LittleEndian with the unused bits of the last byte set to padding
+    fn write_bits_with_padding(&mut self, bits: &[bool], padding: bool) -> Result<(), BinlogError> {
+        let mut padded_bits = bits.to_vec();
+        padded_bits.resize(bits.len().div_ceil(8) * 8, padding);
+        self.write_bits(&padded_bits, false)
+    }
+
     /// Reverse the order of contents in the buf
     fn reverse(&mut self) {
         for i in 0..self.len() >> 1 {
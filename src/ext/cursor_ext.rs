@@ -21,11 +21,9 @@ pub trait CursorExt {
         -> Result<Vec<u8>, BinlogError>;
 
     fn available(&mut self) -> usize;
-
-    fn read_bytes(&mut self, size: usize) -> Result<Vec<u8>, BinlogError>;
 }
 
-impl CursorExt for Cursor<&Vec<u8>> {
+impl<T: AsRef<[u8]>> CursorExt for Cursor<T> {
     /// Read bytes from cursor and parse into utf8 string
     fn read_string(&mut self, size: usize) -> Result<String, BinlogError> {
         let mut buf = vec![0; size];
@@ -106,15 +104,8 @@ impl CursorExt for Cursor<&Vec<u8>> {
         Ok(bytes)
     }
 
-    /// Read n bytes from cursor and return the buf
-    fn read_bytes(&mut self, size: usize) -> Result<Vec<u8>, BinlogError> {
-        let mut buf = vec![0; size];
-        self.read_exact(&mut buf)?;
-        Ok(buf)
-    }
-
     /// Return the available bytes count in cursor
     fn available(&mut self) -> usize {
-        self.get_ref().len() - self.position() as usize
+        self.get_ref().as_ref().len() - self.position() as usize
     }
 }
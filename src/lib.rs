@@ -1,10 +1,25 @@
+pub mod binlog_async_reader;
+pub mod binlog_bounded_reader;
 pub mod binlog_client;
+pub mod binlog_directory_reader;
+pub mod binlog_encoder;
+#[cfg(feature = "encryption")]
+pub mod binlog_encryption;
 pub mod binlog_error;
+pub mod binlog_file;
+pub mod binlog_mirror;
+#[cfg(feature = "mmap")]
+pub mod binlog_mmap_parser;
+pub mod binlog_offset_index;
 pub mod binlog_parser;
+pub mod binlog_recovery_reader;
 pub mod binlog_stream;
+pub mod binlog_writer;
+pub mod change;
 pub mod column;
 pub mod command;
 mod constants;
 pub mod event;
 mod ext;
 mod network;
+pub mod output;
@@ -5,6 +5,8 @@ use crate::{binlog_error::BinlogError, ext::cursor_ext::CursorExt};
 pub struct AuthPluginSwitchPacket {
     pub auth_plugin_name: String,
     pub scramble: String,
+    /// Raw data following the plugin name, as read by the plugin.
+    pub auth_plugin_data: Vec<u8>,
 }
 
 impl AuthPluginSwitchPacket {
@@ -14,10 +16,12 @@ impl AuthPluginSwitchPacket {
         cursor.seek(SeekFrom::Current(1))?;
 
         let auth_plugin_name = cursor.read_null_terminated_string()?;
+        let auth_plugin_data = packet[cursor.position() as usize..].to_vec();
         let scramble = cursor.read_null_terminated_string()?;
         Ok(Self {
             auth_plugin_name,
             scramble,
+            auth_plugin_data,
         })
     }
 }
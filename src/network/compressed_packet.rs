@@ -0,0 +1,137 @@
+use std::io::Cursor;
+#[cfg(feature = "zlib")]
+use std::io::{Read, Write};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+#[cfg(feature = "zlib")]
+use flate2::{read::ZlibDecoder, write::ZlibEncoder};
+
+use crate::{binlog_client::ProtocolCompression, binlog_error::BinlogError};
+
+pub const COMPRESSED_HEADER_LENGTH: usize = 7;
+/// Payloads shorter than this are sent uncompressed, like the server does.
+const MIN_COMPRESS_LENGTH: usize = 50;
+
+/// Header of a compressed packet, which wraps one or more (parts of) packets.
+pub struct CompressedPacketHeader {
+    pub compressed_length: usize,
+    pub sequence: u8,
+    /// 0 if the payload is not compressed.
+    pub uncompressed_length: usize,
+}
+
+impl CompressedPacketHeader {
+    pub fn parse(buf: &[u8]) -> Result<Self, BinlogError> {
+        let mut cursor = Cursor::new(buf);
+        Ok(Self {
+            compressed_length: cursor.read_u24::<LittleEndian>()? as usize,
+            sequence: cursor.read_u8()?,
+            uncompressed_length: cursor.read_u24::<LittleEndian>()? as usize,
+        })
+    }
+}
+
+/// Builds a compressed packet of a payload of at most 16 MiB - 1 bytes.
+pub fn encode(
+    compression: ProtocolCompression,
+    sequence: u8,
+    payload: &[u8],
+) -> Result<Vec<u8>, BinlogError> {
+    let (body, uncompressed_length) = if payload.len() < MIN_COMPRESS_LENGTH {
+        (payload.to_vec(), 0)
+    } else {
+        (compress(compression, payload)?, payload.len())
+    };
+
+    let mut buf = Vec::with_capacity(COMPRESSED_HEADER_LENGTH + body.len());
+    buf.write_u24::<LittleEndian>(body.len() as u32)?;
+    buf.write_u8(sequence)?;
+    buf.write_u24::<LittleEndian>(uncompressed_length as u32)?;
+    buf.extend_from_slice(&body);
+    Ok(buf)
+}
+
+/// Decompresses the payload of a compressed packet.
+pub fn decode(
+    compression: ProtocolCompression,
+    header: &CompressedPacketHeader,
+    body: Vec<u8>,
+) -> Result<Vec<u8>, BinlogError> {
+    if header.uncompressed_length == 0 {
+        return Ok(body);
+    }
+
+    let payload = match compression {
+        #[cfg(feature = "zlib")]
+        ProtocolCompression::Zlib => {
+            let mut payload = Vec::with_capacity(header.uncompressed_length);
+            ZlibDecoder::new(body.as_slice()).read_to_end(&mut payload)?;
+            payload
+        }
+        ProtocolCompression::Zstd(_) => zstd::bulk::decompress(&body, header.uncompressed_length)?,
+    };
+    if payload.len() != header.uncompressed_length {
+        return Err(BinlogError::UnexpectedData(format!(
+            "compressed packet length mismatch, expected: {}, actual: {}",
+            header.uncompressed_length,
+            payload.len()
+        )));
+    }
+    Ok(payload)
+}
+
+fn compress(compression: ProtocolCompression, payload: &[u8]) -> Result<Vec<u8>, BinlogError> {
+    match compression {
+        #[cfg(feature = "zlib")]
+        ProtocolCompression::Zlib => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(payload)?;
+            Ok(encoder.finish()?)
+        }
+        ProtocolCompression::Zstd(level) => Ok(zstd::bulk::compress(payload, level as i32)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::binlog_client::ProtocolCompression;
+
+    use super::{decode, encode, CompressedPacketHeader, COMPRESSED_HEADER_LENGTH};
+
+    fn roundtrip(compression: ProtocolCompression, payload: &[u8]) -> (usize, Vec<u8>) {
+        let buf = encode(compression, 7, payload).unwrap();
+        let header = CompressedPacketHeader::parse(&buf).unwrap();
+        assert_eq!(header.sequence, 7);
+        assert_eq!(
+            header.compressed_length,
+            buf.len() - COMPRESSED_HEADER_LENGTH
+        );
+        let body = buf[COMPRESSED_HEADER_LENGTH..].to_vec();
+        (
+            header.uncompressed_length,
+            decode(compression, &header, body).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let payload: Vec<u8> = (0..10_000).map(|i| (i % 17) as u8).collect();
+        let compressions = [
+            #[cfg(feature = "zlib")]
+            ProtocolCompression::Zlib,
+            ProtocolCompression::Zstd(3),
+        ];
+        for compression in compressions {
+            let (uncompressed_length, decoded) = roundtrip(compression, &payload);
+            assert_eq!(uncompressed_length, payload.len());
+            assert_eq!(decoded, payload);
+        }
+    }
+
+    #[test]
+    fn test_short_payload_not_compressed() {
+        let (uncompressed_length, decoded) = roundtrip(ProtocolCompression::Zstd(3), b"\x0eping");
+        assert_eq!(uncompressed_length, 0);
+        assert_eq!(decoded, b"\x0eping");
+    }
+}
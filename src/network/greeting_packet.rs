@@ -8,7 +8,7 @@ pub struct GreetingPacket {
     pub protocol_version: u8,
     pub server_version: String,
     pub thread_id: u32,
-    pub server_capabilities: u16,
+    pub server_capabilities: u32,
     pub server_collation: u8,
     pub server_status: u16,
     pub scramble: String,
@@ -22,12 +22,13 @@ impl GreetingPacket {
         let server_version = cursor.read_null_terminated_string()?;
         let thread_id = cursor.read_u32::<LittleEndian>()?;
         let mut scramble = cursor.read_null_terminated_string()?;
-        let server_capabilities = cursor.read_u16::<LittleEndian>()?;
+        let mut server_capabilities = cursor.read_u16::<LittleEndian>()? as u32;
         let server_collation = cursor.read_u8()?;
         let server_status = cursor.read_u16::<LittleEndian>()?;
+        server_capabilities |= (cursor.read_u16::<LittleEndian>()? as u32) << 16;
 
-        // reserved
-        cursor.seek(SeekFrom::Current(13))?;
+        // auth plugin data length and reserved
+        cursor.seek(SeekFrom::Current(11))?;
         scramble.push_str(cursor.read_null_terminated_string()?.as_str());
 
         let mut plugin_provided_data = "".to_string();
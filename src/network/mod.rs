@@ -1,4 +1,5 @@
 pub mod auth_plugin_switch_packet;
+pub mod compressed_packet;
 pub mod error_packet;
 pub mod greeting_packet;
 pub mod packet_channel;
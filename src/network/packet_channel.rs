@@ -13,6 +13,8 @@ use std::{
     time::Duration,
 };
 
+#[cfg(unix)]
+use async_std::os::unix::net::UnixStream;
 use async_std::{
     future::timeout,
     io::{ReadExt as AsyncStdReadExt, WriteExt as AsyncStdWriteExt},
@@ -21,7 +23,11 @@ use async_std::{
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use log::{trace, warn};
 
-use crate::binlog_error::BinlogError;
+use crate::{
+    binlog_client::ProtocolCompression,
+    binlog_error::BinlogError,
+    network::compressed_packet::{self, CompressedPacketHeader, COMPRESSED_HEADER_LENGTH},
+};
 
 #[cfg(feature = "openssl-tls")]
 use async_std_openssl::SslStream as OpenSslStream;
@@ -32,13 +38,17 @@ use futures_rustls::client::TlsStream;
 #[cfg(feature = "rustls")]
 use futures_rustls::TlsConnector;
 #[cfg(feature = "openssl-tls")]
-use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode};
+use openssl::{
+    ssl::{SslConnector, SslFiletype, SslMethod, SslVerifyMode},
+    x509::{store::X509StoreBuilder, X509},
+};
 #[cfg(feature = "rustls")]
-use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer, ServerName, UnixTime};
 #[cfg(feature = "rustls")]
 use rustls::{
     client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
-    ClientConfig, DigitallySignedStruct, SignatureScheme,
+    client::WebPkiServerVerifier,
+    CertificateError, ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
 };
 #[cfg(feature = "rustls")]
 use std::sync::Arc;
@@ -47,6 +57,8 @@ const MAX_PACKET_LENGTH: usize = 16777215;
 
 enum ChannelStream {
     Plain(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
     #[cfg(feature = "rustls")]
     TlsRustls(Box<TlsStream<TcpStream>>),
     #[cfg(feature = "openssl-tls")]
@@ -56,6 +68,11 @@ enum ChannelStream {
 pub struct PacketChannel {
     stream: Option<ChannelStream>,
     timeout_secs: u64,
+    compression: Option<ProtocolCompression>,
+    compressed_sequence: u8,
+    /// Payload of the last compressed packet, read from `decompressed_offset`.
+    decompressed: Vec<u8>,
+    decompressed_offset: usize,
 }
 
 pub struct KeepAliveConfig {
@@ -63,6 +80,31 @@ pub struct KeepAliveConfig {
     pub keepintvl_secs: u64,
 }
 
+/// TLS modes, like the `--ssl-mode` option of the mysql client.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TlsMode {
+    #[default]
+    Disabled,
+    /// TLS if the server supports it, plain TCP otherwise.
+    Preferred,
+    Required,
+    /// Verifies that the server certificate is signed by a trusted CA.
+    VerifyCa,
+    /// Verifies the CA and that the server certificate matches the host.
+    VerifyIdentity,
+}
+
+/// TLS settings of a connection, the files are in PEM format.
+#[derive(Clone, Debug, Default)]
+pub struct TlsConfig {
+    pub mode: TlsMode,
+    /// CA certificates to verify the server with, required by VerifyCa and VerifyIdentity.
+    pub ca_path: Option<String>,
+    /// Client certificate chain and private key, for servers requiring X509 users.
+    pub cert_path: Option<String>,
+    pub key_path: Option<String>,
+}
+
 #[cfg(feature = "rustls")]
 #[derive(Debug)]
 struct NoCertificateVerification;
@@ -117,6 +159,56 @@ impl ServerCertVerifier for NoCertificateVerification {
     }
 }
 
+/// Verifies the server certificate chain but not its name, for ssl-mode=verify_ca.
+#[cfg(feature = "rustls")]
+#[derive(Debug)]
+struct CaOnlyVerification(Arc<WebPkiServerVerifier>);
+
+#[cfg(feature = "rustls")]
+impl ServerCertVerifier for CaOnlyVerification {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        // the name is checked after the chain
+        match self
+            .0
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+        {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) => Ok(ServerCertVerified::assertion()),
+            res => res,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.supported_verify_schemes()
+    }
+}
+
 impl PacketChannel {
     pub async fn new(
         ip: &str,
@@ -144,9 +236,46 @@ impl PacketChannel {
         Ok(Self {
             stream: Some(ChannelStream::Plain(stream)),
             timeout_secs,
+            compression: None,
+            compressed_sequence: 0,
+            decompressed: Vec::new(),
+            decompressed_offset: 0,
         })
     }
 
+    /// Connects to a Unix domain socket, such as /var/run/mysqld/mysqld.sock.
+    #[cfg(unix)]
+    pub async fn new_unix(path: &str, timeout_secs: u64) -> Result<Self, BinlogError> {
+        let stream =
+            match timeout(Duration::from_secs(timeout_secs), UnixStream::connect(path)).await {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(e)) => return Err(BinlogError::from(e)),
+                Err(_) => {
+                    return Err(BinlogError::ConnectError(format!(
+                        "Connection timeout after {} seconds while connecting to {}",
+                        timeout_secs, path
+                    )))
+                }
+            };
+
+        Ok(Self {
+            stream: Some(ChannelStream::Unix(stream)),
+            timeout_secs,
+            compression: None,
+            compressed_sequence: 0,
+            decompressed: Vec::new(),
+            decompressed_offset: 0,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub async fn new_unix(path: &str, _timeout_secs: u64) -> Result<Self, BinlogError> {
+        Err(BinlogError::ConnectError(format!(
+            "unix domain sockets are unavailable on this platform: {}",
+            path
+        )))
+    }
+
     /// Configure TCP keepalive settings for the stream
     /// This is safe because:
     /// 1. We only borrow the stream temporarily
@@ -202,6 +331,9 @@ impl PacketChannel {
     pub fn is_secure_transport(&self) -> bool {
         match self.stream.as_ref() {
             Some(ChannelStream::Plain(_)) => false,
+            // like the server, a local socket is considered secure
+            #[cfg(unix)]
+            Some(ChannelStream::Unix(_)) => true,
             #[cfg(feature = "rustls")]
             Some(ChannelStream::TlsRustls(_)) => true,
             #[cfg(feature = "openssl-tls")]
@@ -211,9 +343,20 @@ impl PacketChannel {
     }
 
     #[cfg(feature = "rustls")]
-    pub async fn upgrade_to_tls(&mut self, host: &str) -> Result<(), BinlogError> {
+    pub async fn upgrade_to_tls(
+        &mut self,
+        host: &str,
+        tls_config: &TlsConfig,
+    ) -> Result<(), BinlogError> {
         let plain_stream = match self.stream.take() {
             Some(ChannelStream::Plain(stream)) => stream,
+            #[cfg(unix)]
+            Some(ChannelStream::Unix(stream)) => {
+                self.stream = Some(ChannelStream::Unix(stream));
+                return Err(BinlogError::ConnectError(
+                    "tls is not supported over unix domain sockets".into(),
+                ));
+            }
             Some(ChannelStream::TlsRustls(stream)) => {
                 self.stream = Some(ChannelStream::TlsRustls(stream));
                 return Ok(());
@@ -231,10 +374,7 @@ impl PacketChannel {
         };
 
         let server_name = Self::build_server_name(host)?;
-        let config = ClientConfig::builder()
-            .dangerous()
-            .with_custom_certificate_verifier(Arc::new(NoCertificateVerification))
-            .with_no_client_auth();
+        let config = Self::build_rustls_config(tls_config)?;
         let connector = TlsConnector::from(Arc::new(config));
         let tls_stream = connector
             .connect(server_name, plain_stream)
@@ -245,6 +385,55 @@ impl PacketChannel {
         Ok(())
     }
 
+    #[cfg(feature = "rustls")]
+    fn build_rustls_config(tls_config: &TlsConfig) -> Result<ClientConfig, BinlogError> {
+        let tls_error = |e: String| BinlogError::ConnectError(format!("invalid tls config: {}", e));
+        let builder = ClientConfig::builder();
+        let builder = match (tls_config.mode, &tls_config.ca_path) {
+            (TlsMode::VerifyCa | TlsMode::VerifyIdentity, Some(ca_path)) => {
+                let mut roots = RootCertStore::empty();
+                for cert in Self::load_rustls_certs(ca_path)? {
+                    roots.add(cert).map_err(|e| tls_error(e.to_string()))?;
+                }
+                let verifier = WebPkiServerVerifier::builder(Arc::new(roots))
+                    .build()
+                    .map_err(|e| tls_error(e.to_string()))?;
+                if tls_config.mode == TlsMode::VerifyCa {
+                    builder
+                        .dangerous()
+                        .with_custom_certificate_verifier(Arc::new(CaOnlyVerification(verifier)))
+                } else {
+                    builder.with_webpki_verifier(verifier)
+                }
+            }
+            (TlsMode::VerifyCa | TlsMode::VerifyIdentity, None) => {
+                return Err(tls_error("ssl-ca is required to verify the server".into()))
+            }
+            _ => builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(NoCertificateVerification)),
+        };
+
+        match (&tls_config.cert_path, &tls_config.key_path) {
+            (Some(cert_path), Some(key_path)) => {
+                let certs = Self::load_rustls_certs(cert_path)?;
+                let key = PrivateKeyDer::from_pem_file(key_path)
+                    .map_err(|e| tls_error(format!("{}: {}", key_path, e)))?;
+                builder
+                    .with_client_auth_cert(certs, key)
+                    .map_err(|e| tls_error(e.to_string()))
+            }
+            _ => Ok(builder.with_no_client_auth()),
+        }
+    }
+
+    #[cfg(feature = "rustls")]
+    fn load_rustls_certs(path: &str) -> Result<Vec<CertificateDer<'static>>, BinlogError> {
+        CertificateDer::pem_file_iter(path)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| BinlogError::ConnectError(format!("invalid tls config: {}: {}", path, e)))
+    }
+
     #[cfg(feature = "rustls")]
     fn build_server_name(host: &str) -> Result<ServerName<'static>, BinlogError> {
         if let Ok(ip_addr) = host.parse::<IpAddr>() {
@@ -257,9 +446,20 @@ impl PacketChannel {
 
     #[cfg(not(feature = "rustls"))]
     #[cfg(feature = "openssl-tls")]
-    pub async fn upgrade_to_tls(&mut self, host: &str) -> Result<(), BinlogError> {
+    pub async fn upgrade_to_tls(
+        &mut self,
+        host: &str,
+        tls_config: &TlsConfig,
+    ) -> Result<(), BinlogError> {
         let plain_stream = match self.stream.take() {
             Some(ChannelStream::Plain(stream)) => stream,
+            #[cfg(unix)]
+            Some(ChannelStream::Unix(stream)) => {
+                self.stream = Some(ChannelStream::Unix(stream));
+                return Err(BinlogError::ConnectError(
+                    "tls is not supported over unix domain sockets".into(),
+                ));
+            }
             #[cfg(feature = "rustls")]
             Some(ChannelStream::TlsRustls(stream)) => {
                 self.stream = Some(ChannelStream::TlsRustls(stream));
@@ -276,21 +476,47 @@ impl PacketChannel {
             }
         };
 
+        let tls_error = |e: openssl::error::ErrorStack| {
+            BinlogError::ConnectError(format!("invalid tls config: {}", e))
+        };
         let mut builder = SslConnector::builder(SslMethod::tls_client()).map_err(|e| {
             BinlogError::ConnectError(format!("failed to build openssl connector: {}", e))
         })?;
-        builder.set_verify(SslVerifyMode::NONE);
-
-        let ssl = builder
-            .build()
-            .configure()
-            .map_err(|e| {
-                BinlogError::ConnectError(format!("failed to configure openssl connector: {}", e))
-            })?
-            .into_ssl(host)
-            .map_err(|e| {
-                BinlogError::ConnectError(format!("failed to prepare openssl session: {}", e))
+        let verify = matches!(tls_config.mode, TlsMode::VerifyCa | TlsMode::VerifyIdentity);
+        if verify {
+            let ca_path = tls_config.ca_path.as_ref().ok_or_else(|| {
+                BinlogError::ConnectError(
+                    "invalid tls config: ssl-ca is required to verify the server".into(),
+                )
             })?;
+            // only the ssl-ca certificates are trusted, the connector also loads the system ones
+            let ca_pem = std::fs::read(ca_path)?;
+            let mut store = X509StoreBuilder::new().map_err(tls_error)?;
+            for cert in X509::stack_from_pem(&ca_pem).map_err(tls_error)? {
+                store.add_cert(cert).map_err(tls_error)?;
+            }
+            builder.set_cert_store(store.build());
+            builder.set_verify(SslVerifyMode::PEER);
+        } else {
+            builder.set_verify(SslVerifyMode::NONE);
+        }
+        if let (Some(cert_path), Some(key_path)) = (&tls_config.cert_path, &tls_config.key_path) {
+            builder
+                .set_certificate_chain_file(cert_path)
+                .map_err(tls_error)?;
+            builder
+                .set_private_key_file(key_path, SslFiletype::PEM)
+                .map_err(tls_error)?;
+            builder.check_private_key().map_err(tls_error)?;
+        }
+
+        let mut config = builder.build().configure().map_err(|e| {
+            BinlogError::ConnectError(format!("failed to configure openssl connector: {}", e))
+        })?;
+        config.set_verify_hostname(tls_config.mode == TlsMode::VerifyIdentity);
+        let ssl = config.into_ssl(host).map_err(|e| {
+            BinlogError::ConnectError(format!("failed to prepare openssl session: {}", e))
+        })?;
 
         let mut tls_stream = OpenSslStream::new(ssl, plain_stream).map_err(|e| {
             BinlogError::ConnectError(format!("failed to create openssl stream: {}", e))
@@ -306,7 +532,11 @@ impl PacketChannel {
     }
 
     #[cfg(not(any(feature = "rustls", feature = "openssl-tls")))]
-    pub async fn upgrade_to_tls(&mut self, _host: &str) -> Result<(), BinlogError> {
+    pub async fn upgrade_to_tls(
+        &mut self,
+        _host: &str,
+        _tls_config: &TlsConfig,
+    ) -> Result<(), BinlogError> {
         Err(BinlogError::ConnectError(
             "TLS support is unavailable because no TLS feature is enabled".into(),
         ))
@@ -317,6 +547,10 @@ impl PacketChannel {
             Some(ChannelStream::Plain(stream)) => {
                 stream.shutdown(std::net::Shutdown::Both)?;
             }
+            #[cfg(unix)]
+            Some(ChannelStream::Unix(stream)) => {
+                stream.shutdown(std::net::Shutdown::Both)?;
+            }
             #[cfg(feature = "rustls")]
             Some(ChannelStream::TlsRustls(stream)) => {
                 AsyncWriteExt::close(stream.as_mut()).await?;
@@ -330,12 +564,30 @@ impl PacketChannel {
         Ok(())
     }
 
+    /// Uses the compressed protocol from now on, once the authentication succeeds.
+    pub fn enable_compression(&mut self, compression: ProtocolCompression) {
+        self.compression = Some(compression);
+        self.compressed_sequence = 0;
+    }
+
     pub async fn write(&mut self, buf: &[u8], sequence: u8) -> Result<(), BinlogError> {
         let mut wtr = Vec::new();
         wtr.write_u24::<LittleEndian>(buf.len() as u32)?;
         wtr.write_u8(sequence)?;
         Write::write(&mut wtr, buf)?;
-        self.write_all(&wtr).await?;
+
+        let Some(compression) = self.compression else {
+            return self.write_all(&wtr).await;
+        };
+        // the compressed sequence restarts with each command
+        if sequence == 0 {
+            self.compressed_sequence = 0;
+        }
+        for chunk in wtr.chunks(MAX_PACKET_LENGTH) {
+            let packet = compressed_packet::encode(compression, self.compressed_sequence, chunk)?;
+            self.compressed_sequence = self.compressed_sequence.wrapping_add(1);
+            self.write_all(&packet).await?;
+        }
         Ok(())
     }
 
@@ -389,12 +641,45 @@ impl PacketChannel {
 
     async fn read_exact(&mut self, length: usize) -> Result<Vec<u8>, BinlogError> {
         let mut buf = vec![0u8; length];
-        self.read_loop(&mut buf).await?;
+        self.read_exact_into(&mut buf).await?;
         Ok(buf)
     }
 
     async fn read_exact_into(&mut self, buf: &mut [u8]) -> Result<(), BinlogError> {
-        self.read_loop(buf).await
+        let Some(compression) = self.compression else {
+            return self.read_loop(buf).await;
+        };
+
+        // packets may span compressed packets
+        let mut read_count = 0;
+        while read_count < buf.len() {
+            if self.decompressed_offset == self.decompressed.len() {
+                self.read_compressed_packet(compression).await?;
+                continue;
+            }
+            let available = &self.decompressed[self.decompressed_offset..];
+            let n = available.len().min(buf.len() - read_count);
+            buf[read_count..read_count + n].copy_from_slice(&available[..n]);
+            self.decompressed_offset += n;
+            read_count += n;
+        }
+        Ok(())
+    }
+
+    async fn read_compressed_packet(
+        &mut self,
+        compression: ProtocolCompression,
+    ) -> Result<(), BinlogError> {
+        let mut header_buf = [0u8; COMPRESSED_HEADER_LENGTH];
+        self.read_loop(&mut header_buf).await?;
+        let header = CompressedPacketHeader::parse(&header_buf)?;
+        self.compressed_sequence = header.sequence.wrapping_add(1);
+
+        let mut body = vec![0u8; header.compressed_length];
+        self.read_loop(&mut body).await?;
+        self.decompressed = compressed_packet::decode(compression, &header, body)?;
+        self.decompressed_offset = 0;
+        Ok(())
     }
 
     async fn read_loop(&mut self, buf: &mut [u8]) -> Result<(), BinlogError> {
@@ -453,6 +738,11 @@ impl PacketChannel {
                 AsyncStdWriteExt::write_all(stream, buf).await?;
                 AsyncStdWriteExt::flush(stream).await?;
             }
+            #[cfg(unix)]
+            Some(ChannelStream::Unix(stream)) => {
+                AsyncStdWriteExt::write_all(stream, buf).await?;
+                AsyncStdWriteExt::flush(stream).await?;
+            }
             #[cfg(feature = "rustls")]
             Some(ChannelStream::TlsRustls(stream)) => {
                 AsyncWriteExt::write_all(stream.as_mut(), buf).await?;
@@ -475,6 +765,8 @@ impl PacketChannel {
     async fn read_once(&mut self, buf: &mut [u8]) -> Result<usize, BinlogError> {
         let read = match self.stream.as_mut() {
             Some(ChannelStream::Plain(stream)) => AsyncStdReadExt::read(stream, buf).await?,
+            #[cfg(unix)]
+            Some(ChannelStream::Unix(stream)) => AsyncStdReadExt::read(stream, buf).await?,
             #[cfg(feature = "rustls")]
             Some(ChannelStream::TlsRustls(stream)) => {
                 AsyncReadExt::read(stream.as_mut(), buf).await?
@@ -0,0 +1,649 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use arrow_array::{
+    builder::{Float32Builder, ListBuilder},
+    ArrayRef, BinaryArray, BooleanArray, Date32Array, Decimal128Array, DurationMicrosecondArray,
+    Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, RecordBatch,
+    StringArray, TimestampMicrosecondArray, TimestampSecondArray, UInt16Array, UInt32Array,
+    UInt64Array, UInt8Array,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+
+use crate::{
+    binlog_error::BinlogError,
+    change::change_event::{RowChange, RowOperation},
+    column::{column_type::ColumnType, column_value::ColumnValue, json::json_binary::JsonBinary},
+    event::{
+        row::{Row, RowColumn},
+        row_event::RowEvent,
+        table_map_event::TableMapEvent,
+    },
+};
+
+use super::value_util;
+
+/// Names of the metadata columns appended after the table columns.
+pub const OPERATION_COLUMN: &str = "_op";
+pub const GTID_COLUMN: &str = "_gtid";
+pub const BINLOG_FILENAME_COLUMN: &str = "_binlog_filename";
+pub const BINLOG_POSITION_COLUMN: &str = "_binlog_position";
+pub const EVENT_TIMESTAMP_COLUMN: &str = "_event_timestamp";
+
+const UTC: &str = "UTC";
+
+/// A RecordBatch of rows of a table.
+#[derive(Debug, Clone)]
+pub struct ArrowBatch {
+    pub database_name: String,
+    pub table_name: String,
+    pub batch: RecordBatch,
+}
+
+/// Accumulates row changes of each table into RecordBatches. A batch is flushed when it
+/// reaches `max_rows`, when its first row is older than `max_interval` (checked by `poll`),
+/// or when the table changes shape.
+///
+/// Each row change is one row with the after image for inserts and updates, the before
+/// image for deletes, followed by the metadata columns: _op (c / u / d / r), _gtid,
+/// _binlog_filename, _binlog_position and _event_timestamp.
+#[derive(Debug)]
+pub struct ArrowBatcher {
+    pub max_rows: usize,
+    pub max_interval: Duration,
+    buffers: HashMap<(String, String), TableBuffer>,
+}
+
+#[derive(Debug)]
+struct TableBuffer {
+    schema: SchemaRef,
+    changes: Vec<RowChange>,
+    created_at: Instant,
+}
+
+impl Default for ArrowBatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ArrowBatcher {
+    pub fn new() -> Self {
+        Self {
+            max_rows: 8192,
+            max_interval: Duration::from_secs(60),
+            buffers: HashMap::new(),
+        }
+    }
+
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = max_rows.max(1);
+        self
+    }
+
+    pub fn with_max_interval(mut self, max_interval: Duration) -> Self {
+        self.max_interval = max_interval;
+        self
+    }
+
+    /// Appends a row change, returns the batches flushed by a schema change or the size limit.
+    pub fn push(&mut self, change: &RowChange) -> Result<Vec<ArrowBatch>, BinlogError> {
+        let mut batches = Vec::new();
+        let key = (
+            change.database_name().to_string(),
+            change.table_name().to_string(),
+        );
+        let schema = arrow_schema_from_table_map_event(&change.table_map_event);
+
+        if let Some(buffer) = self.buffers.get(&key) {
+            if buffer.schema != schema {
+                let buffer = self.buffers.remove(&key).unwrap();
+                batches.push(buffer.flush(&key)?);
+            }
+        }
+
+        let buffer = self
+            .buffers
+            .entry(key.clone())
+            .or_insert_with(|| TableBuffer {
+                schema,
+                changes: Vec::new(),
+                created_at: Instant::now(),
+            });
+        buffer.changes.push(change.clone());
+
+        if buffer.changes.len() >= self.max_rows {
+            let buffer = self.buffers.remove(&key).unwrap();
+            batches.push(buffer.flush(&key)?);
+        }
+        Ok(batches)
+    }
+
+    /// Flushes the batches whose first row is older than `max_interval`,
+    /// should be called periodically.
+    pub fn poll(&mut self) -> Result<Vec<ArrowBatch>, BinlogError> {
+        let max_interval = self.max_interval;
+        self.flush_where(|buffer| buffer.created_at.elapsed() >= max_interval)
+    }
+
+    /// Flushes all batches.
+    pub fn flush(&mut self) -> Result<Vec<ArrowBatch>, BinlogError> {
+        self.flush_where(|_| true)
+    }
+
+    /// Number of rows not flushed yet.
+    pub fn pending_rows(&self) -> usize {
+        self.buffers.values().map(|b| b.changes.len()).sum()
+    }
+
+    fn flush_where(
+        &mut self,
+        predicate: impl Fn(&TableBuffer) -> bool,
+    ) -> Result<Vec<ArrowBatch>, BinlogError> {
+        let keys: Vec<(String, String)> = self
+            .buffers
+            .iter()
+            .filter(|(_, buffer)| predicate(buffer))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        let mut batches = Vec::with_capacity(keys.len());
+        for key in keys {
+            let buffer = self.buffers.remove(&key).unwrap();
+            batches.push(buffer.flush(&key)?);
+        }
+        Ok(batches)
+    }
+}
+
+impl TableBuffer {
+    fn flush(self, key: &(String, String)) -> Result<ArrowBatch, BinlogError> {
+        Ok(ArrowBatch {
+            database_name: key.0.clone(),
+            table_name: key.1.clone(),
+            batch: to_record_batch(self.schema, &self.changes)?,
+        })
+    }
+}
+
+/// Derives the Arrow schema of a table, including the metadata columns.
+pub fn arrow_schema_from_table_map_event(table_map_event: &TableMapEvent) -> SchemaRef {
+    // resolve column types and charsets the same way as decoded rows
+    let row_event = RowEvent {
+        column_values: vec![ColumnValue::None; table_map_event.column_types.len()],
+    };
+    let row = Row::new(&row_event, table_map_event);
+
+    let mut fields: Vec<Field> = row
+        .columns
+        .iter()
+        .map(|column| {
+            let data_type = arrow_type(column);
+            // MySQL allows zero dates in NOT NULL columns, which are converted to null
+            let nullable = column.is_nullable
+                || matches!(data_type, DataType::Date32 | DataType::Timestamp(..));
+            Field::new(column.name_or_position(), data_type, nullable)
+        })
+        .collect();
+
+    fields.extend([
+        Field::new(OPERATION_COLUMN, DataType::Utf8, false),
+        Field::new(GTID_COLUMN, DataType::Utf8, true),
+        Field::new(BINLOG_FILENAME_COLUMN, DataType::Utf8, false),
+        Field::new(BINLOG_POSITION_COLUMN, DataType::UInt64, false),
+        Field::new(
+            EVENT_TIMESTAMP_COLUMN,
+            DataType::Timestamp(TimeUnit::Second, Some(UTC.into())),
+            false,
+        ),
+    ]);
+
+    let metadata = HashMap::from([
+        (
+            "database".to_string(),
+            table_map_event.database_name.clone(),
+        ),
+        ("table".to_string(), table_map_event.table_name.clone()),
+    ]);
+    Arc::new(Schema::new_with_metadata(fields, metadata))
+}
+
+fn arrow_type(column: &RowColumn) -> DataType {
+    let unsigned = column.is_signed() == Some(false);
+    match column.column_type {
+        ColumnType::Tiny if unsigned => DataType::UInt8,
+        ColumnType::Tiny => DataType::Int8,
+        ColumnType::Short if unsigned => DataType::UInt16,
+        ColumnType::Short => DataType::Int16,
+        ColumnType::Int24 | ColumnType::Long if unsigned => DataType::UInt32,
+        ColumnType::Int24 | ColumnType::Long => DataType::Int32,
+        ColumnType::LongLong if unsigned => DataType::UInt64,
+        ColumnType::LongLong => DataType::Int64,
+        ColumnType::Float => DataType::Float32,
+        ColumnType::Double => DataType::Float64,
+        ColumnType::NewDecimal => {
+            let precision = (column.column_meta & 0xFF) as u8;
+            let scale = (column.column_meta >> 8) as i8;
+            if precision <= 38 {
+                DataType::Decimal128(precision, scale)
+            } else {
+                DataType::Utf8
+            }
+        }
+        ColumnType::Date => DataType::Date32,
+        // MySQL TIME may be negative or exceed 24 hours, so it is a duration rather than a time of day
+        ColumnType::Time | ColumnType::Time2 => DataType::Duration(TimeUnit::Microsecond),
+        ColumnType::DateTime | ColumnType::DateTime2 => {
+            DataType::Timestamp(TimeUnit::Microsecond, None)
+        }
+        ColumnType::TimeStamp | ColumnType::TimeStamp2 => {
+            DataType::Timestamp(TimeUnit::Microsecond, Some(UTC.into()))
+        }
+        ColumnType::Year => DataType::UInt16,
+        ColumnType::Bit if value_util::bit_count(column) == 1 => DataType::Boolean,
+        ColumnType::Bit => DataType::UInt64,
+        ColumnType::Enum => {
+            if column
+                .metadata
+                .is_some_and(|m| m.enum_string_values.is_some())
+            {
+                DataType::Utf8
+            } else {
+                DataType::UInt32
+            }
+        }
+        ColumnType::Set => {
+            if column
+                .metadata
+                .is_some_and(|m| m.set_string_values.is_some())
+            {
+                DataType::Utf8
+            } else {
+                DataType::UInt64
+            }
+        }
+        ColumnType::Json => DataType::Utf8,
+        ColumnType::Geometry => DataType::Binary,
+        ColumnType::Vector => {
+            DataType::List(Arc::new(Field::new_list_field(DataType::Float32, true)))
+        }
+        _ => {
+            if column.is_binary() {
+                DataType::Binary
+            } else {
+                DataType::Utf8
+            }
+        }
+    }
+}
+
+/// Converts row changes of the same table into a RecordBatch with the schema.
+pub fn to_record_batch(
+    schema: SchemaRef,
+    changes: &[RowChange],
+) -> Result<RecordBatch, BinlogError> {
+    let rows = changes
+        .iter()
+        .map(|change| {
+            change.key_row().ok_or_else(|| {
+                BinlogError::UnexpectedData("row change contains no row image".to_string())
+            })
+        })
+        .collect::<Result<Vec<Row>, BinlogError>>()?;
+
+    let column_count = schema.fields().len() - 5;
+    let mut arrays: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+    for (i, field) in schema.fields().iter().take(column_count).enumerate() {
+        let columns: Vec<&RowColumn> = rows.iter().map(|row| &row.columns[i]).collect();
+        arrays.push(build_array(field.data_type(), &columns)?);
+    }
+
+    let op = |change: &RowChange| match change.operation {
+        RowOperation::Insert => "c",
+        RowOperation::Update => "u",
+        RowOperation::Delete => "d",
+        RowOperation::Read => "r",
+    };
+    arrays.push(Arc::new(StringArray::from_iter_values(
+        changes.iter().map(op),
+    )));
+    arrays.push(Arc::new(StringArray::from_iter(
+        changes.iter().map(|c| c.source.gtid.as_deref()),
+    )));
+    arrays.push(Arc::new(StringArray::from_iter_values(
+        changes.iter().map(|c| c.source.binlog_filename.as_str()),
+    )));
+    arrays.push(Arc::new(UInt64Array::from_iter_values(
+        changes.iter().map(|c| c.source.binlog_position),
+    )));
+    arrays.push(Arc::new(
+        TimestampSecondArray::from_iter_values(changes.iter().map(|c| c.source.timestamp as i64))
+            .with_timezone(UTC),
+    ));
+
+    Ok(RecordBatch::try_new(schema, arrays)?)
+}
+
+fn build_array(data_type: &DataType, columns: &[&RowColumn]) -> Result<ArrayRef, BinlogError> {
+    let integers = || columns.iter().map(|c| value_util::integer_value(c));
+    let unsigned = || {
+        columns.iter().map(|c| match c.value {
+            ColumnValue::Bit(v) | ColumnValue::Set(v) => Some(*v),
+            ColumnValue::Enum(v) => Some(*v as u64),
+            _ => value_util::integer_value(c).map(|v| v as u64),
+        })
+    };
+
+    let array: ArrayRef = match data_type {
+        DataType::Int8 => Arc::new(Int8Array::from_iter(integers().map(|v| v.map(|v| v as i8)))),
+        DataType::Int16 => Arc::new(Int16Array::from_iter(
+            integers().map(|v| v.map(|v| v as i16)),
+        )),
+        DataType::Int32 => Arc::new(Int32Array::from_iter(
+            integers().map(|v| v.map(|v| v as i32)),
+        )),
+        DataType::Int64 => Arc::new(Int64Array::from_iter(
+            integers().map(|v| v.map(|v| v as i64)),
+        )),
+        DataType::UInt8 => Arc::new(UInt8Array::from_iter(
+            unsigned().map(|v| v.map(|v| v as u8)),
+        )),
+        DataType::UInt16 => Arc::new(UInt16Array::from_iter(
+            unsigned().map(|v| v.map(|v| v as u16)),
+        )),
+        DataType::UInt32 => Arc::new(UInt32Array::from_iter(
+            unsigned().map(|v| v.map(|v| v as u32)),
+        )),
+        DataType::UInt64 => Arc::new(UInt64Array::from_iter(unsigned())),
+
+        DataType::Float32 => Arc::new(Float32Array::from_iter(columns.iter().map(
+            |c| match c.value {
+                ColumnValue::Float(v) => Some(*v),
+                _ => None,
+            },
+        ))),
+        DataType::Float64 => Arc::new(Float64Array::from_iter(columns.iter().map(
+            |c| match c.value {
+                ColumnValue::Double(v) => Some(*v),
+                _ => None,
+            },
+        ))),
+
+        DataType::Decimal128(precision, scale) => {
+            let values = columns
+                .iter()
+                .map(|c| match c.value {
+                    ColumnValue::Decimal(v) => parse_decimal128(v).map(Some),
+                    _ => Ok(None),
+                })
+                .collect::<Result<Vec<Option<i128>>, BinlogError>>()?;
+            Arc::new(
+                Decimal128Array::from_iter(values).with_precision_and_scale(*precision, *scale)?,
+            )
+        }
+
+        DataType::Date32 => Arc::new(Date32Array::from_iter(columns.iter().map(
+            |c| match c.value {
+                ColumnValue::Date(v) => value_util::parse_date_days(v).map(|d| d as i32),
+                _ => None,
+            },
+        ))),
+
+        DataType::Duration(TimeUnit::Microsecond) => Arc::new(DurationMicrosecondArray::from_iter(
+            columns.iter().map(|c| match c.value {
+                ColumnValue::Time(v) => value_util::parse_time_micros(v),
+                _ => None,
+            }),
+        )),
+
+        DataType::Timestamp(TimeUnit::Microsecond, timezone) => {
+            let array = TimestampMicrosecondArray::from_iter(columns.iter().map(|c| {
+                match c.value {
+                    ColumnValue::DateTime(v) => value_util::parse_datetime_micros(v),
+                    // zero TIMESTAMP 0000-00-00 00:00:00 is stored as 0
+                    ColumnValue::Timestamp(v) if *v != 0 => Some(*v),
+                    _ => None,
+                }
+            }));
+            Arc::new(array.with_timezone_opt(timezone.clone()))
+        }
+
+        DataType::Boolean => Arc::new(BooleanArray::from_iter(columns.iter().map(
+            |c| match c.value {
+                ColumnValue::Bit(v) => Some(*v != 0),
+                _ => None,
+            },
+        ))),
+
+        DataType::Binary => Arc::new(BinaryArray::from_iter(columns.iter().map(
+            |c| match c.value {
+                ColumnValue::String(v) | ColumnValue::Blob(v) => Some(v.as_slice()),
+                _ => None,
+            },
+        ))),
+
+        DataType::Utf8 => {
+            let values = columns
+                .iter()
+                .map(|c| text_value(c))
+                .collect::<Result<Vec<Option<String>>, BinlogError>>()?;
+            Arc::new(StringArray::from_iter(values))
+        }
+
+        DataType::List(_) => {
+            let mut builder = ListBuilder::new(Float32Builder::new());
+            for column in columns {
+                match column.value {
+                    ColumnValue::Vector(v) => {
+                        builder.values().append_slice(v);
+                        builder.append(true);
+                    }
+                    _ => builder.append(false),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+
+        _ => {
+            return Err(BinlogError::UnexpectedData(format!(
+                "unsupported arrow type: {}",
+                data_type
+            )))
+        }
+    };
+    Ok(array)
+}
+
+fn text_value(column: &RowColumn) -> Result<Option<String>, BinlogError> {
+    let value = match column.value {
+        ColumnValue::None => None,
+        ColumnValue::Decimal(v) => Some(v.clone()),
+        ColumnValue::String(v) | ColumnValue::Blob(v) => Some(value_util::text(v)),
+        ColumnValue::Enum(_) => column.enum_string().map(|s| s.to_string()),
+        ColumnValue::Set(_) => column.set_strings().map(|s| s.join(",")),
+        ColumnValue::Json(v) if v.is_empty() => None,
+        ColumnValue::Json(v) => Some(JsonBinary::parse_as_string(v)?),
+        _ => None,
+    };
+    Ok(value)
+}
+
+/// Parses a decimal string into the unscaled i128 value, the fraction always has
+/// exactly `scale` digits as decoded by the parser.
+fn parse_decimal128(decimal: &str) -> Result<i128, BinlogError> {
+    decimal
+        .replacen('.', "", 1)
+        .parse::<i128>()
+        .map_err(|_| BinlogError::UnexpectedData(format!("invalid decimal: {}", decimal)))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{cast::AsArray, types::Float32Type, Array};
+
+    use super::*;
+    use crate::{
+        change::change_event::ChangeSource,
+        event::table_map::table_metadata::{ColumnMetadata, TableMetadata},
+    };
+
+    fn create_table_map_event(column_count: usize) -> Arc<TableMapEvent> {
+        let names = ["id", "price", "created_at", "name", "v"];
+        // LONG UNSIGNED, NEWDECIMAL(10,2), DATETIME2(6), VARCHAR, VECTOR
+        let column_types = [3, 246, 18, 15, 242];
+        let column_metas = [0, (2 << 8) | 10, 6, 80, 4];
+        Arc::new(TableMapEvent {
+            table_id: 1,
+            flags: 1,
+            database_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            column_types: column_types[..column_count].to_vec(),
+            column_metas: column_metas[..column_count].to_vec(),
+            null_bits: (0..column_count).map(|i| i != 0).collect(),
+            table_metadata: Some(TableMetadata {
+                default_charset: None,
+                enum_and_set_default_charset: None,
+                columns: names[..column_count]
+                    .iter()
+                    .map(|name| ColumnMetadata {
+                        column_name: Some(name.to_string()),
+                        is_signed: Some(*name != "id"),
+                        ..Default::default()
+                    })
+                    .collect(),
+            }),
+        })
+    }
+
+    fn create_row_change(
+        table_map_event: Arc<TableMapEvent>,
+        operation: RowOperation,
+        values: Vec<ColumnValue>,
+    ) -> RowChange {
+        let row = RowEvent {
+            column_values: values,
+        };
+        RowChange {
+            operation,
+            source: ChangeSource {
+                timestamp: 1700000000,
+                binlog_filename: "mysql-bin.000001".into(),
+                binlog_position: 120,
+                gtid: Some("5ca6b6a2-1de8-11ee-a4d4-0242ac110002:7".into()),
+                ..Default::default()
+            },
+            row_index: 0,
+            table_map_event,
+            before: (operation == RowOperation::Delete).then(|| row.clone()),
+            after: (operation != RowOperation::Delete).then_some(row),
+            included_columns_before: Vec::new(),
+            included_columns_after: Vec::new(),
+        }
+    }
+
+    fn row(id: i32) -> Vec<ColumnValue> {
+        vec![
+            ColumnValue::Long(id),
+            ColumnValue::Decimal("-12.50".into()),
+            ColumnValue::DateTime("0000-00-00 00:00:00.000000".into()),
+            ColumnValue::String(b"abc".to_vec()),
+            ColumnValue::Vector(vec![1.0, 2.0]),
+        ]
+    }
+
+    #[test]
+    fn test_schema() {
+        let schema = arrow_schema_from_table_map_event(&create_table_map_event(5));
+        let types: Vec<(&str, &DataType, bool)> = schema
+            .fields()
+            .iter()
+            .map(|f| (f.name().as_str(), f.data_type(), f.is_nullable()))
+            .collect();
+        assert_eq!(types[0], ("id", &DataType::UInt32, false));
+        assert_eq!(types[1], ("price", &DataType::Decimal128(10, 2), true));
+        assert_eq!(
+            types[2],
+            (
+                "created_at",
+                &DataType::Timestamp(TimeUnit::Microsecond, None),
+                true
+            )
+        );
+        assert_eq!(types[3], ("name", &DataType::Utf8, true));
+        assert_eq!(types[5].0, OPERATION_COLUMN);
+        assert_eq!(types.len(), 10);
+        assert_eq!(schema.metadata()["table"], "tb_1");
+    }
+
+    #[test]
+    fn test_batches() {
+        let mut batcher = ArrowBatcher::new().with_max_rows(2);
+        let table_map_event = create_table_map_event(5);
+
+        let change = create_row_change(table_map_event.clone(), RowOperation::Insert, row(-1));
+        assert!(batcher.push(&change).unwrap().is_empty());
+        let change = create_row_change(table_map_event.clone(), RowOperation::Delete, row(2));
+        let batches = batcher.push(&change).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batcher.pending_rows(), 0);
+
+        let batch = &batches[0].batch;
+        assert_eq!(batch.num_rows(), 2);
+        let ids = batch
+            .column(0)
+            .as_primitive::<arrow_array::types::UInt32Type>();
+        assert_eq!(ids.value(0), u32::MAX);
+        let prices = batch
+            .column(1)
+            .as_primitive::<arrow_array::types::Decimal128Type>();
+        assert_eq!(prices.value(0), -1250);
+        assert!(batch.column(2).is_null(0));
+        assert_eq!(batch.column(3).as_string::<i32>().value(1), "abc");
+        let vectors = batch.column(4).as_list::<i32>();
+        assert_eq!(
+            vectors.value(0).as_primitive::<Float32Type>().values(),
+            &[1.0, 2.0]
+        );
+        let ops = batch.column(5).as_string::<i32>();
+        assert_eq!((ops.value(0), ops.value(1)), ("c", "d"));
+        assert_eq!(
+            batch
+                .column(8)
+                .as_primitive::<arrow_array::types::UInt64Type>()
+                .value(0),
+            120
+        );
+    }
+
+    #[test]
+    fn test_flush_on_schema_change_and_time() {
+        let mut batcher = ArrowBatcher::new().with_max_interval(Duration::from_secs(3600));
+        let change = create_row_change(
+            create_table_map_event(1),
+            RowOperation::Insert,
+            vec![ColumnValue::Long(1)],
+        );
+        assert!(batcher.push(&change).unwrap().is_empty());
+        assert!(batcher.poll().unwrap().is_empty());
+
+        // a column is added
+        let change = create_row_change(
+            create_table_map_event(2),
+            RowOperation::Insert,
+            vec![ColumnValue::Long(2), ColumnValue::None],
+        );
+        let batches = batcher.push(&change).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].batch.num_columns(), 6);
+        assert_eq!(batcher.pending_rows(), 1);
+
+        batcher.max_interval = Duration::ZERO;
+        let batches = batcher.poll().unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].batch.num_columns(), 7);
+        assert_eq!(batcher.pending_rows(), 0);
+    }
+}
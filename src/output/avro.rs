@@ -0,0 +1,717 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    binlog_error::BinlogError,
+    change::change_event::{RowChange, RowOperation},
+    column::{column_type::ColumnType, column_value::ColumnValue, json::json_binary::JsonBinary},
+    event::{
+        row::{Row, RowColumn},
+        row_event::RowEvent,
+        table_map_event::TableMapEvent,
+    },
+};
+
+use super::value_util;
+
+/// Avro type of a column,
+/// refer: https://avro.apache.org/docs/1.11.1/specification/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AvroType {
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+    /// bytes with logicalType decimal.
+    Decimal {
+        precision: u32,
+        scale: u32,
+    },
+    /// int with logicalType date, days since epoch.
+    Date,
+    /// long with logicalType time-micros, MySQL TIME may be negative or exceed 24 hours.
+    TimeMicros,
+    /// long with logicalType timestamp-micros, for TIMESTAMP columns.
+    TimestampMicros,
+    /// long with logicalType local-timestamp-micros, for DATETIME columns.
+    LocalTimestampMicros,
+    /// The first symbol stands for the '' value (index 0) stored for invalid ENUM values.
+    Enum {
+        name: String,
+        symbols: Vec<String>,
+    },
+    Array(Box<AvroType>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvroField {
+    pub name: String,
+    pub field_type: AvroType,
+    pub nullable: bool,
+}
+
+/// An Avro record schema derived from a TableMapEvent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvroSchema {
+    pub namespace: String,
+    pub name: String,
+    /// Starts from 1 and increases each time the table changes shape.
+    pub version: u32,
+    pub fields: Vec<AvroField>,
+}
+
+/// A row encoded in Avro binary.
+#[derive(Debug, Clone)]
+pub struct AvroRecord {
+    pub schema: Arc<AvroSchema>,
+    /// Whether the schema is a new version, which should be registered before the record is published.
+    pub is_new_schema: bool,
+    pub operation: RowOperation,
+    /// The after image for inserts and updates, the before image for deletes.
+    pub payload: Vec<u8>,
+    /// The before image of updates encoded with the same schema, None for inserts and deletes.
+    pub before_payload: Option<Vec<u8>>,
+}
+
+/// Encodes row changes into Avro binary, schemas are derived from the table map events
+/// and cached per table, a new schema version is created when a table changes shape.
+#[derive(Debug, Clone, Default)]
+pub struct AvroEncoder {
+    schemas: HashMap<(String, String), Arc<AvroSchema>>,
+}
+
+impl AvroEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the current schema of a table.
+    pub fn get_schema(&self, database_name: &str, table_name: &str) -> Option<Arc<AvroSchema>> {
+        self.schemas
+            .get(&(database_name.to_string(), table_name.to_string()))
+            .cloned()
+    }
+
+    /// Encodes a row change, both images are encoded for updates, so non-nullable columns
+    /// missing from the before image (binlog_row_image=MINIMAL) are errors.
+    pub fn encode(&mut self, change: &RowChange) -> Result<AvroRecord, BinlogError> {
+        let (schema, is_new_schema) = self.get_or_update_schema(&change.table_map_event);
+        let row = change.key_row().ok_or_else(|| {
+            BinlogError::UnexpectedData("row change contains no row image".to_string())
+        })?;
+        let before_payload = match (change.operation, change.before_row()) {
+            (RowOperation::Update, Some(before)) => Some(encode_row(&schema, &before)?),
+            _ => None,
+        };
+
+        Ok(AvroRecord {
+            payload: encode_row(&schema, &row)?,
+            before_payload,
+            schema,
+            is_new_schema,
+            operation: change.operation,
+        })
+    }
+
+    fn get_or_update_schema(&mut self, table_map_event: &TableMapEvent) -> (Arc<AvroSchema>, bool) {
+        let key = (
+            table_map_event.database_name.clone(),
+            table_map_event.table_name.clone(),
+        );
+        let mut schema = AvroSchema::from_table_map_event(table_map_event);
+        if let Some(current) = self.schemas.get(&key) {
+            if current.fields == schema.fields {
+                return (current.clone(), false);
+            }
+            schema.version = current.version + 1;
+        }
+
+        let schema = Arc::new(schema);
+        self.schemas.insert(key, schema.clone());
+        (schema, true)
+    }
+}
+
+impl AvroSchema {
+    pub fn from_table_map_event(table_map_event: &TableMapEvent) -> Self {
+        // resolve column types and charsets the same way as decoded rows
+        let row_event = RowEvent {
+            column_values: vec![ColumnValue::None; table_map_event.column_types.len()],
+        };
+        let row = Row::new(&row_event, table_map_event);
+        let mut names = HashSet::new();
+        let fields = row
+            .columns
+            .iter()
+            .map(|column| {
+                let name = avro_name(&column.name().map_or_else(
+                    || format!("col_{}", column.index + 1),
+                    |name| name.to_string(),
+                ));
+                // different columns like a-b and a_b may map to the same name
+                let name = unique_name(&name, &mut names);
+                let field_type = avro_type(column, &name);
+                AvroField {
+                    // MySQL allows zero dates in NOT NULL columns, which are encoded as null
+                    nullable: column.is_nullable || is_temporal(&field_type),
+                    name,
+                    field_type,
+                }
+            })
+            .collect();
+
+        Self {
+            namespace: avro_name(&table_map_event.database_name),
+            name: avro_name(&table_map_event.table_name),
+            version: 1,
+            fields,
+        }
+    }
+
+    /// Gets the schema in Avro JSON format.
+    pub fn to_json(&self) -> Value {
+        let fields: Vec<Value> = self
+            .fields
+            .iter()
+            .map(|field| {
+                let field_type = type_to_json(&field.field_type);
+                if field.nullable {
+                    json!({"name": field.name, "type": ["null", field_type], "default": null})
+                } else {
+                    json!({"name": field.name, "type": field_type})
+                }
+            })
+            .collect();
+
+        json!({
+            "type": "record",
+            "name": self.name,
+            "namespace": self.namespace,
+            "fields": fields,
+        })
+    }
+}
+
+fn avro_type(column: &RowColumn, field_name: &str) -> AvroType {
+    let unsigned = column.is_signed() == Some(false);
+    match column.column_type {
+        ColumnType::Tiny | ColumnType::Short | ColumnType::Int24 | ColumnType::Year => {
+            AvroType::Int
+        }
+        ColumnType::Long if unsigned => AvroType::Long,
+        ColumnType::Long => AvroType::Int,
+        ColumnType::LongLong if unsigned => AvroType::Decimal {
+            precision: 20,
+            scale: 0,
+        },
+        ColumnType::LongLong => AvroType::Long,
+        ColumnType::Float => AvroType::Float,
+        ColumnType::Double => AvroType::Double,
+        ColumnType::NewDecimal => AvroType::Decimal {
+            precision: (column.column_meta & 0xFF) as u32,
+            scale: (column.column_meta >> 8) as u32,
+        },
+        ColumnType::Date => AvroType::Date,
+        ColumnType::Time | ColumnType::Time2 => AvroType::TimeMicros,
+        ColumnType::DateTime | ColumnType::DateTime2 => AvroType::LocalTimestampMicros,
+        ColumnType::TimeStamp | ColumnType::TimeStamp2 => AvroType::TimestampMicros,
+        ColumnType::Bit if value_util::bit_count(column) == 1 => AvroType::Boolean,
+        ColumnType::Bit | ColumnType::Geometry => AvroType::Bytes,
+        ColumnType::Enum => {
+            let symbols = column.metadata.and_then(|m| m.enum_string_values.as_ref());
+            match symbols {
+                // symbols must be valid Avro names
+                Some(symbols) if symbols.iter().all(|s| avro_name(s) == *s) => {
+                    let mut used: HashSet<String> = symbols.iter().cloned().collect();
+                    let mut all_symbols = vec![unique_name("_", &mut used)];
+                    all_symbols.extend(symbols.iter().cloned());
+                    AvroType::Enum {
+                        name: format!("{}_enum", field_name),
+                        symbols: all_symbols,
+                    }
+                }
+                Some(_) => AvroType::String,
+                None => AvroType::Int,
+            }
+        }
+        ColumnType::Set => {
+            if column
+                .metadata
+                .is_some_and(|m| m.set_string_values.is_some())
+            {
+                AvroType::Array(Box::new(AvroType::String))
+            } else {
+                AvroType::Long
+            }
+        }
+        ColumnType::Vector => AvroType::Array(Box::new(AvroType::Float)),
+        ColumnType::Json => AvroType::String,
+        _ => {
+            if column.is_binary() {
+                AvroType::Bytes
+            } else {
+                AvroType::String
+            }
+        }
+    }
+}
+
+fn is_temporal(avro_type: &AvroType) -> bool {
+    matches!(
+        avro_type,
+        AvroType::Date | AvroType::LocalTimestampMicros | AvroType::TimestampMicros
+    )
+}
+
+fn type_to_json(avro_type: &AvroType) -> Value {
+    match avro_type {
+        AvroType::Boolean => json!("boolean"),
+        AvroType::Int => json!("int"),
+        AvroType::Long => json!("long"),
+        AvroType::Float => json!("float"),
+        AvroType::Double => json!("double"),
+        AvroType::Bytes => json!("bytes"),
+        AvroType::String => json!("string"),
+        AvroType::Decimal { precision, scale } => {
+            json!({"type": "bytes", "logicalType": "decimal", "precision": precision, "scale": scale})
+        }
+        AvroType::Date => json!({"type": "int", "logicalType": "date"}),
+        AvroType::TimeMicros => json!({"type": "long", "logicalType": "time-micros"}),
+        AvroType::TimestampMicros => json!({"type": "long", "logicalType": "timestamp-micros"}),
+        AvroType::LocalTimestampMicros => {
+            json!({"type": "long", "logicalType": "local-timestamp-micros"})
+        }
+        AvroType::Enum { name, symbols } => {
+            json!({"type": "enum", "name": name, "symbols": symbols})
+        }
+        AvroType::Array(items) => json!({"type": "array", "items": type_to_json(items)}),
+    }
+}
+
+/// Converts a MySQL identifier to a valid Avro name: [A-Za-z_][A-Za-z0-9_]*
+fn avro_name(name: &str) -> String {
+    let mut result: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !result.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        result.insert(0, '_');
+    }
+    result
+}
+
+/// Appends a suffix (_2, _3 ...) to the name if it is already used.
+fn unique_name(name: &str, used: &mut HashSet<String>) -> String {
+    let mut result = name.to_string();
+    let mut suffix = 2;
+    while used.contains(&result) {
+        result = format!("{}_{}", name, suffix);
+        suffix += 1;
+    }
+    used.insert(result.clone());
+    result
+}
+
+/// Encodes a row in Avro binary by the schema derived from its table map event.
+pub fn encode_row(schema: &AvroSchema, row: &Row) -> Result<Vec<u8>, BinlogError> {
+    if schema.fields.len() != row.columns.len() {
+        return Err(BinlogError::UnexpectedData(format!(
+            "schema has {} fields but row has {} columns",
+            schema.fields.len(),
+            row.columns.len()
+        )));
+    }
+
+    let mut buf = Vec::new();
+    for (field, column) in schema.fields.iter().zip(row.columns.iter()) {
+        let is_null = match (&field.field_type, column.value) {
+            (_, ColumnValue::None) => true,
+            (AvroType::Date, ColumnValue::Date(v)) => value_util::parse_date_days(v).is_none(),
+            (AvroType::LocalTimestampMicros, ColumnValue::DateTime(v)) => {
+                value_util::parse_datetime_micros(v).is_none()
+            }
+            (AvroType::TimestampMicros, ColumnValue::Timestamp(v)) => *v == 0,
+            _ => false,
+        };
+
+        if is_null {
+            if !field.nullable {
+                return Err(BinlogError::UnexpectedData(format!(
+                    "null value for non-nullable field: {}",
+                    field.name
+                )));
+            }
+            // index of the null branch in the union
+            write_long(&mut buf, 0);
+            continue;
+        }
+
+        if field.nullable {
+            write_long(&mut buf, 1);
+        }
+        encode_value(&mut buf, &field.field_type, column)?;
+    }
+    Ok(buf)
+}
+
+fn encode_value(
+    buf: &mut Vec<u8>,
+    avro_type: &AvroType,
+    column: &RowColumn,
+) -> Result<(), BinlogError> {
+    let mismatch = || {
+        BinlogError::UnexpectedData(format!(
+            "can not encode {:?} as avro {:?}",
+            column.value, avro_type
+        ))
+    };
+
+    match (avro_type, column.value) {
+        (AvroType::Boolean, ColumnValue::Bit(v)) => buf.push((*v != 0) as u8),
+
+        (AvroType::Int | AvroType::Long, _) => {
+            let value = match column.value {
+                ColumnValue::Enum(v) => *v as i128,
+                ColumnValue::Set(v) => *v as i64 as i128,
+                _ => value_util::integer_value(column).ok_or_else(mismatch)?,
+            };
+            write_long(buf, value as i64);
+        }
+
+        (AvroType::Float, ColumnValue::Float(v)) => buf.extend_from_slice(&v.to_le_bytes()),
+
+        (AvroType::Double, ColumnValue::Double(v)) => buf.extend_from_slice(&v.to_le_bytes()),
+
+        (AvroType::Decimal { .. }, ColumnValue::Decimal(v)) => {
+            let (bytes, _) = value_util::decimal_unscaled_bytes(v)?;
+            write_bytes(buf, &bytes);
+        }
+
+        (AvroType::Decimal { .. }, ColumnValue::LongLong(v)) => {
+            let (bytes, _) = value_util::decimal_unscaled_bytes(&(*v as u64).to_string())?;
+            write_bytes(buf, &bytes);
+        }
+
+        (AvroType::Date, ColumnValue::Date(v)) => {
+            write_long(buf, value_util::parse_date_days(v).ok_or_else(mismatch)?)
+        }
+
+        (AvroType::TimeMicros, ColumnValue::Time(v)) => {
+            write_long(buf, value_util::parse_time_micros(v).ok_or_else(mismatch)?)
+        }
+
+        (AvroType::LocalTimestampMicros, ColumnValue::DateTime(v)) => write_long(
+            buf,
+            value_util::parse_datetime_micros(v).ok_or_else(mismatch)?,
+        ),
+
+        (AvroType::TimestampMicros, ColumnValue::Timestamp(v)) => write_long(buf, *v),
+
+        (AvroType::Bytes, ColumnValue::String(v) | ColumnValue::Blob(v)) => write_bytes(buf, v),
+
+        (AvroType::Bytes, ColumnValue::Bit(v)) => {
+            write_bytes(buf, &value_util::bit_bytes(column, *v))
+        }
+
+        (AvroType::String, ColumnValue::String(v) | ColumnValue::Blob(v)) => {
+            write_bytes(buf, value_util::text(v).as_bytes())
+        }
+
+        (AvroType::String, ColumnValue::Enum(_)) => {
+            write_bytes(buf, column.enum_string().ok_or_else(mismatch)?.as_bytes())
+        }
+
+        (AvroType::String, ColumnValue::Json(v)) => {
+            let json = if v.is_empty() {
+                "null".to_string()
+            } else {
+                JsonBinary::parse_as_string(v)?
+            };
+            write_bytes(buf, json.as_bytes())
+        }
+
+        (AvroType::Enum { symbols, .. }, ColumnValue::Enum(v)) => {
+            // index 0 is the '' value of invalid values, which is the first symbol
+            if *v as usize >= symbols.len() {
+                return Err(mismatch());
+            }
+            write_long(buf, *v as i64);
+        }
+
+        (AvroType::Array(_), ColumnValue::Set(_)) => {
+            let strings = column.set_strings().ok_or_else(mismatch)?;
+            write_array(buf, strings.len(), |buf| {
+                for s in strings.iter() {
+                    write_bytes(buf, s.as_bytes());
+                }
+            });
+        }
+
+        (AvroType::Array(_), ColumnValue::Vector(v)) => {
+            write_array(buf, v.len(), |buf| {
+                for f in v.iter() {
+                    buf.extend_from_slice(&f.to_le_bytes());
+                }
+            });
+        }
+
+        _ => return Err(mismatch()),
+    }
+    Ok(())
+}
+
+/// Writes a long in zig-zag variable-length encoding.
+fn write_long(buf: &mut Vec<u8>, value: i64) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n & !0x7F != 0 {
+        buf.push((n & 0x7F) as u8 | 0x80);
+        n >>= 7;
+    }
+    buf.push(n as u8);
+}
+
+fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    write_long(buf, bytes.len() as i64);
+    buf.extend_from_slice(bytes);
+}
+
+/// Writes an array as a single block followed by the end marker.
+fn write_array(buf: &mut Vec<u8>, count: usize, write_items: impl FnOnce(&mut Vec<u8>)) {
+    if count > 0 {
+        write_long(buf, count as i64);
+        write_items(buf);
+    }
+    write_long(buf, 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        change::change_event::ChangeSource,
+        event::table_map::table_metadata::{ColumnMetadata, TableMetadata},
+    };
+
+    fn create_table_map_event(column_types: Vec<u8>, column_metas: Vec<u16>) -> TableMapEvent {
+        let column_metadata = |name: &str| ColumnMetadata {
+            column_name: Some(name.to_string()),
+            is_signed: Some(name != "count"),
+            enum_string_values: (name == "status").then(|| vec!["on".into(), "off".into()]),
+            ..Default::default()
+        };
+        let names = ["id", "price", "status", "name", "created_at", "count"];
+
+        TableMapEvent {
+            table_id: 1,
+            flags: 1,
+            database_name: "db-1".into(),
+            table_name: "tb_1".into(),
+            null_bits: (0..column_types.len()).map(|i| i != 0).collect(),
+            table_metadata: Some(TableMetadata {
+                default_charset: None,
+                enum_and_set_default_charset: None,
+                columns: names[..column_types.len()]
+                    .iter()
+                    .map(|name| column_metadata(name))
+                    .collect(),
+            }),
+            column_types,
+            column_metas,
+        }
+    }
+
+    fn create_row_change(table_map_event: TableMapEvent, values: Vec<ColumnValue>) -> RowChange {
+        RowChange {
+            operation: RowOperation::Insert,
+            source: ChangeSource::default(),
+            row_index: 0,
+            table_map_event: Arc::new(table_map_event),
+            before: None,
+            after: Some(RowEvent {
+                column_values: values,
+            }),
+            included_columns_before: Vec::new(),
+            included_columns_after: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_schema() {
+        // LONG, NEWDECIMAL(10,2), ENUM, VARCHAR, DATETIME2(6)
+        let table_map_event = create_table_map_event(
+            vec![3, 246, 254, 15, 18],
+            vec![0, (2 << 8) | 10, (247 << 8) | 1, 80, 6],
+        );
+        let schema = AvroSchema::from_table_map_event(&table_map_event);
+        assert_eq!(
+            schema.to_json(),
+            json!({
+                "type": "record",
+                "name": "tb_1",
+                "namespace": "db_1",
+                "fields": [
+                    {"name": "id", "type": "int"},
+                    {"name": "price", "type": ["null", {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}], "default": null},
+                    {"name": "status", "type": ["null", {"type": "enum", "name": "status_enum", "symbols": ["_", "on", "off"]}], "default": null},
+                    {"name": "name", "type": ["null", "string"], "default": null},
+                    {"name": "created_at", "type": ["null", {"type": "long", "logicalType": "local-timestamp-micros"}], "default": null},
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        let table_map_event = create_table_map_event(
+            vec![3, 246, 254, 15, 18],
+            vec![0, (2 << 8) | 10, (247 << 8) | 1, 80, 6],
+        );
+        let change = create_row_change(
+            table_map_event,
+            vec![
+                ColumnValue::Long(-2),
+                ColumnValue::Decimal("1.28".into()),
+                ColumnValue::Enum(2),
+                ColumnValue::String(b"ab".to_vec()),
+                ColumnValue::DateTime("0000-00-00 00:00:00".into()),
+            ],
+        );
+
+        let mut encoder = AvroEncoder::new();
+        let record = encoder.encode(&change).unwrap();
+        assert!(record.is_new_schema);
+        assert_eq!(record.schema.version, 1);
+        assert_eq!(
+            record.payload,
+            vec![
+                3, // id: -2 in zig-zag
+                2, 4, 0, 0x80, // price: union branch 1, 2 bytes, 128
+                2, 4, // status: union branch 1, symbol 2
+                2, 4, b'a', b'b', // name: union branch 1, "ab"
+                0,    // created_at: zero date as null
+            ]
+        );
+
+        let record = encoder.encode(&change).unwrap();
+        assert!(!record.is_new_schema);
+        assert!(record.before_payload.is_none());
+    }
+
+    #[test]
+    fn test_encode_invalid_enum() {
+        let change = create_row_change(
+            create_table_map_event(vec![3, 246, 254], vec![0, (2 << 8) | 10, (247 << 8) | 1]),
+            vec![
+                ColumnValue::Long(1),
+                ColumnValue::None,
+                ColumnValue::Enum(0),
+            ],
+        );
+        let record = AvroEncoder::new().encode(&change).unwrap();
+        // status: union branch 1, symbol 0 for ''
+        assert_eq!(&record.payload[2..], &[2, 0]);
+
+        let change = create_row_change(
+            create_table_map_event(vec![3, 246, 254], vec![0, (2 << 8) | 10, (247 << 8) | 1]),
+            vec![
+                ColumnValue::Long(1),
+                ColumnValue::None,
+                ColumnValue::Enum(3),
+            ],
+        );
+        assert!(AvroEncoder::new().encode(&change).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_field_names() {
+        let mut table_map_event = create_table_map_event(vec![3, 3, 3], vec![0, 0, 0]);
+        let columns = &mut table_map_event.table_metadata.as_mut().unwrap().columns;
+        columns[0].column_name = Some("a-b".into());
+        columns[1].column_name = Some("a_b".into());
+        columns[2].column_name = Some("a_b_2".into());
+        let schema = AvroSchema::from_table_map_event(&table_map_event);
+        let names: Vec<&str> = schema.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["a_b", "a_b_2", "a_b_2_2"]);
+    }
+
+    #[test]
+    fn test_encode_update() {
+        let mut change = create_row_change(
+            create_table_map_event(vec![3, 15], vec![0, 80]),
+            vec![ColumnValue::Long(1), ColumnValue::String(b"b".to_vec())],
+        );
+        change.operation = RowOperation::Update;
+        change.before = Some(RowEvent {
+            column_values: vec![ColumnValue::Long(1), ColumnValue::None],
+        });
+
+        let mut encoder = AvroEncoder::new();
+        let record = encoder.encode(&change).unwrap();
+        assert_eq!(record.payload, vec![2, 2, 2, b'b']);
+        assert_eq!(record.before_payload, Some(vec![2, 0]));
+    }
+
+    #[test]
+    fn test_schema_version() {
+        let mut encoder = AvroEncoder::new();
+        let change = create_row_change(
+            create_table_map_event(vec![3], vec![0]),
+            vec![ColumnValue::Long(1)],
+        );
+        assert_eq!(encoder.encode(&change).unwrap().schema.version, 1);
+
+        // a column is added, INT UNSIGNED is encoded as long
+        let change = create_row_change(
+            create_table_map_event(
+                vec![3, 246, 254, 15, 18, 3],
+                vec![0, (2 << 8) | 10, (247 << 8) | 1, 80, 6, 0],
+            ),
+            vec![
+                ColumnValue::Long(1),
+                ColumnValue::None,
+                ColumnValue::None,
+                ColumnValue::None,
+                ColumnValue::None,
+                ColumnValue::Long(-1),
+            ],
+        );
+        let record = encoder.encode(&change).unwrap();
+        assert!(record.is_new_schema);
+        assert_eq!(record.schema.version, 2);
+        assert_eq!(record.schema.fields[5].field_type, AvroType::Long);
+        // 4294967295 in zig-zag varint
+        assert_eq!(&record.payload[5..], &[2, 0xFE, 0xFF, 0xFF, 0xFF, 0x1F]);
+        assert_eq!(encoder.get_schema("db-1", "tb_1").unwrap().version, 2);
+    }
+
+    #[test]
+    fn test_write_long() {
+        let encode = |value: i64| {
+            let mut buf = Vec::new();
+            write_long(&mut buf, value);
+            buf
+        };
+        assert_eq!(encode(0), vec![0]);
+        assert_eq!(encode(-1), vec![1]);
+        assert_eq!(encode(1), vec![2]);
+        assert_eq!(encode(-64), vec![0x7F]);
+        assert_eq!(encode(64), vec![0x80, 0x01]);
+        assert_eq!(
+            encode(i64::MIN),
+            vec![0xFF; 9].into_iter().chain([0x01]).collect::<Vec<u8>>()
+        );
+    }
+}
@@ -0,0 +1,553 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    binlog_error::BinlogError,
+    change::{
+        change_event::{ChangeEvent, RowChange, RowOperation, SchemaChange},
+        transaction::Transaction,
+    },
+    column::{column_type::ColumnType, column_value::ColumnValue, json::json_binary::JsonBinary},
+    event::row::{Row, RowColumn},
+};
+
+use super::{time_zone::TimeZone, value_util};
+
+/// A Canal flat message, the JSON format of canal.mq.flatMessage=true,
+/// refer: https://github.com/alibaba/canal/blob/master/protocol/src/main/java/com/alibaba/otter/canal/protocol/FlatMessage.java
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CanalFlatMessage {
+    pub id: u64,
+    pub database: String,
+    pub table: String,
+    pub pk_names: Option<Vec<String>>,
+    pub is_ddl: bool,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    /// Binlog event time in milliseconds.
+    pub es: u64,
+    /// Processing time in milliseconds.
+    pub ts: u64,
+    pub sql: String,
+    pub sql_type: Option<Map<String, Value>>,
+    pub mysql_type: Option<Map<String, Value>>,
+    pub data: Option<Vec<Map<String, Value>>>,
+    pub old: Option<Vec<Map<String, Value>>>,
+    pub gtid: Option<String>,
+}
+
+/// Converts transactions into Canal flat messages, rows of the same rows event are
+/// grouped into one message like Canal does. All column values are strings.
+///
+/// TIMESTAMP values are printed in `time_zone`, UTC by default, set it to the time zone
+/// of the server to print them like the server does.
+#[derive(Debug, Clone)]
+pub struct CanalConverter {
+    /// Id of the next message, increased for each message.
+    pub next_id: u64,
+    /// Time zone of TIMESTAMP values.
+    pub time_zone: TimeZone,
+}
+
+impl CanalConverter {
+    pub fn new() -> Self {
+        Self {
+            next_id: 1,
+            time_zone: TimeZone::default(),
+        }
+    }
+
+    pub fn with_time_zone(mut self, time_zone: TimeZone) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
+    pub fn convert_transaction(
+        &mut self,
+        transaction: &Transaction,
+    ) -> Result<Vec<CanalFlatMessage>, BinlogError> {
+        let mut messages = Vec::new();
+        let mut rows: Vec<&RowChange> = Vec::new();
+        for event in transaction.changes.iter() {
+            match event {
+                ChangeEvent::Row(change) => {
+                    if let Some(last) = rows.last() {
+                        if !is_same_rows_event(last, change) {
+                            messages.push(self.convert_row_changes(&rows)?);
+                            rows.clear();
+                        }
+                    }
+                    rows.push(change);
+                }
+
+                ChangeEvent::Schema(change) => {
+                    if !rows.is_empty() {
+                        messages.push(self.convert_row_changes(&rows)?);
+                        rows.clear();
+                    }
+                    messages.push(self.convert_schema_change(change));
+                }
+
+                _ => {}
+            }
+        }
+
+        if !rows.is_empty() {
+            messages.push(self.convert_row_changes(&rows)?);
+        }
+        Ok(messages)
+    }
+
+    /// Converts row changes of the same table and operation into a message.
+    pub fn convert_row_changes(
+        &mut self,
+        changes: &[&RowChange],
+    ) -> Result<CanalFlatMessage, BinlogError> {
+        let first = changes
+            .first()
+            .ok_or_else(|| BinlogError::UnexpectedData("no row changes to convert".to_string()))?;
+
+        let mut data = Vec::with_capacity(changes.len());
+        let mut old = Vec::new();
+        for change in changes {
+            let (before, after) = (change.before_row(), change.after_row());
+            match (&before, &after) {
+                (Some(before), Some(after)) => {
+                    data.push(convert_row(after, self.time_zone)?);
+                    old.push(convert_changed_columns(before, after, self.time_zone)?);
+                }
+                (Some(row), None) | (None, Some(row)) => {
+                    data.push(convert_row(row, self.time_zone)?)
+                }
+                (None, None) => {}
+            }
+        }
+
+        let event_type = match first.operation {
+            RowOperation::Insert | RowOperation::Read => "INSERT",
+            RowOperation::Update => "UPDATE",
+            RowOperation::Delete => "DELETE",
+        };
+
+        let (mut sql_type, mut mysql_type, mut pk_names) = (Map::new(), Map::new(), Vec::new());
+        if let Some(row) = first.key_row() {
+            for column in row.columns.iter() {
+                let name = column.name_or_position();
+                sql_type.insert(name.clone(), Value::from(sql_type_code(column)));
+                mysql_type.insert(
+                    name.clone(),
+                    Value::from(value_util::column_definition(column)),
+                );
+                if column.is_primary_key() {
+                    pk_names.push(name);
+                }
+            }
+        }
+
+        Ok(CanalFlatMessage {
+            id: self.generate_id(),
+            database: first.database_name().to_string(),
+            table: first.table_name().to_string(),
+            pk_names: (!pk_names.is_empty()).then_some(pk_names),
+            is_ddl: false,
+            event_type: event_type.to_string(),
+            es: first.source.timestamp as u64 * 1000,
+            ts: value_util::now_millis(),
+            sql: first.source.query.clone().unwrap_or_default(),
+            sql_type: Some(sql_type),
+            mysql_type: Some(mysql_type),
+            data: Some(data),
+            old: (first.operation == RowOperation::Update).then_some(old),
+            gtid: first.source.gtid.clone(),
+        })
+    }
+
+    pub fn convert_schema_change(&mut self, change: &SchemaChange) -> CanalFlatMessage {
+        let (statement, object) = change.statement_type();
+        let event_type = match (statement.as_str(), object.as_str()) {
+            ("CREATE", "INDEX") => "CINDEX",
+            ("DROP", "INDEX") => "DINDEX",
+            ("DROP", _) => "ERASE",
+            ("CREATE" | "ALTER" | "RENAME" | "TRUNCATE", _) => statement.as_str(),
+            _ => "QUERY",
+        };
+        let (database, table) = change
+            .table()
+            .unwrap_or_else(|| (change.database.clone(), String::new()));
+
+        CanalFlatMessage {
+            id: self.generate_id(),
+            database,
+            table,
+            pk_names: None,
+            is_ddl: true,
+            event_type: event_type.to_string(),
+            es: change.source.timestamp as u64 * 1000,
+            ts: value_util::now_millis(),
+            sql: change.ddl.clone(),
+            sql_type: None,
+            mysql_type: None,
+            data: None,
+            old: None,
+            gtid: change.source.gtid.clone(),
+        }
+    }
+
+    fn generate_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+impl Default for CanalConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_same_rows_event(a: &RowChange, b: &RowChange) -> bool {
+    a.source.binlog_position == b.source.binlog_position
+        && a.operation == b.operation
+        && a.table_map_event.table_id == b.table_map_event.table_id
+}
+
+fn convert_row(row: &Row, time_zone: TimeZone) -> Result<Map<String, Value>, BinlogError> {
+    let mut map = Map::new();
+    for column in row.columns.iter() {
+        map.insert(column.name_or_position(), convert_value(column, time_zone)?);
+    }
+    Ok(map)
+}
+
+/// Converts the before values of the columns changed by an update.
+fn convert_changed_columns(
+    before: &Row,
+    after: &Row,
+    time_zone: TimeZone,
+) -> Result<Map<String, Value>, BinlogError> {
+    let mut map = Map::new();
+    for (before, after) in before.columns.iter().zip(after.columns.iter()) {
+        if before.value != after.value {
+            map.insert(before.name_or_position(), convert_value(before, time_zone)?);
+        }
+    }
+    Ok(map)
+}
+
+/// Converts a column value into its string representation like Canal does,
+/// TIMESTAMP values are printed in `time_zone`.
+pub fn convert_value(column: &RowColumn, time_zone: TimeZone) -> Result<Value, BinlogError> {
+    let fsp = value_util::fsp(column);
+    let value = match column.value {
+        ColumnValue::None => return Ok(Value::Null),
+
+        ColumnValue::Tiny(_)
+        | ColumnValue::Short(_)
+        | ColumnValue::Long(_)
+        | ColumnValue::LongLong(_)
+        | ColumnValue::Year(_) => value_util::integer_value(column)
+            .unwrap_or_default()
+            .to_string(),
+
+        ColumnValue::Float(v) => v.to_string(),
+
+        ColumnValue::Double(v) => v.to_string(),
+
+        ColumnValue::Decimal(v) => v.clone(),
+
+        ColumnValue::Date(v) => v.clone(),
+
+        ColumnValue::Time(v) | ColumnValue::DateTime(v) => {
+            value_util::truncate_fraction(v, fsp).to_string()
+        }
+
+        // zero TIMESTAMP 0000-00-00 00:00:00 is stored as 0
+        ColumnValue::Timestamp(0) => "0000-00-00 00:00:00".to_string(),
+
+        ColumnValue::Timestamp(v) => {
+            value_util::format_timestamp(time_zone.to_local_micros(*v)?, fsp)
+        }
+
+        ColumnValue::String(v) | ColumnValue::Blob(v) => {
+            if column.is_binary() {
+                // Canal decodes binary values as ISO-8859-1
+                v.iter().map(|b| *b as char).collect()
+            } else {
+                value_util::text(v)
+            }
+        }
+
+        ColumnValue::Bit(v) => v.to_string(),
+
+        ColumnValue::Enum(v) => match column.enum_string() {
+            Some(s) => s.to_string(),
+            None => v.to_string(),
+        },
+
+        ColumnValue::Set(v) => match column.set_strings() {
+            Some(strings) => strings.join(","),
+            None => v.to_string(),
+        },
+
+        ColumnValue::Json(v) => {
+            if v.is_empty() {
+                return Ok(Value::Null);
+            }
+            JsonBinary::parse_as_string(v)?
+        }
+
+        ColumnValue::Vector(v) => Value::from(v.clone()).to_string(),
+    };
+    Ok(Value::String(value))
+}
+
+/// Gets the java.sql.Types code of the column, as Canal puts in sqlType.
+pub fn sql_type_code(column: &RowColumn) -> i32 {
+    match column.column_type {
+        ColumnType::Bit => -7,
+        ColumnType::Tiny => -6,
+        ColumnType::Short => 5,
+        ColumnType::Int24 | ColumnType::Long => 4,
+        ColumnType::LongLong => -5,
+        ColumnType::Float => 7,
+        ColumnType::Double => 8,
+        ColumnType::Decimal | ColumnType::NewDecimal => 3,
+        ColumnType::Date | ColumnType::NewDate => 91,
+        ColumnType::Time | ColumnType::Time2 => 92,
+        ColumnType::DateTime
+        | ColumnType::DateTime2
+        | ColumnType::TimeStamp
+        | ColumnType::TimeStamp2 => 93,
+        ColumnType::Year | ColumnType::Json => 12,
+        ColumnType::String => {
+            if column.is_binary() {
+                -2
+            } else {
+                1
+            }
+        }
+        ColumnType::VarChar | ColumnType::VarString => {
+            if column.is_binary() {
+                -3
+            } else {
+                12
+            }
+        }
+        ColumnType::TinyBlob | ColumnType::MediumBlob | ColumnType::LongBlob | ColumnType::Blob => {
+            if column.is_binary() {
+                2004
+            } else {
+                2005
+            }
+        }
+        ColumnType::Enum | ColumnType::Set => 1,
+        ColumnType::Geometry | ColumnType::Vector => -2,
+        _ => 1111,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        change::change_event::ChangeSource,
+        event::{
+            row_event::RowEvent,
+            table_map::{
+                default_charset::DefaultCharset,
+                table_metadata::{ColumnMetadata, TableMetadata},
+            },
+            table_map_event::TableMapEvent,
+        },
+    };
+
+    fn create_table_map_event() -> Arc<TableMapEvent> {
+        let column_metadata =
+            |name: &str, is_primary_key: bool, is_signed: Option<bool>| ColumnMetadata {
+                column_name: Some(name.to_string()),
+                is_signed,
+                is_simple_primary_key: is_primary_key.then_some(true),
+                ..Default::default()
+            };
+
+        Arc::new(TableMapEvent {
+            table_id: 1,
+            flags: 1,
+            database_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            // INT UNSIGNED, VARCHAR(64) utf8mb4, DATETIME2(3)
+            column_types: vec![3, 15, 18],
+            column_metas: vec![0, 256, 3],
+            null_bits: vec![false, true, true],
+            table_metadata: Some(TableMetadata {
+                default_charset: Some(DefaultCharset::new(255, vec![])),
+                enum_and_set_default_charset: None,
+                columns: vec![
+                    column_metadata("id", true, Some(false)),
+                    column_metadata("name", false, None),
+                    column_metadata("created_at", false, None),
+                ],
+            }),
+        })
+    }
+
+    fn row(id: i32, name: &str) -> RowEvent {
+        RowEvent {
+            column_values: vec![
+                ColumnValue::Long(id),
+                ColumnValue::String(name.as_bytes().to_vec()),
+                ColumnValue::DateTime("2024-01-31 12:34:56.789000".into()),
+            ],
+        }
+    }
+
+    fn row_change(binlog_position: u64, before: Option<RowEvent>, after: RowEvent) -> ChangeEvent {
+        ChangeEvent::Row(RowChange {
+            operation: if before.is_some() {
+                RowOperation::Update
+            } else {
+                RowOperation::Insert
+            },
+            source: ChangeSource {
+                timestamp: 1700000000,
+                binlog_position,
+                ..Default::default()
+            },
+            row_index: 0,
+            table_map_event: create_table_map_event(),
+            before,
+            after: Some(after),
+            included_columns_before: Vec::new(),
+            included_columns_after: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_convert_transaction() {
+        let transaction = Transaction {
+            begin: Some(ChangeSource::default()),
+            changes: vec![
+                row_change(100, None, row(-1, "a")),
+                row_change(100, None, row(2, "b")),
+                row_change(200, Some(row(2, "b")), row(2, "c")),
+            ],
+            commit: ChangeSource::default(),
+            xid: Some(1),
+            rolled_back: false,
+        };
+
+        let mut converter = CanalConverter::new();
+        let messages = converter.convert_transaction(&transaction).unwrap();
+        assert_eq!(messages.len(), 2);
+
+        let insert = serde_json::to_value(&messages[0]).unwrap();
+        assert_eq!(insert["id"], 1);
+        assert_eq!(insert["type"], "INSERT");
+        assert_eq!(insert["isDdl"], false);
+        assert_eq!(insert["es"], 1_700_000_000_000_u64);
+        assert_eq!(insert["pkNames"], json!(["id"]));
+        assert_eq!(
+            insert["data"],
+            json!([
+                {"id": "4294967295", "name": "a", "created_at": "2024-01-31 12:34:56.789"},
+                {"id": "2", "name": "b", "created_at": "2024-01-31 12:34:56.789"},
+            ])
+        );
+        assert_eq!(
+            insert["mysqlType"],
+            json!({"id": "int unsigned", "name": "varchar(64)", "created_at": "datetime(3)"})
+        );
+        assert_eq!(
+            insert["sqlType"],
+            json!({"id": 4, "name": 12, "created_at": 93})
+        );
+        assert_eq!(insert["old"], Value::Null);
+
+        let update = serde_json::to_value(&messages[1]).unwrap();
+        assert_eq!(update["id"], 2);
+        assert_eq!(update["type"], "UPDATE");
+        assert_eq!(update["old"], json!([{"name": "b"}]));
+    }
+
+    #[test]
+    fn test_convert_timestamp() {
+        // TIMESTAMP2(3)
+        let table_map_event = TableMapEvent {
+            table_id: 1,
+            flags: 1,
+            database_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            column_types: vec![17],
+            column_metas: vec![3],
+            null_bits: vec![true],
+            table_metadata: None,
+        };
+        // 2024-01-31 12:34:56.789 UTC
+        let row_event = RowEvent {
+            column_values: vec![ColumnValue::Timestamp(1_706_704_496_789_000)],
+        };
+        let row = Row::new(&row_event, &table_map_event);
+        assert_eq!(
+            convert_value(&row.columns[0], TimeZone::Offset(0)).unwrap(),
+            "2024-01-31 12:34:56.789"
+        );
+        assert_eq!(
+            convert_value(&row.columns[0], TimeZone::Offset(8 * 3600)).unwrap(),
+            "2024-01-31 20:34:56.789"
+        );
+        assert_eq!(
+            convert_value(&row.columns[0], TimeZone::Offset(-13 * 3600)).unwrap(),
+            "2024-01-30 23:34:56.789"
+        );
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn test_convert_timestamp_in_named_time_zone() {
+        // TIMESTAMP
+        let table_map_event = TableMapEvent {
+            table_id: 1,
+            flags: 1,
+            database_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            column_types: vec![17],
+            column_metas: vec![0],
+            null_bits: vec![true],
+            table_metadata: None,
+        };
+        let time_zone = TimeZone::named("Europe/Berlin").unwrap();
+        // 2024-01-31 12:00:00 UTC and 2024-07-31 12:00:00 UTC, before and after DST starts
+        for (timestamp, expected) in [
+            (1_706_702_400_000_000, "2024-01-31 13:00:00"),
+            (1_722_427_200_000_000, "2024-07-31 14:00:00"),
+        ] {
+            let row_event = RowEvent {
+                column_values: vec![ColumnValue::Timestamp(timestamp)],
+            };
+            let row = Row::new(&row_event, &table_map_event);
+            assert_eq!(convert_value(&row.columns[0], time_zone).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_convert_schema_change() {
+        let mut converter = CanalConverter::new();
+        let message = converter.convert_schema_change(&SchemaChange {
+            source: ChangeSource::default(),
+            database: "db_1".into(),
+            ddl: "DROP TABLE IF EXISTS tb_1".into(),
+        });
+        assert!(message.is_ddl);
+        assert_eq!(message.event_type, "ERASE");
+        assert_eq!(message.database, "db_1");
+        assert_eq!(message.table, "tb_1");
+        assert_eq!(message.sql, "DROP TABLE IF EXISTS tb_1");
+        assert_eq!(message.data, None);
+    }
+}
@@ -0,0 +1,536 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    binlog_error::BinlogError,
+    change::change_event::{ChangeEvent, ChangeSource, RowChange, RowOperation, SchemaChange},
+    column::{column_type::ColumnType, column_value::ColumnValue, json::json_binary::JsonBinary},
+    event::row::{Row, RowColumn},
+};
+
+use super::value_util;
+
+/// How DECIMAL values are represented, the same as Debezium decimal.handling.mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecimalHandlingMode {
+    /// Base64 of the unscaled value in big-endian two's complement (org.apache.kafka.connect.data.Decimal).
+    #[default]
+    Precise,
+    Double,
+    String,
+}
+
+/// How temporal values are represented, the same as Debezium time.precision.mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimePrecisionMode {
+    /// TIME as microseconds, DATETIME as milliseconds or microseconds depending on the precision.
+    #[default]
+    AdaptiveTimeMicroseconds,
+    /// TIME and DATETIME as milliseconds.
+    Connect,
+}
+
+/// How BIGINT UNSIGNED values are represented, the same as Debezium bigint.unsigned.handling.mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BigIntUnsignedHandlingMode {
+    /// As signed 64 bit integers, values larger than i64::MAX overflow.
+    #[default]
+    Long,
+    /// As DECIMAL with scale 0, always encoded as in DecimalHandlingMode::Precise.
+    Precise,
+}
+
+/// How binary values are represented, the same as Debezium binary.handling.mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinaryHandlingMode {
+    /// Kafka Connect JSON converter writes bytes as base64.
+    #[default]
+    Bytes,
+    Base64,
+    Hex,
+}
+
+/// A Debezium change record, value is the envelope with before / after / source / op.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebeziumRecord {
+    pub topic: String,
+    pub key: Option<Value>,
+    pub value: Value,
+}
+
+/// Converts ChangeEvents into Debezium MySQL connector compatible records,
+/// as produced by the Kafka Connect JSON converter with schemas.enable=false.
+#[derive(Debug, Clone)]
+pub struct DebeziumConverter {
+    /// Logical name of the server, used as the topic prefix and source.name.
+    pub server_name: String,
+    pub decimal_handling_mode: DecimalHandlingMode,
+    pub time_precision_mode: TimePrecisionMode,
+    pub bigint_unsigned_handling_mode: BigIntUnsignedHandlingMode,
+    pub binary_handling_mode: BinaryHandlingMode,
+}
+
+const CONNECTOR_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+impl DebeziumConverter {
+    pub fn new(server_name: &str) -> Self {
+        Self {
+            server_name: server_name.to_string(),
+            decimal_handling_mode: DecimalHandlingMode::default(),
+            time_precision_mode: TimePrecisionMode::default(),
+            bigint_unsigned_handling_mode: BigIntUnsignedHandlingMode::default(),
+            binary_handling_mode: BinaryHandlingMode::default(),
+        }
+    }
+
+    pub fn with_decimal_handling_mode(mut self, mode: DecimalHandlingMode) -> Self {
+        self.decimal_handling_mode = mode;
+        self
+    }
+
+    pub fn with_time_precision_mode(mut self, mode: TimePrecisionMode) -> Self {
+        self.time_precision_mode = mode;
+        self
+    }
+
+    pub fn with_bigint_unsigned_handling_mode(mut self, mode: BigIntUnsignedHandlingMode) -> Self {
+        self.bigint_unsigned_handling_mode = mode;
+        self
+    }
+
+    pub fn with_binary_handling_mode(mut self, mode: BinaryHandlingMode) -> Self {
+        self.binary_handling_mode = mode;
+        self
+    }
+
+    /// Converts a ChangeEvent, returns None for transaction boundaries which have no
+    /// Debezium data change record.
+    pub fn convert(&self, event: &ChangeEvent) -> Result<Option<DebeziumRecord>, BinlogError> {
+        match event {
+            ChangeEvent::Row(change) => Ok(Some(self.convert_row_change(change)?)),
+            ChangeEvent::Schema(change) => Ok(Some(self.convert_schema_change(change))),
+            ChangeEvent::Begin(_) | ChangeEvent::Commit { .. } | ChangeEvent::Rollback(_) => {
+                Ok(None)
+            }
+        }
+    }
+
+    pub fn convert_row_change(&self, change: &RowChange) -> Result<DebeziumRecord, BinlogError> {
+        let before = match change.before_row() {
+            Some(row) => self.convert_row(&row)?,
+            None => Value::Null,
+        };
+        let after = match change.after_row() {
+            Some(row) => self.convert_row(&row)?,
+            None => Value::Null,
+        };
+        let op = match change.operation {
+            RowOperation::Insert => "c",
+            RowOperation::Update => "u",
+            RowOperation::Delete => "d",
+            RowOperation::Read => "r",
+        };
+
+        let mut source = self.convert_source(&change.source);
+        if change.operation == RowOperation::Read {
+            source["snapshot"] = json!("true");
+        }
+        source["db"] = json!(change.database_name());
+        source["table"] = json!(change.table_name());
+        source["row"] = json!(change.row_index);
+
+        let value = json!({
+            "before": before,
+            "after": after,
+            "source": source,
+            "op": op,
+            "ts_ms": value_util::now_millis(),
+            "transaction": null,
+        });
+
+        Ok(DebeziumRecord {
+            topic: format!(
+                "{}.{}.{}",
+                self.server_name,
+                change.database_name(),
+                change.table_name()
+            ),
+            key: self.convert_key(change)?,
+            value,
+        })
+    }
+
+    /// Converts a DDL into a schema change record, tableChanges is always empty since
+    /// the DDL is not parsed.
+    pub fn convert_schema_change(&self, change: &SchemaChange) -> DebeziumRecord {
+        let mut source = self.convert_source(&change.source);
+        source["db"] = json!(change.database);
+        let value = json!({
+            "source": source,
+            "ts_ms": value_util::now_millis(),
+            "databaseName": change.database,
+            "schemaName": null,
+            "ddl": change.ddl,
+            "tableChanges": [],
+        });
+
+        DebeziumRecord {
+            topic: self.server_name.clone(),
+            key: Some(json!({ "databaseName": change.database })),
+            value,
+        }
+    }
+
+    /// Converts the primary key columns, None if the primary key is unknown,
+    /// which requires binlog_row_metadata=FULL.
+    pub fn convert_key(&self, change: &RowChange) -> Result<Option<Value>, BinlogError> {
+        let row = match change.key_row() {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+
+        let primary_key = row.primary_key();
+        if primary_key.is_empty() {
+            return Ok(None);
+        }
+
+        let mut key = Map::new();
+        for column in primary_key {
+            key.insert(column.name_or_position(), self.convert_value(column)?);
+        }
+        Ok(Some(Value::Object(key)))
+    }
+
+    pub fn convert_row(&self, row: &Row) -> Result<Value, BinlogError> {
+        let mut map = Map::new();
+        for column in row.columns.iter() {
+            map.insert(column.name_or_position(), self.convert_value(column)?);
+        }
+        Ok(Value::Object(map))
+    }
+
+    /// Converts a column value by the Debezium MySQL connector data type mappings,
+    /// refer: https://debezium.io/documentation/reference/stable/connectors/mysql.html#mysql-data-types
+    pub fn convert_value(&self, column: &RowColumn) -> Result<Value, BinlogError> {
+        let value = match column.value {
+            ColumnValue::None => Value::Null,
+
+            ColumnValue::Tiny(_) | ColumnValue::Short(_) | ColumnValue::Long(_) => {
+                json!(value_util::integer_value(column).unwrap_or_default() as i64)
+            }
+
+            ColumnValue::LongLong(v) => {
+                if column.is_signed() == Some(false)
+                    && self.bigint_unsigned_handling_mode == BigIntUnsignedHandlingMode::Precise
+                {
+                    let (bytes, _) = value_util::decimal_unscaled_bytes(&(*v as u64).to_string())?;
+                    json!(STANDARD.encode(bytes))
+                } else {
+                    json!(v)
+                }
+            }
+
+            ColumnValue::Float(v) => json!(v),
+
+            ColumnValue::Double(v) => json!(v),
+
+            ColumnValue::Decimal(v) => self.convert_decimal(v)?,
+
+            ColumnValue::Year(v) => json!(v),
+
+            ColumnValue::Date(v) => match value_util::parse_date_days(v) {
+                Some(days) => json!(days),
+                None => Value::Null,
+            },
+
+            ColumnValue::Time(v) => match value_util::parse_time_micros(v) {
+                Some(micros) => match self.time_precision_mode {
+                    TimePrecisionMode::AdaptiveTimeMicroseconds => json!(micros),
+                    TimePrecisionMode::Connect => json!(micros / 1000),
+                },
+                None => Value::Null,
+            },
+
+            ColumnValue::DateTime(v) => match value_util::parse_datetime_micros(v) {
+                Some(micros) => {
+                    if self.time_precision_mode == TimePrecisionMode::AdaptiveTimeMicroseconds
+                        && value_util::fsp(column) > 3
+                    {
+                        json!(micros)
+                    } else {
+                        json!(micros.div_euclid(1000))
+                    }
+                }
+                None => Value::Null,
+            },
+
+            // zero TIMESTAMP 0000-00-00 00:00:00 is stored as 0
+            ColumnValue::Timestamp(0) => Value::Null,
+
+            ColumnValue::Timestamp(v) => {
+                json!(value_util::format_iso_timestamp(
+                    *v,
+                    value_util::fsp(column)
+                ))
+            }
+
+            ColumnValue::String(v) | ColumnValue::Blob(v) => {
+                if column.column_type == ColumnType::Geometry {
+                    self.convert_geometry(v)
+                } else if column.is_binary() {
+                    self.convert_binary(v)
+                } else {
+                    json!(value_util::text(v))
+                }
+            }
+
+            ColumnValue::Bit(v) => {
+                if value_util::bit_count(column) == 1 {
+                    json!(*v != 0)
+                } else {
+                    self.convert_binary(&value_util::bit_bytes(column, *v))
+                }
+            }
+
+            ColumnValue::Enum(v) => match column.enum_string() {
+                Some(s) => json!(s),
+                None => json!(v),
+            },
+
+            ColumnValue::Set(v) => match column.set_strings() {
+                Some(strings) => json!(strings.join(",")),
+                None => json!(v),
+            },
+
+            ColumnValue::Json(v) => {
+                if v.is_empty() {
+                    Value::Null
+                } else {
+                    json!(JsonBinary::parse_as_string(v)?)
+                }
+            }
+
+            ColumnValue::Vector(v) => json!(v),
+        };
+        Ok(value)
+    }
+
+    /// Source block of a change in the binlog, not a snapshot read.
+    fn convert_source(&self, source: &ChangeSource) -> Value {
+        json!({
+            "version": CONNECTOR_VERSION,
+            "connector": "mysql",
+            "name": self.server_name,
+            "ts_ms": source.timestamp as u64 * 1000,
+            "snapshot": "false",
+            "db": "",
+            "sequence": null,
+            "table": null,
+            "server_id": source.server_id,
+            "gtid": source.gtid,
+            "file": source.binlog_filename,
+            "pos": source.binlog_position,
+            "row": 0,
+            "thread": source.thread_id,
+            "query": source.query,
+        })
+    }
+
+    fn convert_decimal(&self, decimal: &str) -> Result<Value, BinlogError> {
+        let value = match self.decimal_handling_mode {
+            DecimalHandlingMode::Precise => {
+                let (bytes, _) = value_util::decimal_unscaled_bytes(decimal)?;
+                json!(STANDARD.encode(bytes))
+            }
+            DecimalHandlingMode::Double => match decimal.parse::<f64>() {
+                Ok(v) => json!(v),
+                Err(_) => {
+                    return Err(BinlogError::UnexpectedData(format!(
+                        "invalid decimal: {}",
+                        decimal
+                    )))
+                }
+            },
+            DecimalHandlingMode::String => json!(decimal),
+        };
+        Ok(value)
+    }
+
+    fn convert_binary(&self, bytes: &[u8]) -> Value {
+        match self.binary_handling_mode {
+            BinaryHandlingMode::Bytes | BinaryHandlingMode::Base64 => json!(STANDARD.encode(bytes)),
+            BinaryHandlingMode::Hex => {
+                json!(bytes
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>())
+            }
+        }
+    }
+
+    fn convert_geometry(&self, bytes: &[u8]) -> Value {
+        // MySQL stores geometry values as 4 bytes SRID followed by WKB
+        if bytes.len() < 4 {
+            return Value::Null;
+        }
+        let srid = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        json!({
+            "wkb": STANDARD.encode(&bytes[4..]),
+            "srid": srid,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::event::{
+        row_event::RowEvent,
+        table_map::table_metadata::{ColumnMetadata, TableMetadata},
+        table_map_event::TableMapEvent,
+    };
+
+    fn create_row_change(operation: RowOperation) -> RowChange {
+        let column_metadata =
+            |name: &str, is_signed: Option<bool>, is_primary_key: bool| ColumnMetadata {
+                column_name: Some(name.to_string()),
+                is_signed,
+                is_simple_primary_key: is_primary_key.then_some(true),
+                ..Default::default()
+            };
+
+        let table_map_event = TableMapEvent {
+            table_id: 1,
+            flags: 1,
+            database_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            // LONGLONG, NEWDECIMAL(10,2), DATE, DATETIME2(6), TIMESTAMP2(3), VARCHAR, BIT(1)
+            column_types: vec![8, 246, 10, 18, 17, 15, 16],
+            column_metas: vec![0, (2 << 8) | 10, 0, 6, 3, 80, 1],
+            null_bits: vec![false, true, true, true, true, true, true],
+            table_metadata: Some(TableMetadata {
+                default_charset: None,
+                enum_and_set_default_charset: None,
+                columns: vec![
+                    column_metadata("id", Some(false), true),
+                    column_metadata("price", Some(true), false),
+                    column_metadata("birthday", None, false),
+                    column_metadata("created_at", None, false),
+                    column_metadata("updated_at", None, false),
+                    column_metadata("name", None, false),
+                    column_metadata("flag", None, false),
+                ],
+            }),
+        };
+
+        let row = RowEvent {
+            column_values: vec![
+                ColumnValue::LongLong(-1),
+                ColumnValue::Decimal("-12345.67".into()),
+                ColumnValue::Date("2000-03-01".into()),
+                ColumnValue::DateTime("1970-01-02 00:00:01.500000".into()),
+                ColumnValue::Timestamp(1_700_000_000_123_000),
+                ColumnValue::String(b"abc".to_vec()),
+                ColumnValue::Bit(1),
+            ],
+        };
+
+        RowChange {
+            operation,
+            source: ChangeSource {
+                server_id: 1,
+                timestamp: 1700000000,
+                binlog_filename: "mysql-bin.000001".into(),
+                binlog_position: 1024,
+                next_binlog_position: 1100,
+                gtid: Some("5ca6b6a2-1de8-11ee-a4d4-0242ac110002:7".into()),
+                thread_id: Some(9),
+                query: None,
+            },
+            row_index: 0,
+            table_map_event: Arc::new(table_map_event),
+            before: (operation != RowOperation::Insert).then(|| row.clone()),
+            after: (operation != RowOperation::Delete).then_some(row),
+            included_columns_before: Vec::new(),
+            included_columns_after: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_convert_insert() {
+        let converter = DebeziumConverter::new("server_1");
+        let record = converter
+            .convert(&ChangeEvent::Row(create_row_change(RowOperation::Insert)))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(record.topic, "server_1.db_1.tb_1");
+        assert_eq!(record.key, Some(json!({"id": -1})));
+
+        let value = record.value;
+        assert_eq!(value["op"], "c");
+        assert_eq!(value["before"], Value::Null);
+        assert_eq!(
+            value["after"],
+            json!({
+                "id": -1,
+                "price": "7Sl5",
+                "birthday": 11017,
+                "created_at": 86_401_500_000_i64,
+                "updated_at": "2023-11-14T22:13:20.123Z",
+                "name": "abc",
+                "flag": true,
+            })
+        );
+
+        let source = &value["source"];
+        assert_eq!(source["connector"], "mysql");
+        assert_eq!(source["name"], "server_1");
+        assert_eq!(source["ts_ms"], 1_700_000_000_000_u64);
+        assert_eq!(source["db"], "db_1");
+        assert_eq!(source["table"], "tb_1");
+        assert_eq!(source["file"], "mysql-bin.000001");
+        assert_eq!(source["pos"], 1024);
+        assert_eq!(source["thread"], 9);
+        assert_eq!(source["gtid"], "5ca6b6a2-1de8-11ee-a4d4-0242ac110002:7");
+    }
+
+    #[test]
+    fn test_convert_with_modes() {
+        let converter = DebeziumConverter::new("server_1")
+            .with_decimal_handling_mode(DecimalHandlingMode::String)
+            .with_time_precision_mode(TimePrecisionMode::Connect)
+            .with_bigint_unsigned_handling_mode(BigIntUnsignedHandlingMode::Precise);
+        let record = converter
+            .convert_row_change(&create_row_change(RowOperation::Delete))
+            .unwrap();
+
+        let value = record.value;
+        assert_eq!(value["op"], "d");
+        assert_eq!(value["after"], Value::Null);
+        let before = &value["before"];
+        // 18446744073709551615 unscaled
+        assert_eq!(before["id"], "AP//////////");
+        assert_eq!(before["price"], "-12345.67");
+        assert_eq!(before["created_at"], 86_401_500_i64);
+    }
+
+    #[test]
+    fn test_convert_schema_change() {
+        let converter = DebeziumConverter::new("server_1");
+        let change = ChangeEvent::Schema(SchemaChange {
+            source: ChangeSource::default(),
+            database: "db_1".into(),
+            ddl: "CREATE TABLE tb_1 (id INT)".into(),
+        });
+        let record = converter.convert(&change).unwrap().unwrap();
+        assert_eq!(record.topic, "server_1");
+        assert_eq!(record.value["databaseName"], "db_1");
+        assert_eq!(record.value["ddl"], "CREATE TABLE tb_1 (id INT)");
+
+        let begin = ChangeEvent::Begin(ChangeSource::default());
+        assert_eq!(converter.convert(&begin).unwrap(), None);
+    }
+}
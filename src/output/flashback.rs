@@ -0,0 +1,322 @@
+use crate::{
+    binlog_error::BinlogError,
+    binlog_file::binlog_file_sequence,
+    change::{
+        change_event::{ChangeEvent, RowChange, RowOperation},
+        change_tracker::ChangeTracker,
+    },
+    command::gtid_set::GtidSet,
+    event::{event_data::EventData, event_header::EventHeader},
+};
+
+use super::sql::SqlGenerator;
+
+/// Generates the SQL statements to undo the row changes in a binlog range, like
+/// `binlog2sql --flashback`: inserts are undone by DELETEs, deletes by INSERTs and updates
+/// by UPDATEs with the before and after images swapped, in the reverse order of the changes.
+///
+/// The range is bounded by positions ([start, stop), compared by binlog file sequence first),
+/// event times in seconds ([start, stop)) and a GTID set, all optional. Flashback needs the
+/// full row images, rows events in the range logged with binlog_row_image=MINIMAL / NOBLOB
+/// are refused.
+#[derive(Debug, Clone)]
+pub struct Flashback {
+    pub start_position: Option<(String, u64)>,
+    pub stop_position: Option<(String, u64)>,
+    pub start_time: Option<u32>,
+    pub stop_time: Option<u32>,
+    /// Only changes of transactions in the set are undone.
+    pub gtid_set: Option<GtidSet>,
+    tracker: ChangeTracker,
+    changes: Vec<RowChange>,
+}
+
+impl Flashback {
+    pub fn new(binlog_filename: &str) -> Self {
+        Self {
+            start_position: None,
+            stop_position: None,
+            start_time: None,
+            stop_time: None,
+            gtid_set: None,
+            tracker: ChangeTracker::new(binlog_filename),
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn with_start_position(mut self, binlog_filename: &str, binlog_position: u64) -> Self {
+        self.start_position = Some((binlog_filename.to_string(), binlog_position));
+        self
+    }
+
+    pub fn with_stop_position(mut self, binlog_filename: &str, binlog_position: u64) -> Self {
+        self.stop_position = Some((binlog_filename.to_string(), binlog_position));
+        self
+    }
+
+    pub fn with_start_time(mut self, start_time: u32) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    pub fn with_stop_time(mut self, stop_time: u32) -> Self {
+        self.stop_time = Some(stop_time);
+        self
+    }
+
+    pub fn with_gtid_set(mut self, gtid_set: GtidSet) -> Self {
+        self.gtid_set = Some(gtid_set);
+        self
+    }
+
+    /// Handles a binlog event, the row changes in the range are kept to be undone.
+    pub fn handle(&mut self, header: &EventHeader, data: EventData) -> Result<(), BinlogError> {
+        let full_row_image = is_full_row_image(&data);
+        for change in self.tracker.handle(header, data) {
+            let ChangeEvent::Row(change) = change else {
+                continue;
+            };
+            if !self.is_in_range(&change)? {
+                continue;
+            }
+            if !full_row_image {
+                return Err(BinlogError::UnsupportedRowImage(format!(
+                    "flashback requires binlog_row_image=FULL, rows event at {}:{} has a partial row image",
+                    change.source.binlog_filename, change.source.binlog_position
+                )));
+            }
+            self.changes.push(change);
+        }
+        Ok(())
+    }
+
+    /// Number of row changes to undo.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Generates the statements undoing the handled row changes, the latest change first.
+    pub fn statements(&self) -> Result<Vec<String>, BinlogError> {
+        let generator = SqlGenerator::new();
+        let mut statements = Vec::with_capacity(self.changes.len());
+        for change in self.changes.iter().rev() {
+            statements.extend(generator.generate(&reverse(change))?);
+        }
+        Ok(statements)
+    }
+
+    fn is_in_range(&self, change: &RowChange) -> Result<bool, BinlogError> {
+        let source = &change.source;
+        let position = (
+            binlog_file_sequence(&source.binlog_filename),
+            source.binlog_position,
+        );
+        if let Some((filename, binlog_position)) = &self.start_position {
+            if position < (binlog_file_sequence(filename), *binlog_position) {
+                return Ok(false);
+            }
+        }
+        if let Some((filename, binlog_position)) = &self.stop_position {
+            if position >= (binlog_file_sequence(filename), *binlog_position) {
+                return Ok(false);
+            }
+        }
+        if self.start_time.is_some_and(|t| source.timestamp < t)
+            || self.stop_time.is_some_and(|t| source.timestamp >= t)
+        {
+            return Ok(false);
+        }
+        if let Some(gtid_set) = &self.gtid_set {
+            return match &source.gtid {
+                Some(gtid) => gtid_set.contains(gtid),
+                None => Ok(false),
+            };
+        }
+        Ok(true)
+    }
+}
+
+/// Whether all columns are included in the row images of a rows event,
+/// events other than rows events are considered full.
+fn is_full_row_image(data: &EventData) -> bool {
+    let all = |columns: &[bool]| columns.iter().all(|included| *included);
+    match data {
+        EventData::WriteRows(event) => all(&event.included_columns),
+        EventData::UpdateRows(event) => {
+            all(&event.included_columns_before) && all(&event.included_columns_after)
+        }
+        EventData::DeleteRows(event) => all(&event.included_columns),
+        EventData::TransactionPayload(event) => event
+            .uncompressed_events
+            .iter()
+            .all(|(_, data)| is_full_row_image(data)),
+        _ => true,
+    }
+}
+
+fn reverse(change: &RowChange) -> RowChange {
+    let operation = match change.operation {
+        RowOperation::Insert => RowOperation::Delete,
+        RowOperation::Delete => RowOperation::Insert,
+        operation => operation,
+    };
+    RowChange {
+        operation,
+        before: change.after.clone(),
+        after: change.before.clone(),
+        included_columns_before: change.included_columns_after.clone(),
+        included_columns_after: change.included_columns_before.clone(),
+        ..change.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        column::column_value::ColumnValue,
+        event::{
+            gtid_event::GtidEvent,
+            rotate_event::RotateEvent,
+            row_event::RowEvent,
+            table_map::table_metadata::{ColumnMetadata, TableMetadata},
+            table_map_event::TableMapEvent,
+            update_rows_event::UpdateRowsEvent,
+            write_rows_event::WriteRowsEvent,
+            xid_event::XidEvent,
+        },
+    };
+
+    const UUID: &str = "24bc7850-2c16-11e6-a073-0242ac110002";
+
+    fn header(timestamp: u32, next_event_position: u32) -> EventHeader {
+        EventHeader {
+            timestamp,
+            event_type: 0,
+            server_id: 1,
+            event_length: 100,
+            next_event_position,
+            event_flags: 0,
+        }
+    }
+
+    fn table_map_event() -> EventData {
+        let column = |name: &str, is_primary_key: bool| ColumnMetadata {
+            column_name: Some(name.to_string()),
+            is_simple_primary_key: is_primary_key.then_some(true),
+            ..Default::default()
+        };
+        EventData::TableMap(TableMapEvent {
+            table_id: 1,
+            flags: 1,
+            database_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            column_types: vec![3, 3],
+            column_metas: vec![0, 0],
+            null_bits: vec![false, true],
+            table_metadata: Some(TableMetadata {
+                default_charset: None,
+                enum_and_set_default_charset: None,
+                columns: vec![column("id", true), column("v", false)],
+            }),
+        })
+    }
+
+    fn row(id: i32, v: i32) -> RowEvent {
+        RowEvent {
+            column_values: vec![ColumnValue::Long(id), ColumnValue::Long(v)],
+        }
+    }
+
+    /// Handles a transaction of 2 row events at the given time, GTID and end position.
+    fn handle_transaction(
+        flashback: &mut Flashback,
+        timestamp: u32,
+        transaction_id: u64,
+        position: u32,
+        update_columns: Vec<bool>,
+    ) -> Result<(), BinlogError> {
+        let gtid = EventData::Gtid(GtidEvent {
+            flags: 0,
+            gtid: format!("{}:{}", UUID, transaction_id),
+            ..Default::default()
+        });
+        flashback.handle(&header(timestamp, position), gtid)?;
+        flashback.handle(&header(timestamp, position + 100), table_map_event())?;
+        let insert = EventData::WriteRows(WriteRowsEvent {
+            table_id: 1,
+            flags: 1,
+            extra_data: Vec::new(),
+            included_columns: vec![true, true],
+            rows: vec![row(transaction_id as i32, 1)],
+        });
+        flashback.handle(&header(timestamp, position + 200), insert)?;
+        let update = EventData::UpdateRows(UpdateRowsEvent {
+            table_id: 1,
+            flags: 1,
+            extra_data: Vec::new(),
+            included_columns_before: vec![true, true],
+            included_columns_after: update_columns,
+            rows: vec![(row(transaction_id as i32, 1), row(transaction_id as i32, 2))],
+        });
+        flashback.handle(&header(timestamp, position + 300), update)?;
+        let xid = EventData::Xid(XidEvent {
+            xid: transaction_id,
+        });
+        flashback.handle(&header(timestamp, position + 400), xid)
+    }
+
+    #[test]
+    fn test_flashback() {
+        let mut flashback = Flashback::new("mysql-bin.000001")
+            .with_start_position("mysql-bin.000001", 1000)
+            .with_stop_time(1700000300);
+        let rotate = EventData::Rotate(RotateEvent {
+            binlog_filename: "mysql-bin.000001".into(),
+            binlog_position: 4,
+        });
+        flashback.handle(&header(0, 0), rotate).unwrap();
+
+        // before the start position
+        handle_transaction(&mut flashback, 1700000000, 1, 500, vec![true; 2]).unwrap();
+        handle_transaction(&mut flashback, 1700000100, 2, 1000, vec![true; 2]).unwrap();
+        handle_transaction(&mut flashback, 1700000200, 3, 1500, vec![true; 2]).unwrap();
+        // after the stop time, not checked for the row image
+        handle_transaction(&mut flashback, 1700000300, 4, 2000, vec![false, true]).unwrap();
+        assert_eq!(flashback.len(), 4);
+
+        assert_eq!(
+            flashback.statements().unwrap(),
+            vec![
+                "UPDATE `db_1`.`tb_1` SET `id`=3, `v`=1 WHERE `id`=3 LIMIT 1;",
+                "DELETE FROM `db_1`.`tb_1` WHERE `id`=3 LIMIT 1;",
+                "UPDATE `db_1`.`tb_1` SET `id`=2, `v`=1 WHERE `id`=2 LIMIT 1;",
+                "DELETE FROM `db_1`.`tb_1` WHERE `id`=2 LIMIT 1;",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flashback_gtid_set_and_row_image() {
+        let gtid_set = GtidSet::new(&format!("{}:2", UUID)).unwrap();
+        let mut flashback = Flashback::new("mysql-bin.000001").with_gtid_set(gtid_set);
+        handle_transaction(&mut flashback, 1700000000, 1, 500, vec![false, true]).unwrap();
+        assert!(flashback.is_empty());
+
+        let res = handle_transaction(&mut flashback, 1700000000, 2, 1000, vec![false, true]);
+        assert!(matches!(res, Err(BinlogError::UnsupportedRowImage(_))));
+    }
+
+    #[test]
+    fn test_flashback_position_after_sequence_rollover() {
+        let mut flashback = Flashback::new("mysql-bin.1000000")
+            .with_start_position("mysql-bin.999999", 4)
+            .with_stop_position("mysql-bin.1000001", 4);
+        handle_transaction(&mut flashback, 1700000000, 1, 500, vec![true; 2]).unwrap();
+        assert_eq!(flashback.len(), 2);
+    }
+}
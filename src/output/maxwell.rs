@@ -0,0 +1,548 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use serde_json::{json, value::RawValue, Value};
+
+use crate::{
+    binlog_error::BinlogError,
+    change::{
+        change_event::{ChangeEvent, ChangeSource, RowChange, RowOperation, SchemaChange},
+        transaction::Transaction,
+    },
+    column::{column_value::ColumnValue, json::json_binary::JsonBinary},
+    event::row::{Row, RowColumn},
+};
+
+use super::value_util;
+
+/// A Maxwell's daemon record,
+/// refer: https://maxwells-daemon.io/dataformat/
+#[derive(Debug, Clone, Serialize)]
+pub struct MaxwellRecord {
+    pub database: String,
+    pub table: Option<String>,
+    #[serde(rename = "type")]
+    pub record_type: String,
+    /// Binlog event time in seconds.
+    pub ts: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xid: Option<u64>,
+    /// Index of the row in the transaction, only set if it is not the last row.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xoffset: Option<usize>,
+    /// Only set on the last row of a transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gtid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_key: Option<Vec<Box<RawValue>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Statement of DDL records.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sql: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<MaxwellColumns>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<MaxwellColumns>,
+}
+
+/// Column name -> value in column order, values are raw JSON so that
+/// DECIMAL values keep their precision.
+#[derive(Debug, Clone, Default)]
+pub struct MaxwellColumns(pub Vec<(String, Box<RawValue>)>);
+
+impl MaxwellColumns {
+    pub fn get(&self, name: &str) -> Option<&RawValue> {
+        self.0
+            .iter()
+            .find(|(column_name, _)| column_name == name)
+            .map(|(_, value)| value.as_ref())
+    }
+}
+
+impl Serialize for MaxwellColumns {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (name, value) in self.0.iter() {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Converts transactions into Maxwell records, the optional fields are controlled
+/// like the Maxwell output_* options.
+#[derive(Debug, Clone)]
+pub struct MaxwellConverter {
+    /// Output xid, and commit: true on the last row of each transaction.
+    pub output_commit_info: bool,
+    pub output_binlog_position: bool,
+    pub output_gtid_position: bool,
+    pub output_server_id: bool,
+    pub output_thread_id: bool,
+    pub output_primary_keys: bool,
+    /// Output the original statement, requires binlog_rows_query_log_events=ON.
+    pub output_row_query: bool,
+    /// Output records for DDLs.
+    pub output_ddl: bool,
+}
+
+impl Default for MaxwellConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MaxwellConverter {
+    pub fn new() -> Self {
+        Self {
+            output_commit_info: true,
+            output_binlog_position: false,
+            output_gtid_position: false,
+            output_server_id: false,
+            output_thread_id: false,
+            output_primary_keys: false,
+            output_row_query: false,
+            output_ddl: false,
+        }
+    }
+
+    pub fn with_output_commit_info(mut self, enabled: bool) -> Self {
+        self.output_commit_info = enabled;
+        self
+    }
+
+    pub fn with_output_binlog_position(mut self, enabled: bool) -> Self {
+        self.output_binlog_position = enabled;
+        self
+    }
+
+    pub fn with_output_gtid_position(mut self, enabled: bool) -> Self {
+        self.output_gtid_position = enabled;
+        self
+    }
+
+    pub fn with_output_server_id(mut self, enabled: bool) -> Self {
+        self.output_server_id = enabled;
+        self
+    }
+
+    pub fn with_output_thread_id(mut self, enabled: bool) -> Self {
+        self.output_thread_id = enabled;
+        self
+    }
+
+    pub fn with_output_primary_keys(mut self, enabled: bool) -> Self {
+        self.output_primary_keys = enabled;
+        self
+    }
+
+    pub fn with_output_row_query(mut self, enabled: bool) -> Self {
+        self.output_row_query = enabled;
+        self
+    }
+
+    pub fn with_output_ddl(mut self, enabled: bool) -> Self {
+        self.output_ddl = enabled;
+        self
+    }
+
+    pub fn convert_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Vec<MaxwellRecord>, BinlogError> {
+        let mut records = Vec::new();
+        for event in transaction.changes.iter() {
+            match event {
+                ChangeEvent::Row(change) => records.push(self.convert_row_change(change)?),
+                ChangeEvent::Schema(change) if self.output_ddl => {
+                    records.extend(self.convert_schema_change(change))
+                }
+                _ => {}
+            }
+        }
+
+        if self.output_commit_info {
+            let last = records.iter().rposition(|record| record.data.is_some());
+            for (i, record) in records.iter_mut().enumerate() {
+                if record.data.is_none() {
+                    // DDL records have no commit info
+                    continue;
+                }
+                record.xid = transaction.xid;
+                if Some(i) == last {
+                    record.commit = Some(true);
+                } else {
+                    record.xoffset = Some(i);
+                }
+            }
+        }
+        Ok(records)
+    }
+
+    pub fn convert_row_change(&self, change: &RowChange) -> Result<MaxwellRecord, BinlogError> {
+        let record_type = match change.operation {
+            RowOperation::Insert => "insert",
+            RowOperation::Update => "update",
+            RowOperation::Delete => "delete",
+            // Maxwell bootstrap rows
+            RowOperation::Read => "bootstrap-insert",
+        };
+
+        let (before, after) = (change.before_row(), change.after_row());
+        let (data, old) = match (&before, &after) {
+            (Some(before), Some(after)) => (
+                convert_row(after)?,
+                Some(convert_changed_columns(before, after)?),
+            ),
+            (Some(row), None) | (None, Some(row)) => (convert_row(row)?, None),
+            (None, None) => (MaxwellColumns::default(), None),
+        };
+
+        let mut record = self.new_record(
+            &change.source,
+            change.database_name(),
+            Some(change.table_name()),
+            record_type,
+        );
+        record.data = Some(data);
+        record.old = old;
+        if self.output_primary_keys {
+            if let Some(row) = change.key_row() {
+                let primary_key = row
+                    .primary_key()
+                    .into_iter()
+                    .map(convert_value)
+                    .collect::<Result<Vec<Box<RawValue>>, BinlogError>>()?;
+                record.primary_key = Some(primary_key);
+            }
+        }
+        if self.output_row_query {
+            record.query = change.source.query.clone();
+        }
+        Ok(record)
+    }
+
+    /// Converts a DDL into a record, returns None for DDLs on objects other than
+    /// databases and tables, which Maxwell does not output either.
+    pub fn convert_schema_change(&self, change: &SchemaChange) -> Option<MaxwellRecord> {
+        let (statement, object) = change.statement_type();
+        let object = match object.as_str() {
+            "DATABASE" | "SCHEMA" => "database",
+            "TABLE" => "table",
+            _ => return None,
+        };
+        let action = match statement.as_str() {
+            "CREATE" => "create",
+            "DROP" => "drop",
+            // Maxwell reports renames as table-alter
+            "ALTER" | "RENAME" => "alter",
+            _ => return None,
+        };
+
+        let (database, table) = match change.table() {
+            Some((database, table)) if object == "table" => (database, Some(table)),
+            _ => (change.database.clone(), None),
+        };
+        let mut record = self.new_record(
+            &change.source,
+            &database,
+            table.as_deref(),
+            &format!("{}-{}", object, action),
+        );
+        record.sql = Some(change.ddl.clone());
+        Some(record)
+    }
+
+    fn new_record(
+        &self,
+        source: &ChangeSource,
+        database: &str,
+        table: Option<&str>,
+        record_type: &str,
+    ) -> MaxwellRecord {
+        MaxwellRecord {
+            database: database.to_string(),
+            table: table.map(|t| t.to_string()),
+            record_type: record_type.to_string(),
+            ts: source.timestamp as u64,
+            xid: None,
+            xoffset: None,
+            commit: None,
+            position: self
+                .output_binlog_position
+                .then(|| format!("{}:{}", source.binlog_filename, source.binlog_position)),
+            gtid: if self.output_gtid_position {
+                source.gtid.clone()
+            } else {
+                None
+            },
+            server_id: self.output_server_id.then_some(source.server_id),
+            thread_id: if self.output_thread_id {
+                source.thread_id
+            } else {
+                None
+            },
+            primary_key: None,
+            query: None,
+            sql: None,
+            data: None,
+            old: None,
+        }
+    }
+}
+
+fn convert_row(row: &Row) -> Result<MaxwellColumns, BinlogError> {
+    let mut columns = Vec::with_capacity(row.columns.len());
+    for column in row.columns.iter() {
+        columns.push((column.name_or_position(), convert_value(column)?));
+    }
+    Ok(MaxwellColumns(columns))
+}
+
+/// Converts the before values of the columns changed by an update.
+fn convert_changed_columns(before: &Row, after: &Row) -> Result<MaxwellColumns, BinlogError> {
+    let mut columns = Vec::new();
+    for (before, after) in before.columns.iter().zip(after.columns.iter()) {
+        if before.value != after.value {
+            columns.push((before.name_or_position(), convert_value(before)?));
+        }
+    }
+    Ok(MaxwellColumns(columns))
+}
+
+/// Converts a column value like Maxwell does, DECIMAL values are output as numbers
+/// without losing precision, GEOMETRY values are output as base64 of the WKB instead of WKT.
+pub fn convert_value(column: &RowColumn) -> Result<Box<RawValue>, BinlogError> {
+    let fsp = value_util::fsp(column);
+    let value = match column.value {
+        ColumnValue::None => Value::Null,
+
+        ColumnValue::Tiny(_)
+        | ColumnValue::Short(_)
+        | ColumnValue::Long(_)
+        | ColumnValue::LongLong(_)
+        | ColumnValue::Year(_) => {
+            let value = value_util::integer_value(column).unwrap_or_default();
+            return to_raw_value(value.to_string());
+        }
+
+        ColumnValue::Float(v) => json!(v),
+
+        ColumnValue::Double(v) => json!(v),
+
+        ColumnValue::Decimal(v) => return to_raw_value(v.clone()),
+
+        ColumnValue::Date(v) => json!(v),
+
+        ColumnValue::Time(v) | ColumnValue::DateTime(v) => {
+            json!(value_util::truncate_fraction(v, fsp))
+        }
+
+        // zero TIMESTAMP 0000-00-00 00:00:00 is stored as 0
+        ColumnValue::Timestamp(0) => Value::Null,
+
+        ColumnValue::Timestamp(v) => json!(value_util::format_timestamp(*v, fsp)),
+
+        ColumnValue::String(v) | ColumnValue::Blob(v) => {
+            if column.is_binary() {
+                json!(STANDARD.encode(v))
+            } else {
+                json!(value_util::text(v))
+            }
+        }
+
+        ColumnValue::Bit(v) => {
+            if value_util::bit_count(column) == 1 {
+                json!(*v != 0)
+            } else {
+                json!(v)
+            }
+        }
+
+        ColumnValue::Enum(v) => match column.enum_string() {
+            Some(s) => json!(s),
+            None => json!(v),
+        },
+
+        ColumnValue::Set(v) => match column.set_strings() {
+            Some(strings) => json!(strings),
+            None => json!(v),
+        },
+
+        // JSON documents are nested in the record instead of quoted
+        ColumnValue::Json(v) => {
+            if v.is_empty() {
+                Value::Null
+            } else {
+                return to_raw_value(JsonBinary::parse_as_string(v)?);
+            }
+        }
+
+        ColumnValue::Vector(v) => json!(v),
+    };
+    to_raw_value(value.to_string())
+}
+
+fn to_raw_value(json: String) -> Result<Box<RawValue>, BinlogError> {
+    RawValue::from_string(json).map_err(|e| BinlogError::ParseJsonError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        change::transaction::TransactionBuffer,
+        event::{
+            row_event::RowEvent,
+            table_map::table_metadata::{ColumnMetadata, TableMetadata},
+            table_map_event::TableMapEvent,
+        },
+    };
+
+    fn create_table_map_event() -> Arc<TableMapEvent> {
+        let column_metadata = |name: &str, is_primary_key: bool| ColumnMetadata {
+            column_name: Some(name.to_string()),
+            is_simple_primary_key: is_primary_key.then_some(true),
+            set_string_values: (name == "tags").then(|| vec!["a".into(), "b".into()]),
+            ..Default::default()
+        };
+
+        Arc::new(TableMapEvent {
+            table_id: 1,
+            flags: 1,
+            database_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            // LONG, NEWDECIMAL(30,10), SET
+            column_types: vec![3, 246, 254],
+            column_metas: vec![0, (10 << 8) | 30, (248 << 8) | 1],
+            null_bits: vec![false, true, true],
+            table_metadata: Some(TableMetadata {
+                default_charset: None,
+                enum_and_set_default_charset: None,
+                columns: vec![
+                    column_metadata("id", true),
+                    column_metadata("amount", false),
+                    column_metadata("tags", false),
+                ],
+            }),
+        })
+    }
+
+    fn row(id: i32, amount: &str) -> RowEvent {
+        RowEvent {
+            column_values: vec![
+                ColumnValue::Long(id),
+                ColumnValue::Decimal(amount.into()),
+                ColumnValue::Set(0b11),
+            ],
+        }
+    }
+
+    fn row_change(
+        operation: RowOperation,
+        before: Option<RowEvent>,
+        after: Option<RowEvent>,
+    ) -> ChangeEvent {
+        ChangeEvent::Row(RowChange {
+            operation,
+            source: ChangeSource {
+                server_id: 1,
+                timestamp: 1700000000,
+                binlog_filename: "mysql-bin.000001".into(),
+                binlog_position: 400,
+                next_binlog_position: 500,
+                gtid: None,
+                thread_id: Some(3),
+                query: None,
+            },
+            row_index: 0,
+            table_map_event: create_table_map_event(),
+            before,
+            after,
+            included_columns_before: Vec::new(),
+            included_columns_after: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_convert_transaction() {
+        let mut buffer = TransactionBuffer::new();
+        assert!(buffer
+            .push(ChangeEvent::Begin(ChangeSource::default()))
+            .is_none());
+        let changes = [
+            row_change(
+                RowOperation::Insert,
+                None,
+                Some(row(1, "12345678901234567890.0123456789")),
+            ),
+            row_change(
+                RowOperation::Update,
+                Some(row(1, "1.5")),
+                Some(row(1, "2.5")),
+            ),
+        ];
+        for change in changes {
+            assert!(buffer.push(change).is_none());
+        }
+        let transaction = buffer
+            .push(ChangeEvent::Commit {
+                source: ChangeSource::default(),
+                xid: Some(77),
+            })
+            .unwrap();
+        assert!(buffer.is_empty());
+
+        let converter = MaxwellConverter::new()
+            .with_output_binlog_position(true)
+            .with_output_thread_id(true)
+            .with_output_primary_keys(true);
+        let records = converter.convert_transaction(&transaction).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            serde_json::to_string(&records[0]).unwrap(),
+            r#"{"database":"db_1","table":"tb_1","type":"insert","ts":1700000000,"xid":77,"xoffset":0,"position":"mysql-bin.000001:400","thread_id":3,"primary_key":[1],"data":{"id":1,"amount":12345678901234567890.0123456789,"tags":["a","b"]}}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&records[1]).unwrap(),
+            r#"{"database":"db_1","table":"tb_1","type":"update","ts":1700000000,"xid":77,"commit":true,"position":"mysql-bin.000001:400","thread_id":3,"primary_key":[1],"data":{"id":1,"amount":2.5,"tags":["a","b"]},"old":{"amount":1.5}}"#
+        );
+    }
+
+    #[test]
+    fn test_convert_schema_change() {
+        let converter = MaxwellConverter::new().with_output_ddl(true);
+        let change = |ddl: &str| SchemaChange {
+            source: ChangeSource::default(),
+            database: "db_1".into(),
+            ddl: ddl.into(),
+        };
+
+        let record = converter
+            .convert_schema_change(&change("CREATE TABLE IF NOT EXISTS `db_2`.`tb_2` (id INT)"))
+            .unwrap();
+        assert_eq!(record.record_type, "table-create");
+        assert_eq!(record.database, "db_2");
+        assert_eq!(record.table.as_deref(), Some("tb_2"));
+
+        let record = converter
+            .convert_schema_change(&change("drop database db_3"))
+            .unwrap();
+        assert_eq!(record.record_type, "database-drop");
+        assert_eq!(record.table, None);
+
+        assert!(converter
+            .convert_schema_change(&change("CREATE VIEW v AS SELECT 1"))
+            .is_none());
+    }
+}
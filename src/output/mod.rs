@@ -0,0 +1,14 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "avro")]
+pub mod avro;
+pub mod canal;
+pub mod debezium;
+pub mod flashback;
+pub mod maxwell;
+pub mod mysqlbinlog;
+#[cfg(feature = "parquet")]
+pub mod parquet_archive;
+pub mod sql;
+pub mod time_zone;
+mod value_util;
@@ -0,0 +1,1100 @@
+use std::{
+    collections::HashMap,
+    io::{Cursor, ErrorKind, Read, Seek, Write},
+};
+
+use crate::{
+    binlog_error::BinlogError,
+    binlog_parser::BinlogParser,
+    column::{column_type::ColumnType, column_value::ColumnValue, json::json_binary::JsonBinary},
+    constants,
+    event::{
+        event_data::EventData,
+        event_header::EventHeader,
+        event_type::EventType,
+        query_status_vars::QueryStatusVars,
+        row::{Row, RowColumn},
+        row_event::RowEvent,
+        table_map_event::TableMapEvent,
+    },
+};
+
+use super::{sql::quote_identifier, value_util};
+
+const DELIMITER: &str = "/*!*/;";
+
+/// Flag of the last rows event of a statement.
+const STMT_END_F: u16 = 1;
+
+/// Flag of a GTID event whose transaction may contain statement based events.
+const FLAG_MAY_HAVE_SBR: u8 = 1;
+
+/// Event flag of queries using temporary tables, which depend on the thread id.
+const LOG_EVENT_THREAD_SPECIFIC_F: u16 = 0x4;
+/// Event flag of queries which don't depend on the default database.
+const LOG_EVENT_SUPPRESS_USE_F: u16 = 0x8;
+
+/// Bits of the session options in the flags2 status variable.
+const OPTION_AUTO_IS_NULL: u32 = 1 << 14;
+const OPTION_NOT_AUTOCOMMIT: u32 = 1 << 19;
+const OPTION_NO_FOREIGN_KEY_CHECKS: u32 = 1 << 26;
+const OPTION_RELAXED_UNIQUE_CHECKS: u32 = 1 << 27;
+
+/// Renders binlog events as text in the format of
+/// `mysqlbinlog --verbose --base64-output=DECODE-ROWS`, so the output can be diffed
+/// against the reference tool:
+///
+/// ```text
+/// # at 4
+/// #240610  6:13:20 server id 1  end_log_pos 126 CRC32 0x4c6a7a5b \tStart: binlog v 4, server v 8.0.36 created 240610  6:13:20
+/// ...
+/// ### INSERT INTO `test_db`.`t`
+/// ### SET
+/// ###   @1=1
+/// ```
+///
+/// mysqlbinlog prints times in the local time zone, set `time_zone_offset` to match it.
+pub struct MysqlbinlogRenderer {
+    /// Offset of the time zone in seconds east of UTC, used for event times.
+    pub time_zone_offset: i32,
+    parser: BinlogParser,
+    database: Option<String>,
+    thread_id: Option<u32>,
+    session: SessionVariables,
+    has_gtid: bool,
+}
+
+/// Session variables of the query events rendered so far, mysqlbinlog only prints the
+/// ones which changed. None if not printed yet.
+#[derive(Default)]
+struct SessionVariables {
+    flags2: Option<u32>,
+    sql_mode: Option<u64>,
+    auto_increment: (u16, u16),
+    charset: Option<[u16; 3]>,
+    time_zone: String,
+    lc_time_names: Option<u16>,
+    collation_database: Option<u16>,
+    default_collation_for_utf8mb4: Option<u16>,
+    sql_require_primary_key: Option<u8>,
+    default_table_encryption: Option<u8>,
+}
+
+impl MysqlbinlogRenderer {
+    pub fn new() -> Self {
+        Self {
+            time_zone_offset: 0,
+            parser: BinlogParser {
+                checksum_length: 0,
+                table_map_event_by_table_id: HashMap::new(),
+            },
+            database: None,
+            thread_id: None,
+            session: SessionVariables::default(),
+            has_gtid: false,
+        }
+    }
+
+    pub fn with_time_zone_offset(mut self, time_zone_offset: i32) -> Self {
+        self.time_zone_offset = time_zone_offset;
+        self
+    }
+
+    /// Renders a complete binlog file, including the magic number, with the lines
+    /// mysqlbinlog prints before the first and after the last event.
+    pub fn render<S: Read + Seek, W: Write>(
+        &mut self,
+        stream: &mut S,
+        out: &mut W,
+    ) -> Result<(), BinlogError> {
+        self.parser.check_magic(stream)?;
+        self.render_prologue(out)?;
+        loop {
+            let mut buf = vec![0u8; constants::EVENT_HEADER_LENGTH];
+            match stream.read_exact(&mut buf) {
+                Ok(()) => {}
+                Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err.into()),
+            }
+            let event_length = u32::from_le_bytes(buf[9..13].try_into().unwrap()) as usize;
+            if event_length < constants::EVENT_HEADER_LENGTH {
+                return Err(BinlogError::UnexpectedData(format!(
+                    "invalid event length: {}",
+                    event_length
+                )));
+            }
+            buf.resize(event_length, 0);
+            stream.read_exact(&mut buf[constants::EVENT_HEADER_LENGTH..])?;
+            self.render_event(&buf, out)?;
+        }
+        self.render_epilogue(out)
+    }
+
+    pub fn render_prologue<W: Write>(&mut self, out: &mut W) -> Result<(), BinlogError> {
+        writeln!(
+            out,
+            "# The proper term is pseudo_replica_mode, but we use this compatibility alias"
+        )?;
+        writeln!(
+            out,
+            "# to make the statement usable on server versions 8.0.24 and older."
+        )?;
+        writeln!(out, "/*!50530 SET @@SESSION.PSEUDO_SLAVE_MODE=1*/;")?;
+        writeln!(
+            out,
+            "/*!50003 SET @OLD_COMPLETION_TYPE=@@COMPLETION_TYPE,COMPLETION_TYPE=0*/;"
+        )?;
+        writeln!(out, "DELIMITER {}", DELIMITER)?;
+        Ok(())
+    }
+
+    pub fn render_epilogue<W: Write>(&mut self, out: &mut W) -> Result<(), BinlogError> {
+        if self.has_gtid {
+            writeln!(
+                out,
+                "SET @@SESSION.GTID_NEXT= 'AUTOMATIC' /* added by mysqlbinlog */ {}",
+                DELIMITER
+            )?;
+        }
+        writeln!(out, "DELIMITER ;")?;
+        writeln!(out, "# End of log file")?;
+        writeln!(out, "/*!50003 SET COMPLETION_TYPE=@OLD_COMPLETION_TYPE*/;")?;
+        writeln!(out, "/*!50530 SET @@SESSION.PSEUDO_SLAVE_MODE=0*/;")?;
+        Ok(())
+    }
+
+    /// Renders a complete event (header, data and checksum).
+    pub fn render_event<W: Write>(&mut self, buf: &[u8], out: &mut W) -> Result<(), BinlogError> {
+        let (header, data) = self.parser.next(&mut Cursor::new(buf))?;
+        let checksum_length = self.parser.checksum_length as usize;
+        let checksum = (checksum_length == 4 && buf.len() >= constants::EVENT_HEADER_LENGTH + 4)
+            .then(|| u32::from_le_bytes(buf[buf.len() - 4..].try_into().unwrap()));
+
+        let position = header
+            .next_event_position
+            .saturating_sub(header.event_length);
+        writeln!(out, "# at {}", position)?;
+        self.render_header(&header, checksum, out)?;
+        self.render_data(&header, &data, out)
+    }
+
+    fn render_header<W: Write>(
+        &self,
+        header: &EventHeader,
+        checksum: Option<u32>,
+        out: &mut W,
+    ) -> Result<(), BinlogError> {
+        write!(
+            out,
+            "#{} server id {}  end_log_pos {} ",
+            self.format_time(header.timestamp),
+            header.server_id,
+            header.next_event_position
+        )?;
+        if let Some(checksum) = checksum {
+            write!(out, "CRC32 0x{:08x} ", checksum)?;
+        }
+        Ok(())
+    }
+
+    fn render_data<W: Write>(
+        &mut self,
+        header: &EventHeader,
+        data: &EventData,
+        out: &mut W,
+    ) -> Result<(), BinlogError> {
+        let event_type = EventType::from_code(header.event_type);
+        match data {
+            EventData::FormatDescription(event) => {
+                write!(
+                    out,
+                    "\tStart: binlog v {}, server v {} created ",
+                    event.binlog_version,
+                    event.server_version.trim_end_matches('\0')
+                )?;
+                if event.create_timestamp != 0 {
+                    writeln!(
+                        out,
+                        "{} at startup",
+                        self.format_time(event.create_timestamp)
+                    )?;
+                    writeln!(out, "ROLLBACK{}", DELIMITER)?;
+                } else {
+                    writeln!(out, "{}", self.format_time(header.timestamp))?;
+                }
+            }
+
+            EventData::PreviousGtids(event) => {
+                writeln!(out, "\tPrevious-GTIDs")?;
+                if event.gtid_set.is_empty() {
+                    writeln!(out, "# [empty]")?;
+                } else {
+                    writeln!(out, "# {}", event.gtid_set.replace(',', ",\n"))?;
+                }
+            }
+
+            EventData::Gtid(event) => {
+                self.has_gtid = true;
+                let rbr_only = event.flags & FLAG_MAY_HAVE_SBR == 0;
+                write!(
+                    out,
+                    "\tGTID\tlast_committed={}\tsequence_number={}\trbr_only={}",
+                    event.last_committed.unwrap_or_default(),
+                    event.sequence_number.unwrap_or_default(),
+                    if rbr_only { "yes" } else { "no" }
+                )?;
+                let commit_timestamps = event.immediate_commit_timestamp.map(|immediate| {
+                    (
+                        event.original_commit_timestamp.unwrap_or(immediate),
+                        immediate,
+                    )
+                });
+                if let Some((original, immediate)) = commit_timestamps {
+                    write!(
+                        out,
+                        "\toriginal_committed_timestamp={}\timmediate_commit_timestamp={}\ttransaction_length={}",
+                        original,
+                        immediate,
+                        event.transaction_length.unwrap_or_default()
+                    )?;
+                }
+                writeln!(out)?;
+                if rbr_only {
+                    writeln!(
+                        out,
+                        "/*!50718 SET TRANSACTION ISOLATION LEVEL READ COMMITTED*/{}",
+                        DELIMITER
+                    )?;
+                }
+                if let Some((original, immediate)) = commit_timestamps {
+                    writeln!(
+                        out,
+                        "# original_commit_timestamp={} ({})",
+                        original,
+                        self.format_commit_timestamp(original)
+                    )?;
+                    writeln!(
+                        out,
+                        "# immediate_commit_timestamp={} ({})",
+                        immediate,
+                        self.format_commit_timestamp(immediate)
+                    )?;
+                    writeln!(
+                        out,
+                        "/*!80001 SET @@session.original_commit_timestamp={}*/{}",
+                        original, DELIMITER
+                    )?;
+                }
+                if let Some(immediate) = event.immediate_server_version {
+                    writeln!(
+                        out,
+                        "/*!80014 SET @@session.original_server_version={}*/{}",
+                        event.original_server_version.unwrap_or(immediate),
+                        DELIMITER
+                    )?;
+                    writeln!(
+                        out,
+                        "/*!80014 SET @@session.immediate_server_version={}*/{}",
+                        immediate, DELIMITER
+                    )?;
+                }
+                writeln!(
+                    out,
+                    "SET @@SESSION.GTID_NEXT= '{}'{}",
+                    event.gtid, DELIMITER
+                )?;
+            }
+
+            EventData::Query(event) => {
+                writeln!(
+                    out,
+                    "\tQuery\tthread_id={}\texec_time={}\terror_code={}",
+                    event.thread_id, event.exec_time, event.error_code
+                )?;
+                let vars = event.parse_status_vars()?;
+                if header.event_flags & LOG_EVENT_SUPPRESS_USE_F != 0 {
+                    if !is_trans_keyword(&event.query) {
+                        self.database = None;
+                    }
+                } else if self.database.as_ref() != Some(&event.schema) {
+                    if !event.schema.is_empty() {
+                        writeln!(out, "use {}{}", quote_identifier(&event.schema), DELIMITER)?;
+                    }
+                    self.database = Some(event.schema.clone());
+                }
+
+                write!(out, "SET TIMESTAMP={}", header.timestamp)?;
+                if let Some(microseconds) = vars.microseconds.filter(|v| *v != 0) {
+                    write!(out, ".{:06}", microseconds)?;
+                }
+                writeln!(out, "{}", DELIMITER)?;
+                let thread_specific = header.event_flags & LOG_EVENT_THREAD_SPECIFIC_F != 0;
+                if self.thread_id.is_none()
+                    || (thread_specific && self.thread_id != Some(event.thread_id))
+                {
+                    writeln!(
+                        out,
+                        "SET @@session.pseudo_thread_id={}{}",
+                        event.thread_id, DELIMITER
+                    )?;
+                    self.thread_id = Some(event.thread_id);
+                }
+                self.render_session_variables(&vars, out)?;
+                writeln!(out, "{}", event.query)?;
+                writeln!(out, "{}", DELIMITER)?;
+            }
+
+            EventData::RowsQuery(event) => {
+                writeln!(out, "\tRows_query")?;
+                for line in event.query.lines() {
+                    writeln!(out, "# {}", line)?;
+                }
+            }
+
+            EventData::TableMap(event) => {
+                // table maps in transaction payloads are not kept by the parser
+                self.parser
+                    .table_map_event_by_table_id
+                    .insert(event.table_id, event.clone());
+                writeln!(
+                    out,
+                    "\tTable_map: {}.{} mapped to number {}",
+                    quote_identifier(&event.database_name),
+                    quote_identifier(&event.table_name),
+                    event.table_id
+                )?;
+            }
+
+            EventData::WriteRows(event) => {
+                self.render_rows_header(&event_type, event.table_id, event.flags, out)?;
+                let table_map_event = self.table_map_event(event.table_id)?;
+                for row in event.rows.iter() {
+                    writeln!(out, "### INSERT INTO {}", table_name(table_map_event))?;
+                    writeln!(out, "### SET")?;
+                    render_row(table_map_event, row, &event.included_columns, out)?;
+                }
+            }
+
+            EventData::UpdateRows(event) => {
+                self.render_rows_header(&event_type, event.table_id, event.flags, out)?;
+                let table_map_event = self.table_map_event(event.table_id)?;
+                for (before, after) in event.rows.iter() {
+                    writeln!(out, "### UPDATE {}", table_name(table_map_event))?;
+                    writeln!(out, "### WHERE")?;
+                    render_row(table_map_event, before, &event.included_columns_before, out)?;
+                    writeln!(out, "### SET")?;
+                    render_row(table_map_event, after, &event.included_columns_after, out)?;
+                }
+            }
+
+            EventData::DeleteRows(event) => {
+                self.render_rows_header(&event_type, event.table_id, event.flags, out)?;
+                let table_map_event = self.table_map_event(event.table_id)?;
+                for row in event.rows.iter() {
+                    writeln!(out, "### DELETE FROM {}", table_name(table_map_event))?;
+                    writeln!(out, "### WHERE")?;
+                    render_row(table_map_event, row, &event.included_columns, out)?;
+                }
+            }
+
+            EventData::Xid(event) => {
+                writeln!(out, "\tXid = {}", event.xid)?;
+                writeln!(out, "COMMIT{}", DELIMITER)?;
+            }
+
+            EventData::XaPrepare(event) => {
+                let xid = format!(
+                    "X'{}',X'{}',{}",
+                    hex(event.gtrid.as_bytes()),
+                    hex(event.bqual.as_bytes()),
+                    event.format_id
+                );
+                let statement = if event.one_phase {
+                    format!("XA COMMIT {} ONE PHASE", xid)
+                } else {
+                    format!("XA PREPARE {}", xid)
+                };
+                writeln!(out, "\t{}", statement)?;
+                writeln!(out, "{}", statement)?;
+                writeln!(out, "{}", DELIMITER)?;
+            }
+
+            EventData::Rotate(event) => {
+                writeln!(
+                    out,
+                    "\tRotate to {}  pos: {}",
+                    event.binlog_filename, event.binlog_position
+                )?;
+            }
+
+            EventData::TransactionPayload(event) => {
+                writeln!(
+                    out,
+                    "\tTransaction_Payload\tsize={}\tuncompressed_size={}",
+                    header.event_length, event.uncompressed_size
+                )?;
+                writeln!(out, "# Start of compressed events!")?;
+                for (header, data) in event.uncompressed_events.iter() {
+                    self.render_header(header, None, out)?;
+                    self.render_data(header, data, out)?;
+                }
+                writeln!(out, "# End of compressed events!")?;
+            }
+
+            _ => {
+                writeln!(out, "\t{}", event_type_name(&event_type))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the SET lines of the session variables which changed, in the order of
+    /// Query_log_event::print_query_header of mysqlbinlog.
+    fn render_session_variables<W: Write>(
+        &mut self,
+        vars: &QueryStatusVars,
+        out: &mut W,
+    ) -> Result<(), BinlogError> {
+        let session = &mut self.session;
+        if let Some(flags2) = vars.flags2 {
+            // all the options are printed for the first query event
+            let changed = session.flags2.map_or(u32::MAX, |v| v ^ flags2);
+            if changed != 0 {
+                let options = [
+                    (OPTION_NO_FOREIGN_KEY_CHECKS, "foreign_key_checks", true),
+                    (OPTION_AUTO_IS_NULL, "sql_auto_is_null", false),
+                    (OPTION_RELAXED_UNIQUE_CHECKS, "unique_checks", true),
+                    (OPTION_NOT_AUTOCOMMIT, "autocommit", true),
+                ];
+                let assignments: Vec<String> = options
+                    .iter()
+                    .filter(|(option, _, _)| changed & option != 0)
+                    .map(|(option, name, inverted)| {
+                        let on = (flags2 & option != 0) != *inverted;
+                        format!("@@session.{}={}", name, on as u8)
+                    })
+                    .collect();
+                writeln!(out, "SET {}{}", assignments.join(", "), DELIMITER)?;
+                session.flags2 = Some(flags2);
+            }
+        }
+
+        if let Some(sql_mode) = vars.sql_mode {
+            if session.sql_mode != Some(sql_mode) {
+                writeln!(out, "SET @@session.sql_mode={}{}", sql_mode, DELIMITER)?;
+                session.sql_mode = Some(sql_mode);
+            }
+        }
+
+        let auto_increment = (
+            vars.auto_increment_increment.unwrap_or(1),
+            vars.auto_increment_offset.unwrap_or(1),
+        );
+        if session.auto_increment != auto_increment {
+            writeln!(
+                out,
+                "SET @@session.auto_increment_increment={}, @@session.auto_increment_offset={}{}",
+                auto_increment.0, auto_increment.1, DELIMITER
+            )?;
+            session.auto_increment = auto_increment;
+        }
+
+        if let (Some(client), Some(connection), Some(server)) = (
+            vars.character_set_client,
+            vars.collation_connection,
+            vars.collation_server,
+        ) {
+            let charset = [client, connection, server];
+            if session.charset != Some(charset) {
+                if let Some(name) = charset_name(client) {
+                    writeln!(out, "/*!\\C {} */{}", name, DELIMITER)?;
+                }
+                writeln!(
+                    out,
+                    "SET @@session.character_set_client={},@@session.collation_connection={},@@session.collation_server={}{}",
+                    client, connection, server, DELIMITER
+                )?;
+                session.charset = Some(charset);
+            }
+        }
+
+        if let Some(time_zone) = vars.time_zone.as_ref().filter(|v| !v.is_empty()) {
+            if &session.time_zone != time_zone {
+                writeln!(out, "SET @@session.time_zone='{}'{}", time_zone, DELIMITER)?;
+                session.time_zone = time_zone.clone();
+            }
+        }
+
+        let lc_time_names = vars.lc_time_names.unwrap_or(0);
+        if session.lc_time_names != Some(lc_time_names) {
+            writeln!(
+                out,
+                "SET @@session.lc_time_names={}{}",
+                lc_time_names, DELIMITER
+            )?;
+            session.lc_time_names = Some(lc_time_names);
+        }
+
+        let collation_database = vars.collation_database.unwrap_or(0);
+        if session.collation_database != Some(collation_database) {
+            if collation_database != 0 {
+                writeln!(
+                    out,
+                    "SET @@session.collation_database={}{}",
+                    collation_database, DELIMITER
+                )?;
+            } else {
+                writeln!(out, "SET @@session.collation_database=DEFAULT{}", DELIMITER)?;
+            }
+            session.collation_database = Some(collation_database);
+        }
+
+        if let Some(explicit_defaults_for_timestamp) = vars.explicit_defaults_for_timestamp {
+            writeln!(
+                out,
+                "SET @@session.explicit_defaults_for_timestamp={}{}",
+                explicit_defaults_for_timestamp as u8, DELIMITER
+            )?;
+        }
+
+        let default_collation_for_utf8mb4 = vars.default_collation_for_utf8mb4.unwrap_or(0);
+        if session.default_collation_for_utf8mb4 != Some(default_collation_for_utf8mb4) {
+            if default_collation_for_utf8mb4 != 0 {
+                writeln!(
+                    out,
+                    "/*!80011 SET @@session.default_collation_for_utf8mb4={}*/{}",
+                    default_collation_for_utf8mb4, DELIMITER
+                )?;
+            }
+            session.default_collation_for_utf8mb4 = Some(default_collation_for_utf8mb4);
+        }
+
+        if let Some(sql_require_primary_key) = vars.sql_require_primary_key {
+            if session.sql_require_primary_key != Some(sql_require_primary_key) {
+                writeln!(
+                    out,
+                    "/*!80013 SET @@session.sql_require_primary_key={}*/{}",
+                    sql_require_primary_key, DELIMITER
+                )?;
+                session.sql_require_primary_key = Some(sql_require_primary_key);
+            }
+        }
+
+        if let Some(default_table_encryption) = vars.default_table_encryption {
+            if session.default_table_encryption != Some(default_table_encryption) {
+                writeln!(
+                    out,
+                    "/*!80016 SET @@session.default_table_encryption={}*/{}",
+                    default_table_encryption, DELIMITER
+                )?;
+                session.default_table_encryption = Some(default_table_encryption);
+            }
+        }
+        Ok(())
+    }
+
+    fn render_rows_header<W: Write>(
+        &self,
+        event_type: &EventType,
+        table_id: u64,
+        flags: u16,
+        out: &mut W,
+    ) -> Result<(), BinlogError> {
+        writeln!(
+            out,
+            "\t{}: table id {}{}",
+            event_type_name(event_type),
+            table_id,
+            if flags & STMT_END_F != 0 {
+                " flags: STMT_END_F"
+            } else {
+                ""
+            }
+        )?;
+        Ok(())
+    }
+
+    fn table_map_event(&self, table_id: u64) -> Result<&TableMapEvent, BinlogError> {
+        self.parser
+            .table_map_event_by_table_id
+            .get(&table_id)
+            .ok_or_else(|| {
+                BinlogError::UnexpectedData(format!("no table map event for table {}", table_id))
+            })
+    }
+
+    /// Formats a time like mysqlbinlog does in event headers, e.g. 240610  6:13:20
+    fn format_time(&self, timestamp: u32) -> String {
+        let micros = (timestamp as i64 + self.time_zone_offset as i64) * 1_000_000;
+        let datetime = value_util::format_timestamp(micros, 0);
+        let hour: u32 = datetime[11..13].parse().unwrap_or_default();
+        format!(
+            "{}{}{} {:2}{}",
+            &datetime[2..4],
+            &datetime[5..7],
+            &datetime[8..10],
+            hour,
+            &datetime[13..]
+        )
+    }
+
+    fn format_commit_timestamp(&self, micros: u64) -> String {
+        let offset = self.time_zone_offset as i64;
+        let datetime = value_util::format_timestamp(micros as i64 + offset * 1_000_000, 6);
+        if offset == 0 {
+            format!("{} UTC", datetime)
+        } else {
+            let sign = if offset < 0 { '-' } else { '+' };
+            let offset = offset.abs() / 60;
+            format!("{} {}{:02}:{:02}", datetime, sign, offset / 60, offset % 60)
+        }
+    }
+}
+
+impl Default for MysqlbinlogRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Gets the event type name mysqlbinlog prints.
+fn event_type_name(event_type: &EventType) -> &'static str {
+    match event_type {
+        EventType::StartV3 => "Start_v3",
+        EventType::Query => "Query",
+        EventType::Stop => "Stop",
+        EventType::Rotate => "Rotate",
+        EventType::Intvar => "Intvar",
+        EventType::Rand => "RAND",
+        EventType::UserVar => "User var",
+        EventType::FormatDescription => "Format_desc",
+        EventType::Xid => "Xid",
+        EventType::BeginLoadQuery => "Begin_load_query",
+        EventType::ExecuteLoadQuery => "Execute_load_query",
+        EventType::TableMap => "Table_map",
+        EventType::WriteRows => "Write_rows_v1",
+        EventType::UpdateRows => "Update_rows_v1",
+        EventType::DeleteRows => "Delete_rows_v1",
+        EventType::Incident => "Incident",
+        EventType::HeartBeat => "Heartbeat",
+        EventType::Ignorable => "Ignorable",
+        EventType::RowsQuery => "Rows_query",
+        EventType::ExtWriteRows => "Write_rows",
+        EventType::ExtUpdateRows => "Update_rows",
+        EventType::ExtDeleteRows => "Delete_rows",
+        EventType::Gtid => "Gtid",
+        EventType::AnonymousGtid => "Anonymous_Gtid",
+        EventType::PreviousGtids => "Previous_gtids",
+        EventType::TransactionContext => "Transaction_context",
+        EventType::ViewChage => "View_change",
+        EventType::XaPrepare => "XA_prepare",
+        EventType::PartialUpdateRowsEvent => "Update_rows_partial",
+        EventType::TransactionPayload => "Transaction_payload",
+        _ => "Unknown",
+    }
+}
+
+/// Whether a query is a transaction keyword, which keeps the default database of the
+/// session for events with LOG_EVENT_SUPPRESS_USE_F, like Query_event::is_trans_keyword.
+fn is_trans_keyword(query: &str) -> bool {
+    let starts_with = |keyword: &str| {
+        query
+            .get(..keyword.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(keyword))
+    };
+    "BEGIN".starts_with(query)
+        || "COMMIT".starts_with(query)
+        || [
+            "SAVEPOINT",
+            "ROLLBACK",
+            "XA START",
+            "XA END",
+            "XA PREPARE",
+            "XA COMMIT",
+            "XA ROLLBACK",
+        ]
+        .iter()
+        .any(|keyword| starts_with(keyword))
+}
+
+/// Gets the charset name of a collation id, as mysqlbinlog prints in `/*!\C utf8mb4 */`.
+fn charset_name(collation: u16) -> Option<&'static str> {
+    let name = match collation {
+        1 | 84 => "big5",
+        2 | 9 | 21 | 27 | 77 => "latin2",
+        3 | 69 => "dec8",
+        4 | 80 => "cp850",
+        5 | 8 | 15 | 31 | 47 | 48 | 49 | 94 => "latin1",
+        6 | 72 => "hp8",
+        7 | 74 => "koi8r",
+        10 | 82 => "swe7",
+        11 | 65 => "ascii",
+        12 | 91 => "ujis",
+        13 | 88 => "sjis",
+        14 | 23 | 50 | 51 | 52 => "cp1251",
+        16 | 71 => "hebrew",
+        18 | 89 => "tis620",
+        19 | 85 => "euckr",
+        20 | 41 | 42 | 79 => "latin7",
+        22 | 75 => "koi8u",
+        24 | 86 => "gb2312",
+        25 | 70 => "greek",
+        26 | 34 | 44 | 66 | 99 => "cp1250",
+        28 | 87 => "gbk",
+        29 | 58 | 59 => "cp1257",
+        30 | 78 => "latin5",
+        32 | 64 => "armscii8",
+        33 | 76 | 83 | 192..=215 | 223 => "utf8mb3",
+        35 | 90 | 128..=151 | 159 => "ucs2",
+        36 | 68 => "cp866",
+        37 | 73 => "keybcs2",
+        38 | 43 => "macce",
+        39 | 53 => "macroman",
+        40 | 81 => "cp852",
+        45 | 46 | 224..=247 | 255..=323 => "utf8mb4",
+        54 | 55 | 101..=124 => "utf16",
+        56 | 62 => "utf16le",
+        57 | 67 => "cp1256",
+        60 | 61 | 160..=183 => "utf32",
+        63 => "binary",
+        92 | 93 => "geostd8",
+        95 | 96 => "cp932",
+        97 | 98 => "eucjpms",
+        248..=250 => "gb18030",
+        _ => return None,
+    };
+    Some(name)
+}
+
+fn table_name(table_map_event: &TableMapEvent) -> String {
+    format!(
+        "{}.{}",
+        quote_identifier(&table_map_event.database_name),
+        quote_identifier(&table_map_event.table_name)
+    )
+}
+
+/// Renders the columns included in the row image as `###   @1=value` lines.
+fn render_row<W: Write>(
+    table_map_event: &TableMapEvent,
+    row_event: &RowEvent,
+    included_columns: &[bool],
+    out: &mut W,
+) -> Result<(), BinlogError> {
+    let row = Row::new(row_event, table_map_event);
+    for column in row.columns.iter() {
+        if !included_columns.get(column.index).copied().unwrap_or(true) {
+            continue;
+        }
+        write!(out, "###   @{}=", column.index + 1)?;
+        write_value(column, out)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Writes a column value like mysqlbinlog --verbose does, integers are printed as signed
+/// with the unsigned value in parentheses if negative, since mysqlbinlog doesn't use the
+/// signedness metadata.
+fn write_value<W: Write>(column: &RowColumn, out: &mut W) -> Result<(), BinlogError> {
+    let fsp = value_util::fsp(column);
+    match column.value {
+        ColumnValue::None => write!(out, "NULL")?,
+
+        ColumnValue::Tiny(v) => write_signed_and_unsigned(*v as i64, *v as u8 as u64, out)?,
+
+        ColumnValue::Short(v) => write_signed_and_unsigned(*v as i64, *v as u16 as u64, out)?,
+
+        ColumnValue::Long(v) if column.column_type == ColumnType::Int24 => {
+            write_signed_and_unsigned(*v as i64, (*v as u32 & 0xFFFFFF) as u64, out)?
+        }
+
+        ColumnValue::Long(v) => write_signed_and_unsigned(*v as i64, *v as u32 as u64, out)?,
+
+        ColumnValue::LongLong(v) => write_signed_and_unsigned(*v, *v as u64, out)?,
+
+        ColumnValue::Float(v) => write!(out, "{}", format_g(*v as f64, 20))?,
+
+        ColumnValue::Double(v) => write!(out, "{}", format_g(*v, 20))?,
+
+        ColumnValue::Decimal(v) => write!(out, "{}", v)?,
+
+        // mysqlbinlog separates the date parts with colons
+        ColumnValue::Date(v) => {
+            let parts: Vec<u32> = v.split('-').map(|p| p.parse().unwrap_or(0)).collect();
+            if let [year, month, day] = parts[..] {
+                write!(out, "'{:04}:{:02}:{:02}'", year, month, day)?;
+            } else {
+                write!(out, "'{}'", v)?;
+            }
+        }
+
+        ColumnValue::Time(v) | ColumnValue::DateTime(v) => {
+            write!(out, "'{}'", value_util::truncate_fraction(v, fsp))?
+        }
+
+        ColumnValue::Timestamp(v) => {
+            let seconds = format!("{}.{:06}", v / 1_000_000, v % 1_000_000);
+            write!(out, "{}", value_util::truncate_fraction(&seconds, fsp))?
+        }
+
+        ColumnValue::Year(v) => write!(out, "{}", v)?,
+
+        ColumnValue::String(v) | ColumnValue::Blob(v) => write_quoted(v, out)?,
+
+        ColumnValue::Bit(v) => write_bits(*v, value_util::bit_count(column), out)?,
+
+        ColumnValue::Enum(v) => write!(out, "{}", v)?,
+
+        ColumnValue::Set(v) => write_bits(*v, (column.column_meta & 0xFF) as u32 * 8, out)?,
+
+        ColumnValue::Json(v) => {
+            let json = if v.is_empty() {
+                String::new()
+            } else {
+                JsonBinary::parse_as_string(v)?
+            };
+            write_quoted(json.as_bytes(), out)?
+        }
+
+        ColumnValue::Vector(v) => {
+            let bytes: Vec<u8> = v.iter().flat_map(|f| f.to_le_bytes()).collect();
+            write_quoted(&bytes, out)?
+        }
+    }
+    Ok(())
+}
+
+fn write_signed_and_unsigned<W: Write>(
+    signed: i64,
+    unsigned: u64,
+    out: &mut W,
+) -> Result<(), BinlogError> {
+    write!(out, "{}", signed)?;
+    if signed < 0 {
+        write!(out, " ({})", unsigned)?;
+    }
+    Ok(())
+}
+
+fn write_bits<W: Write>(bits: u64, bit_count: u32, out: &mut W) -> Result<(), BinlogError> {
+    write!(
+        out,
+        "b'{:0width$b}'",
+        bits,
+        width = bit_count.max(1) as usize
+    )?;
+    Ok(())
+}
+
+/// Writes bytes quoted, control characters, quotes and backslashes are written
+/// as \x escapes like mysqlbinlog does, other bytes as they are.
+fn write_quoted<W: Write>(bytes: &[u8], out: &mut W) -> Result<(), BinlogError> {
+    out.write_all(b"'")?;
+    for b in bytes {
+        if *b > 0x1F && *b != b'\'' && *b != b'\\' {
+            out.write_all(&[*b])?;
+        } else {
+            write!(out, "\\x{:02x}", b)?;
+        }
+    }
+    out.write_all(b"'")?;
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// Formats a number like printf("%-.{precision}g").
+fn format_g(value: f64, precision: usize) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return value.to_string();
+    }
+
+    let scientific = format!("{:.*e}", precision - 1, value);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    if exponent < -4 || exponent >= precision as i32 {
+        let mantissa = trim_fraction_zeros(mantissa);
+        let sign = if exponent < 0 { '-' } else { '+' };
+        format!("{}e{}{:02}", mantissa, sign, exponent.abs())
+    } else {
+        let decimals = (precision as i32 - 1 - exponent) as usize;
+        trim_fraction_zeros(&format!("{:.*}", decimals, value)).to_string()
+    }
+}
+
+fn trim_fraction_zeros(number: &str) -> &str {
+    if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::query_event::QueryEvent;
+
+    fn render_values(
+        column_types: Vec<u8>,
+        column_metas: Vec<u16>,
+        values: Vec<ColumnValue>,
+    ) -> Vec<String> {
+        let table_map_event = TableMapEvent {
+            table_id: 1,
+            flags: 1,
+            database_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            null_bits: vec![true; column_types.len()],
+            column_types,
+            column_metas,
+            table_metadata: None,
+        };
+        let row_event = RowEvent {
+            column_values: values,
+        };
+        Row::new(&row_event, &table_map_event)
+            .columns
+            .iter()
+            .map(|column| {
+                let mut out = Vec::new();
+                write_value(column, &mut out).unwrap();
+                String::from_utf8(out).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_write_value() {
+        // tinyint, bigint, float, date, datetime(3), timestamp(2), bit(10), varchar, enum, set
+        let values = render_values(
+            vec![1, 8, 4, 10, 18, 17, 16, 15, 254, 254],
+            vec![0, 0, 4, 0, 3, 2, 0x0102, 64, 0xF701, 0xF801],
+            vec![
+                ColumnValue::Tiny(-1),
+                ColumnValue::LongLong(-2),
+                ColumnValue::Float(1.1),
+                ColumnValue::Date("2024-1-02".into()),
+                ColumnValue::DateTime("2024-01-02 03:04:05.678000".into()),
+                ColumnValue::Timestamp(1_718_000_000_120_000),
+                ColumnValue::Bit(5),
+                ColumnValue::String(b"it's\n\xe4\xb8\xad".to_vec()),
+                ColumnValue::Enum(2),
+                ColumnValue::Set(3),
+            ],
+        );
+        assert_eq!(
+            values,
+            vec![
+                "-1 (255)",
+                "-2 (18446744073709551614)",
+                "1.1000000238418579102",
+                "'2024:01:02'",
+                "'2024-01-02 03:04:05.678'",
+                "1718000000.12",
+                "b'0000000101'",
+                "'it\\x27s\\x0a中'",
+                "2",
+                "b'00000011'",
+            ]
+        );
+    }
+
+    fn render_query(
+        renderer: &mut MysqlbinlogRenderer,
+        event_flags: u16,
+        status_vars: Vec<u8>,
+        query: &str,
+    ) -> String {
+        let header = EventHeader {
+            timestamp: 1718000000,
+            event_type: EventType::Query as u8,
+            server_id: 1,
+            event_length: 0,
+            next_event_position: 0,
+            event_flags,
+        };
+        let data = EventData::Query(QueryEvent {
+            thread_id: 7,
+            exec_time: 0,
+            error_code: 0,
+            status_vars,
+            schema: "db_1".into(),
+            query: query.into(),
+        });
+        let mut out = Vec::new();
+        renderer.render_data(&header, &data, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_render_session_variables() {
+        let mut renderer = MysqlbinlogRenderer::new();
+        // flags2, sql_mode, charset, time_zone
+        let mut status_vars = vec![0, 0, 0, 0, 0];
+        status_vars.extend_from_slice(&[1, 0x20, 0, 0xa0, 0x45, 0, 0, 0, 0]);
+        status_vars.extend_from_slice(&[4, 0xff, 0, 0xff, 0, 0xff, 0]);
+        status_vars.extend_from_slice(&[5, 6, b'+', b'0', b'8', b':', b'0', b'0']);
+        assert_eq!(
+            render_query(&mut renderer, 0, status_vars.clone(), "CREATE TABLE t (id INT)"),
+            "\tQuery\tthread_id=7\texec_time=0\terror_code=0\n\
+             use `db_1`/*!*/;\n\
+             SET TIMESTAMP=1718000000/*!*/;\n\
+             SET @@session.pseudo_thread_id=7/*!*/;\n\
+             SET @@session.foreign_key_checks=1, @@session.sql_auto_is_null=0, @@session.unique_checks=1, @@session.autocommit=1/*!*/;\n\
+             SET @@session.sql_mode=1168113696/*!*/;\n\
+             SET @@session.auto_increment_increment=1, @@session.auto_increment_offset=1/*!*/;\n\
+             /*!\\C utf8mb4 *//*!*/;\n\
+             SET @@session.character_set_client=255,@@session.collation_connection=255,@@session.collation_server=255/*!*/;\n\
+             SET @@session.time_zone='+08:00'/*!*/;\n\
+             SET @@session.lc_time_names=0/*!*/;\n\
+             SET @@session.collation_database=DEFAULT/*!*/;\n\
+             CREATE TABLE t (id INT)\n/*!*/;\n"
+        );
+
+        // only the changed variables are printed again, foreign_key_checks=0 and microseconds
+        status_vars[4] = 0x04;
+        status_vars.extend_from_slice(&[13, 0x40, 0xe2, 0x01]);
+        assert_eq!(
+            render_query(&mut renderer, 0x8, status_vars, "BEGIN"),
+            "\tQuery\tthread_id=7\texec_time=0\terror_code=0\n\
+             SET TIMESTAMP=1718000000.123456/*!*/;\n\
+             SET @@session.foreign_key_checks=0/*!*/;\n\
+             BEGIN\n/*!*/;\n"
+        );
+    }
+
+    #[test]
+    fn test_is_trans_keyword() {
+        assert!(is_trans_keyword("BEGIN"));
+        assert!(is_trans_keyword("COMMIT"));
+        assert!(is_trans_keyword("ROLLBACK TO sp_1"));
+        assert!(is_trans_keyword("xa start X'31',X'',1"));
+        assert!(!is_trans_keyword("BEGIN WORK"));
+        assert!(!is_trans_keyword("INSERT INTO t VALUES (1)"));
+    }
+
+    #[test]
+    fn test_format_g() {
+        assert_eq!(format_g(1.5, 20), "1.5");
+        assert_eq!(format_g(-0.1, 20), "-0.10000000000000000555");
+        assert_eq!(format_g(1e20, 20), "1e+20");
+        assert_eq!(format_g(1.25e-5, 20), "1.2500000000000000599e-05");
+        assert_eq!(format_g(123456.0, 20), "123456");
+    }
+
+    #[test]
+    fn test_format_time() {
+        let renderer = MysqlbinlogRenderer::new();
+        assert_eq!(renderer.format_time(1718000000), "240610  6:13:20");
+        let renderer = MysqlbinlogRenderer::new().with_time_zone_offset(8 * 3600);
+        assert_eq!(renderer.format_time(1718000000), "240610 14:13:20");
+        assert_eq!(
+            renderer.format_commit_timestamp(1718000000123456),
+            "2024-06-10 14:13:20.123456 +08:00"
+        );
+    }
+}
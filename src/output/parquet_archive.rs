@@ -0,0 +1,529 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+
+use arrow_schema::SchemaRef;
+use parquet::{arrow::ArrowWriter, format::KeyValue};
+
+use crate::{
+    binlog_error::BinlogError, change::change_event::RowChange, command::gtid_set::GtidSet,
+};
+
+use super::{
+    arrow::{arrow_schema_from_table_map_event, to_record_batch},
+    value_util,
+};
+
+/// Key-value metadata keys of the binlog range an archive file covers.
+pub const START_BINLOG_FILENAME_KEY: &str = "binlog.start.filename";
+pub const START_BINLOG_POSITION_KEY: &str = "binlog.start.position";
+pub const END_BINLOG_FILENAME_KEY: &str = "binlog.end.filename";
+pub const END_BINLOG_POSITION_KEY: &str = "binlog.end.position";
+pub const GTID_SET_KEY: &str = "binlog.gtid_set";
+
+const IN_PROGRESS_SUFFIX: &str = ".inprogress";
+
+/// Archives row changes into Parquet files partitioned by database, table and the hour
+/// of the event time:
+///
+/// {root_dir}/{database}/{table}/{yyyy-mm-dd}/{hh}/{binlog_filename}-{binlog_position}.parquet
+///
+/// Database and table names are encoded like MySQL names their files on disk, characters
+/// other than [A-Za-z0-9_] are written as @xxxx, so a name like `../a` can not escape root_dir.
+/// Existing archive files are never overwritten, archiving the same binlog range twice fails.
+///
+/// Files are written with an .inprogress suffix and renamed when closed. Event times in the
+/// binlog are not ordered (they are the start times of the statements), so a file is closed
+/// once the watermark, the latest event time seen minus the grace period, passes the end of
+/// its hour, when its table changes shape, or by `close`. A change arriving after the file of
+/// its hour was closed goes to a new file in the same directory.
+/// Each file records the binlog position range and GTID set it covers in its key-value metadata.
+/// Rows are in the format of `ArrowBatcher`.
+pub struct ParquetArchiveWriter {
+    pub root_dir: PathBuf,
+    /// Max number of rows buffered in memory before written to the file as a record batch.
+    pub batch_size: usize,
+    /// Seconds the event times may lag behind the latest one seen before files are closed.
+    pub grace_period: u32,
+    /// Latest event time seen.
+    max_timestamp: u32,
+    files: HashMap<(String, String, u64), ArchiveFile>,
+}
+
+struct ArchiveFile {
+    hour: u64,
+    path: PathBuf,
+    schema: SchemaRef,
+    writer: ArrowWriter<File>,
+    pending: Vec<RowChange>,
+    /// Position of the first event in the file.
+    start: (String, u64),
+    /// Position after the last event in the file, where a replay continues.
+    end: (String, u64),
+    gtid_set: GtidSet,
+}
+
+impl ParquetArchiveWriter {
+    pub fn new(root_dir: &Path) -> Self {
+        Self {
+            root_dir: root_dir.to_path_buf(),
+            batch_size: 8192,
+            grace_period: 300,
+            max_timestamp: 0,
+            files: HashMap::new(),
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn with_grace_period(mut self, grace_period: u32) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// Archives a row change, returns the paths of the files closed by it.
+    /// A batch failing to be converted to a record batch is dropped with the error,
+    /// so the changes after it are still archived.
+    pub fn write(&mut self, change: &RowChange) -> Result<Vec<PathBuf>, BinlogError> {
+        let hour = change.source.timestamp as u64 / 3600;
+        self.max_timestamp = self.max_timestamp.max(change.source.timestamp);
+        // files of hours ended before the watermark are complete
+        let watermark = self.max_timestamp.saturating_sub(self.grace_period) as u64;
+        let mut closed = self.close_where(|_, file| (file.hour + 1) * 3600 <= watermark)?;
+
+        let database_name = change.database_name();
+        let table_name = change.table_name();
+        let schema = arrow_schema_from_table_map_event(&change.table_map_event);
+        closed.extend(self.close_where(|key, file| {
+            key.0 == database_name && key.1 == table_name && file.schema != schema
+        })?);
+
+        let key = (database_name.to_string(), table_name.to_string(), hour);
+        if !self.files.contains_key(&key) {
+            let file = self.create_file(change, hour, schema)?;
+            self.files.insert(key.clone(), file);
+        }
+        let file = self.files.get_mut(&key).unwrap();
+        file.append(change)?;
+        if file.pending.len() >= self.batch_size {
+            file.write_pending()?;
+        }
+        Ok(closed)
+    }
+
+    /// Closes all files, returns their paths.
+    pub fn close(&mut self) -> Result<Vec<PathBuf>, BinlogError> {
+        self.close_where(|_, _| true)
+    }
+
+    fn close_where(
+        &mut self,
+        predicate: impl Fn(&(String, String, u64), &ArchiveFile) -> bool,
+    ) -> Result<Vec<PathBuf>, BinlogError> {
+        let mut keys: Vec<(String, String, u64)> = self
+            .files
+            .iter()
+            .filter(|(key, file)| predicate(key, file))
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.sort();
+
+        let mut closed = Vec::with_capacity(keys.len());
+        for key in keys {
+            let file = self.files.remove(&key).unwrap();
+            closed.push(file.close()?);
+        }
+        Ok(closed)
+    }
+
+    fn create_file(
+        &self,
+        change: &RowChange,
+        hour: u64,
+        schema: SchemaRef,
+    ) -> Result<ArchiveFile, BinlogError> {
+        let datetime = value_util::format_timestamp(hour as i64 * 3_600_000_000, 0);
+        let dir = self
+            .root_dir
+            .join(encode_file_name(change.database_name()))
+            .join(encode_file_name(change.table_name()))
+            .join(&datetime[..10])
+            .join(&datetime[11..13]);
+        fs::create_dir_all(&dir)?;
+
+        let source = &change.source;
+        let path = dir.join(format!(
+            "{}-{}.parquet",
+            source.binlog_filename, source.binlog_position
+        ));
+        check_not_exists(&path)?;
+        let file = File::create(in_progress_path(&path))?;
+        let writer = ArrowWriter::try_new(file, schema.clone(), None)?;
+
+        let start = (source.binlog_filename.clone(), source.binlog_position);
+        Ok(ArchiveFile {
+            hour,
+            path,
+            schema,
+            writer,
+            pending: Vec::new(),
+            end: start.clone(),
+            start,
+            gtid_set: GtidSet::new("")?,
+        })
+    }
+}
+
+impl ArchiveFile {
+    fn append(&mut self, change: &RowChange) -> Result<(), BinlogError> {
+        let source = &change.source;
+        self.end = (source.binlog_filename.clone(), source.next_binlog_position);
+        if let Some(gtid) = &source.gtid {
+            self.gtid_set.add(gtid)?;
+        }
+        self.pending.push(change.clone());
+        Ok(())
+    }
+
+    fn write_pending(&mut self) -> Result<(), BinlogError> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        // taken before the conversion, a bad row must not block the following batches
+        let pending = std::mem::take(&mut self.pending);
+        let batch = to_record_batch(self.schema.clone(), &pending)?;
+        self.writer.write(&batch)?;
+        Ok(())
+    }
+
+    fn close(mut self) -> Result<PathBuf, BinlogError> {
+        self.write_pending()?;
+        let metadata = [
+            (START_BINLOG_FILENAME_KEY, self.start.0.clone()),
+            (START_BINLOG_POSITION_KEY, self.start.1.to_string()),
+            (END_BINLOG_FILENAME_KEY, self.end.0.clone()),
+            (END_BINLOG_POSITION_KEY, self.end.1.to_string()),
+            (GTID_SET_KEY, self.gtid_set.to_string()),
+        ];
+        for (key, value) in metadata {
+            self.writer
+                .append_key_value_metadata(KeyValue::new(key.to_string(), value));
+        }
+        self.writer.close()?;
+
+        check_not_exists(&self.path)?;
+        fs::rename(in_progress_path(&self.path), &self.path)?;
+        Ok(self.path)
+    }
+}
+
+fn check_not_exists(path: &Path) -> Result<(), BinlogError> {
+    if path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("archive file already exists: {}", path.display()),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Encodes a database or table name like MySQL's tablename_to_filename,
+/// e.g. `a/b` -> `a@002fb`, characters beyond the BMP are written as surrogate pairs.
+fn encode_file_name(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            result.push(c);
+        } else {
+            let mut units = [0; 2];
+            for unit in c.encode_utf16(&mut units) {
+                result.push_str(&format!("@{:04x}", unit));
+            }
+        }
+    }
+    result
+}
+
+fn in_progress_path(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(IN_PROGRESS_SUFFIX);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use parquet::{
+        arrow::arrow_reader::ParquetRecordBatchReaderBuilder,
+        file::reader::{FileReader, SerializedFileReader},
+    };
+
+    use super::*;
+    use crate::{
+        change::change_event::{ChangeSource, RowOperation},
+        column::column_value::ColumnValue,
+        event::{row_event::RowEvent, table_map_event::TableMapEvent},
+    };
+
+    fn create_row_change(
+        table_map_event: &Arc<TableMapEvent>,
+        timestamp: u32,
+        position: u64,
+        gtid: &str,
+    ) -> RowChange {
+        RowChange {
+            operation: RowOperation::Insert,
+            source: ChangeSource {
+                timestamp,
+                binlog_filename: "mysql-bin.000003".into(),
+                binlog_position: position,
+                next_binlog_position: position + 50,
+                gtid: Some(gtid.into()),
+                ..Default::default()
+            },
+            row_index: 0,
+            table_map_event: table_map_event.clone(),
+            before: None,
+            after: Some(RowEvent {
+                column_values: (0..table_map_event.column_types.len())
+                    .map(|i| ColumnValue::Long(i as i32))
+                    .collect(),
+            }),
+            included_columns_before: Vec::new(),
+            included_columns_after: Vec::new(),
+        }
+    }
+
+    fn create_table_map_event(column_count: usize) -> Arc<TableMapEvent> {
+        Arc::new(TableMapEvent {
+            table_id: 1,
+            flags: 1,
+            database_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            column_types: vec![3; column_count],
+            column_metas: vec![0; column_count],
+            null_bits: vec![true; column_count],
+            table_metadata: None,
+        })
+    }
+
+    fn key_value_metadata(path: &Path) -> HashMap<String, String> {
+        let reader = SerializedFileReader::new(File::open(path).unwrap()).unwrap();
+        reader
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .unwrap()
+            .iter()
+            .map(|kv| (kv.key.clone(), kv.value.clone().unwrap_or_default()))
+            .collect()
+    }
+
+    fn row_count(path: &Path) -> usize {
+        ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum()
+    }
+
+    #[test]
+    fn test_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = ParquetArchiveWriter::new(dir.path()).with_batch_size(2);
+        let uuid = "5ca6b6a2-1de8-11ee-a4d4-0242ac110002";
+        let table_map_event = create_table_map_event(2);
+
+        // 2023-11-14 22:13:20 UTC
+        let timestamp = 1700000000;
+        for i in 0..3 {
+            let change = create_row_change(
+                &table_map_event,
+                timestamp,
+                100 * (i + 1),
+                &format!("{}:{}", uuid, i + 1),
+            );
+            assert!(writer.write(&change).unwrap().is_empty());
+        }
+
+        // the next hour closes the file
+        let change = create_row_change(
+            &table_map_event,
+            timestamp + 3600,
+            400,
+            &format!("{}:4", uuid),
+        );
+        let closed = writer.write(&change).unwrap();
+        assert_eq!(
+            closed,
+            vec![dir
+                .path()
+                .join("db_1/tb_1/2023-11-14/22/mysql-bin.000003-100.parquet")]
+        );
+        assert_eq!(row_count(&closed[0]), 3);
+        let metadata = key_value_metadata(&closed[0]);
+        assert_eq!(metadata[START_BINLOG_FILENAME_KEY], "mysql-bin.000003");
+        assert_eq!(metadata[START_BINLOG_POSITION_KEY], "100");
+        assert_eq!(metadata[END_BINLOG_POSITION_KEY], "350");
+        assert_eq!(metadata[GTID_SET_KEY], format!("{}:1-3", uuid));
+
+        // a schema change closes the file
+        let change = create_row_change(
+            &create_table_map_event(3),
+            timestamp + 3600,
+            500,
+            &format!("{}:5", uuid),
+        );
+        let closed = writer.write(&change).unwrap();
+        assert_eq!(
+            closed,
+            vec![dir
+                .path()
+                .join("db_1/tb_1/2023-11-14/23/mysql-bin.000003-400.parquet")]
+        );
+        assert_eq!(row_count(&closed[0]), 1);
+
+        let closed = writer.close().unwrap();
+        assert_eq!(closed.len(), 1);
+        assert_eq!(
+            key_value_metadata(&closed[0])[GTID_SET_KEY],
+            format!("{}:5-5", uuid)
+        );
+        assert!(!in_progress_path(&closed[0]).exists());
+    }
+
+    #[test]
+    fn test_archive_unordered_timestamps() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = ParquetArchiveWriter::new(dir.path()).with_grace_period(600);
+        let uuid = "5ca6b6a2-1de8-11ee-a4d4-0242ac110002";
+        let table_map_event = create_table_map_event(2);
+
+        // 2023-11-14 22:59:00, 23:01:00 and 22:59:30 UTC, the late change still goes to
+        // the file of hour 22
+        for (i, timestamp) in [1700002740, 1700002860, 1700002770].into_iter().enumerate() {
+            let change = create_row_change(
+                &table_map_event,
+                timestamp,
+                100 * (i as u64 + 1),
+                &format!("{}:{}", uuid, i + 1),
+            );
+            assert!(writer.write(&change).unwrap().is_empty());
+        }
+
+        // 23:10:00 is past the end of hour 22 plus the grace period
+        let change = create_row_change(&table_map_event, 1700003400, 400, &format!("{}:4", uuid));
+        let closed = writer.write(&change).unwrap();
+        assert_eq!(
+            closed,
+            vec![dir
+                .path()
+                .join("db_1/tb_1/2023-11-14/22/mysql-bin.000003-100.parquet")]
+        );
+        assert_eq!(row_count(&closed[0]), 2);
+        assert_eq!(
+            key_value_metadata(&closed[0])[GTID_SET_KEY],
+            format!("{}:1-1:3-3", uuid)
+        );
+
+        // a change of a closed hour goes to a new file
+        let change = create_row_change(&table_map_event, 1700002799, 500, &format!("{}:5", uuid));
+        assert!(writer.write(&change).unwrap().is_empty());
+        let closed = writer.close().unwrap();
+        assert_eq!(
+            closed,
+            vec![
+                dir.path()
+                    .join("db_1/tb_1/2023-11-14/22/mysql-bin.000003-500.parquet"),
+                dir.path()
+                    .join("db_1/tb_1/2023-11-14/23/mysql-bin.000003-200.parquet"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_file_name() {
+        assert_eq!(encode_file_name("tb_1"), "tb_1");
+        assert_eq!(encode_file_name("../a"), "@002e@002e@002fa");
+        assert_eq!(encode_file_name("a-b.c"), "a@002db@002ec");
+        assert_eq!(encode_file_name("表"), "@8868");
+        assert_eq!(encode_file_name("😀"), "@d83d@de00");
+    }
+
+    #[test]
+    fn test_archive_escaped_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let root_dir = dir.path().join("archive");
+        let mut writer = ParquetArchiveWriter::new(&root_dir);
+        let mut table_map_event = (*create_table_map_event(1)).clone();
+        table_map_event.database_name = "..".into();
+        table_map_event.table_name = "../../tb/1".into();
+        let change = create_row_change(
+            &Arc::new(table_map_event),
+            1700000000,
+            100,
+            "5ca6b6a2-1de8-11ee-a4d4-0242ac110002:1",
+        );
+        writer.write(&change).unwrap();
+        let closed = writer.close().unwrap();
+        assert_eq!(
+            closed,
+            vec![root_dir.join(
+                "@002e@002e/@002e@002e@002f@002e@002e@002ftb@002f1/2023-11-14/22/mysql-bin.000003-100.parquet"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_archive_bad_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = ParquetArchiveWriter::new(dir.path()).with_batch_size(1);
+        let uuid = "5ca6b6a2-1de8-11ee-a4d4-0242ac110002";
+        let table_map_event = create_table_map_event(2);
+
+        // a change without row image can not be converted, its batch is dropped
+        let mut change =
+            create_row_change(&table_map_event, 1700000000, 100, &format!("{}:1", uuid));
+        change.after = None;
+        assert!(matches!(
+            writer.write(&change),
+            Err(BinlogError::UnexpectedData(_))
+        ));
+
+        let change = create_row_change(&table_map_event, 1700000000, 200, &format!("{}:2", uuid));
+        assert!(writer.write(&change).unwrap().is_empty());
+        let closed = writer.close().unwrap();
+        assert_eq!(row_count(&closed[0]), 1);
+    }
+
+    #[test]
+    fn test_archive_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let table_map_event = create_table_map_event(1);
+        let change = create_row_change(
+            &table_map_event,
+            1700000000,
+            100,
+            "5ca6b6a2-1de8-11ee-a4d4-0242ac110002:1",
+        );
+
+        let mut writer = ParquetArchiveWriter::new(dir.path());
+        writer.write(&change).unwrap();
+        let closed = writer.close().unwrap();
+        let content = fs::read(&closed[0]).unwrap();
+
+        // replaying the same range fails instead of overwriting the archived file
+        let mut writer = ParquetArchiveWriter::new(dir.path());
+        assert!(writer.write(&change).is_err());
+        assert_eq!(fs::read(&closed[0]).unwrap(), content);
+    }
+}
@@ -0,0 +1,676 @@
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{
+    binlog_error::BinlogError,
+    change::change_event::{RowChange, RowOperation},
+    column::{column_type::ColumnType, column_value::ColumnValue, json::json_binary::JsonBinary},
+    event::{
+        delete_rows_event::DeleteRowsEvent,
+        row::{Row, RowColumn},
+        row_event::RowEvent,
+        table_map_event::TableMapEvent,
+        update_rows_event::UpdateRowsEvent,
+        write_rows_event::WriteRowsEvent,
+    },
+};
+
+use super::value_util;
+
+/// Generates SQL statements from row events, like binlog2sql:
+///
+/// INSERT INTO `db`.`tb`(`id`, `name`) VALUES (1, 'a');
+/// UPDATE `db`.`tb` SET `id`=1, `name`='b' WHERE `id`=1 LIMIT 1;
+/// DELETE FROM `db`.`tb` WHERE `id`=1 LIMIT 1;
+///
+/// Rows are matched by the primary key columns if the TableMapEvent contains primary key
+/// metadata (binlog_row_metadata=FULL), otherwise by all columns except FLOAT, DOUBLE and
+/// VECTOR columns, whose values may not compare equal once printed. Columns not included in
+/// the row image (binlog_row_image=MINIMAL / NOBLOB) are skipped.
+///
+/// Column names come from the TableMapEvent (binlog_row_metadata=FULL) or from
+/// `with_column_names`, UPDATE and DELETE statements and INSERT statements of partial row
+/// images can not be generated without them.
+#[derive(Debug, Clone, Default)]
+pub struct SqlGenerator {
+    /// Column names by (database, table), used when the TableMapEvent contains none.
+    column_names: HashMap<(String, String), Vec<String>>,
+}
+
+impl SqlGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the column names of a table, in the order of its columns.
+    pub fn with_column_names(mut self, database: &str, table: &str, names: Vec<String>) -> Self {
+        self.column_names
+            .insert((database.to_string(), table.to_string()), names);
+        self
+    }
+
+    /// Generates the statement of a row change, or None for snapshot reads.
+    pub fn generate(&self, change: &RowChange) -> Result<Option<String>, BinlogError> {
+        let table_map_event = &change.table_map_event;
+        let included_before = &change.included_columns_before;
+        let included_after = &change.included_columns_after;
+        let sql = match (change.operation, &change.before, &change.after) {
+            (RowOperation::Insert, _, Some(after)) => {
+                self.insert(table_map_event, after, included_after)?
+            }
+
+            (RowOperation::Update, Some(before), Some(after)) => self.update(
+                table_map_event,
+                before,
+                included_before,
+                after,
+                included_after,
+            )?,
+
+            (RowOperation::Delete, Some(before), _) => {
+                self.delete(table_map_event, before, included_before)?
+            }
+
+            _ => return Ok(None),
+        };
+        Ok(Some(sql))
+    }
+
+    pub fn generate_write_rows(
+        &self,
+        table_map_event: &TableMapEvent,
+        event: &WriteRowsEvent,
+    ) -> Result<Vec<String>, BinlogError> {
+        event
+            .rows
+            .iter()
+            .map(|row| self.insert(table_map_event, row, &event.included_columns))
+            .collect()
+    }
+
+    pub fn generate_update_rows(
+        &self,
+        table_map_event: &TableMapEvent,
+        event: &UpdateRowsEvent,
+    ) -> Result<Vec<String>, BinlogError> {
+        event
+            .rows
+            .iter()
+            .map(|(before, after)| {
+                self.update(
+                    table_map_event,
+                    before,
+                    &event.included_columns_before,
+                    after,
+                    &event.included_columns_after,
+                )
+            })
+            .collect()
+    }
+
+    pub fn generate_delete_rows(
+        &self,
+        table_map_event: &TableMapEvent,
+        event: &DeleteRowsEvent,
+    ) -> Result<Vec<String>, BinlogError> {
+        event
+            .rows
+            .iter()
+            .map(|row| self.delete(table_map_event, row, &event.included_columns))
+            .collect()
+    }
+
+    fn insert(
+        &self,
+        table_map_event: &TableMapEvent,
+        row_event: &RowEvent,
+        included: &[bool],
+    ) -> Result<String, BinlogError> {
+        let row = Row::new(row_event, table_map_event);
+        let columns = included_columns(&row, included);
+
+        let mut sql = format!("INSERT INTO {}", table_name(&row));
+        // the column list may be omitted if all columns are included
+        if columns.len() < row.columns.len() || self.has_column_names(&row) {
+            let names = columns
+                .iter()
+                .map(|column| self.column_name(&row, column))
+                .collect::<Result<Vec<_>, _>>()?;
+            write!(sql, "({})", names.join(", ")).unwrap();
+        }
+        let values = columns
+            .iter()
+            .map(|column| literal(column))
+            .collect::<Result<Vec<_>, _>>()?;
+        write!(sql, " VALUES ({});", values.join(", ")).unwrap();
+        Ok(sql)
+    }
+
+    fn update(
+        &self,
+        table_map_event: &TableMapEvent,
+        before: &RowEvent,
+        included_before: &[bool],
+        after: &RowEvent,
+        included_after: &[bool],
+    ) -> Result<String, BinlogError> {
+        let before = Row::new(before, table_map_event);
+        let after = Row::new(after, table_map_event);
+
+        let mut assignments = Vec::new();
+        for column in included_columns(&after, included_after) {
+            assignments.push(format!(
+                "{}={}",
+                self.column_name(&after, column)?,
+                literal(column)?
+            ));
+        }
+        Ok(format!(
+            "UPDATE {} SET {} {};",
+            table_name(&after),
+            assignments.join(", "),
+            self.where_clause(&before, included_before)?
+        ))
+    }
+
+    fn delete(
+        &self,
+        table_map_event: &TableMapEvent,
+        before: &RowEvent,
+        included: &[bool],
+    ) -> Result<String, BinlogError> {
+        let before = Row::new(before, table_map_event);
+        Ok(format!(
+            "DELETE FROM {} {};",
+            table_name(&before),
+            self.where_clause(&before, included)?
+        ))
+    }
+
+    /// Builds the WHERE clause matching the row by the primary key if known, otherwise by
+    /// all columns in the row image except FLOAT, DOUBLE and VECTOR columns.
+    fn where_clause(&self, row: &Row, included: &[bool]) -> Result<String, BinlogError> {
+        let mut columns = row.primary_key();
+        if columns.is_empty() {
+            columns = included_columns(row, included)
+                .into_iter()
+                .filter(|column| {
+                    !matches!(
+                        column.column_type,
+                        ColumnType::Float | ColumnType::Double | ColumnType::Vector
+                    )
+                })
+                .collect();
+        }
+        if columns.is_empty() {
+            return Err(BinlogError::UnexpectedData(format!(
+                "no column to match rows of {} by",
+                table_name(row)
+            )));
+        }
+
+        let mut conditions = Vec::with_capacity(columns.len());
+        for column in columns {
+            let name = self.column_name(row, column)?;
+            let condition = match column.value {
+                ColumnValue::None => format!("{} IS NULL", name),
+                _ => format!("{}={}", name, literal(column)?),
+            };
+            conditions.push(condition);
+        }
+        Ok(format!("WHERE {} LIMIT 1", conditions.join(" AND ")))
+    }
+
+    fn has_column_names(&self, row: &Row) -> bool {
+        row.columns.iter().all(|column| column.name().is_some())
+            || self.caller_column_names(row).is_some()
+    }
+
+    fn caller_column_names(&self, row: &Row) -> Option<&Vec<String>> {
+        self.column_names
+            .get(&(row.database_name.to_string(), row.table_name.to_string()))
+    }
+
+    /// Quotes the column name from the TableMapEvent or the names set by `with_column_names`.
+    fn column_name(&self, row: &Row, column: &RowColumn) -> Result<String, BinlogError> {
+        let name = column.name().or_else(|| {
+            self.caller_column_names(row)
+                .and_then(|names| names.get(column.index))
+                .map(|name| name.as_str())
+        });
+        match name {
+            Some(name) => Ok(quote_identifier(name)),
+            None => Err(BinlogError::UnexpectedData(format!(
+                "column names of {} are unknown, enable binlog_row_metadata=FULL or set them by SqlGenerator::with_column_names",
+                table_name(row)
+            ))),
+        }
+    }
+}
+
+fn included_columns<'a, 'b>(row: &'b Row<'a>, included: &[bool]) -> Vec<&'b RowColumn<'a>> {
+    row.columns
+        .iter()
+        .filter(|column| included.get(column.index).copied().unwrap_or(true))
+        .collect()
+}
+
+fn table_name(row: &Row) -> String {
+    format!(
+        "{}.{}",
+        quote_identifier(row.database_name),
+        quote_identifier(row.table_name)
+    )
+}
+
+/// Quotes an identifier with backticks, e.g. my`table -> `my``table`
+pub fn quote_identifier(identifier: &str) -> String {
+    format!("`{}`", identifier.replace('`', "``"))
+}
+
+/// Quotes a string as a MySQL string literal, escaping special characters
+/// like mysql_real_escape_string does.
+pub fn quote_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('\'');
+    for c in value.chars() {
+        match c {
+            '\0' => quoted.push_str("\\0"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\x1a' => quoted.push_str("\\Z"),
+            '\\' => quoted.push_str("\\\\"),
+            '\'' => quoted.push_str("\\'"),
+            '"' => quoted.push_str("\\\""),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// Converts a column value into a MySQL literal:
+///
+/// - binary strings, BLOBs and GEOMETRY values, and text that is not valid UTF-8 are hex
+///   literals, e.g. X'0A0B'
+/// - BIT values are bit literals, e.g. b'101'
+/// - TIMESTAMP values are FROM_UNIXTIME(seconds) so they don't depend on the session time zone
+/// - JSON values are CAST('...' AS JSON), VECTOR values are STRING_TO_VECTOR('[...]')
+/// - ENUM and SET values are strings if the TableMapEvent contains the values, otherwise
+///   indexes and bitmaps which MySQL accepts as well
+pub fn literal(column: &RowColumn) -> Result<String, BinlogError> {
+    let fsp = value_util::fsp(column);
+    let literal = match column.value {
+        ColumnValue::None => "NULL".to_string(),
+
+        ColumnValue::Tiny(_)
+        | ColumnValue::Short(_)
+        | ColumnValue::Long(_)
+        | ColumnValue::LongLong(_)
+        | ColumnValue::Year(_) => value_util::integer_value(column)
+            .unwrap_or_default()
+            .to_string(),
+
+        ColumnValue::Float(v) => v.to_string(),
+
+        ColumnValue::Double(v) => v.to_string(),
+
+        ColumnValue::Decimal(v) => v.clone(),
+
+        ColumnValue::Date(v) => quote_string(v),
+
+        ColumnValue::Time(v) | ColumnValue::DateTime(v) => {
+            quote_string(value_util::truncate_fraction(v, fsp))
+        }
+
+        // zero TIMESTAMP 0000-00-00 00:00:00 is stored as 0
+        ColumnValue::Timestamp(0) => "'0000-00-00 00:00:00'".to_string(),
+
+        ColumnValue::Timestamp(v) => {
+            let seconds = format!("{}.{:06}", v.div_euclid(1_000_000), v.rem_euclid(1_000_000));
+            format!(
+                "FROM_UNIXTIME({})",
+                value_util::truncate_fraction(&seconds, fsp)
+            )
+        }
+
+        ColumnValue::String(v) | ColumnValue::Blob(v) => match std::str::from_utf8(v) {
+            Ok(s) if !column.is_binary() => quote_string(s),
+            _ => hex_literal(v),
+        },
+
+        ColumnValue::Bit(v) => format!(
+            "b'{:0width$b}'",
+            v,
+            width = value_util::bit_count(column).max(1) as usize
+        ),
+
+        ColumnValue::Enum(v) => match column.enum_string() {
+            Some(s) => quote_string(s),
+            None => v.to_string(),
+        },
+
+        ColumnValue::Set(v) => match column.set_strings() {
+            Some(strings) => quote_string(&strings.join(",")),
+            None => v.to_string(),
+        },
+
+        ColumnValue::Json(v) => {
+            // partial JSON updates are not supported, an empty value is JSON null
+            let json = if v.is_empty() {
+                "null".to_string()
+            } else {
+                JsonBinary::parse_as_string(v)?
+            };
+            format!("CAST({} AS JSON)", quote_string(&json))
+        }
+
+        ColumnValue::Vector(v) => {
+            let values: Vec<String> = v.iter().map(|f| f.to_string()).collect();
+            format!("STRING_TO_VECTOR('[{}]')", values.join(","))
+        }
+    };
+    Ok(literal)
+}
+
+fn hex_literal(bytes: &[u8]) -> String {
+    let mut literal = String::with_capacity(bytes.len() * 2 + 3);
+    literal.push_str("X'");
+    for b in bytes {
+        write!(literal, "{:02X}", b).unwrap();
+    }
+    literal.push('\'');
+    literal
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        change::change_event::ChangeSource,
+        event::table_map::table_metadata::{ColumnMetadata, TableMetadata},
+    };
+
+    fn create_table_map_event(
+        column_types: Vec<u8>,
+        column_metas: Vec<u16>,
+        names: Option<Vec<&str>>,
+        primary_key: &[usize],
+    ) -> TableMapEvent {
+        let table_metadata = names.map(|names| TableMetadata {
+            columns: names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| ColumnMetadata {
+                    column_name: Some(name.to_string()),
+                    is_simple_primary_key: primary_key.contains(&i).then_some(true),
+                    ..Default::default()
+                })
+                .collect(),
+            default_charset: None,
+            enum_and_set_default_charset: None,
+        });
+        TableMapEvent {
+            table_id: 1,
+            flags: 1,
+            database_name: "db_1".into(),
+            table_name: "tb`1".into(),
+            null_bits: vec![true; column_types.len()],
+            column_types,
+            column_metas,
+            table_metadata,
+        }
+    }
+
+    fn column<'a>(
+        table_map_event: &'a TableMapEvent,
+        row_event: &'a RowEvent,
+        index: usize,
+    ) -> RowColumn<'a> {
+        Row::new(row_event, table_map_event).columns[index].clone()
+    }
+
+    #[test]
+    fn test_quote_string() {
+        assert_eq!(quote_string("it's"), r"'it\'s'");
+        assert_eq!(quote_string("a\\b\"c"), r#"'a\\b\"c'"#);
+        assert_eq!(quote_string("\0\n\r\x1a"), r"'\0\n\r\Z'");
+        assert_eq!(quote_identifier("tb`1"), "`tb``1`");
+    }
+
+    #[test]
+    fn test_literal() {
+        // int unsigned, decimal, datetime(3), timestamp(2), bit(10), varbinary, varchar, json
+        let table_map_event = create_table_map_event(
+            vec![3, 246, 18, 17, 16, 15, 15, 245],
+            vec![0, 0x0A02, 3, 2, 0x0102, 16, 64, 4],
+            None,
+            &[],
+        );
+        let row_event = RowEvent {
+            column_values: vec![
+                ColumnValue::Long(-1),
+                ColumnValue::Decimal("-12.30".into()),
+                ColumnValue::DateTime("2024-01-31 12:34:56.789000".into()),
+                ColumnValue::Timestamp(1_700_000_000_123_456),
+                ColumnValue::Bit(5),
+                ColumnValue::String(vec![0, 0xFF]),
+                ColumnValue::String(b"a'b".to_vec()),
+                ColumnValue::Json(vec![]),
+            ],
+        };
+        let literals: Vec<String> = (0..8)
+            .map(|i| literal(&column(&table_map_event, &row_event, i)).unwrap())
+            .collect();
+        assert_eq!(
+            literals,
+            vec![
+                // signedness unknown without metadata
+                "-1",
+                "-12.30",
+                "'2024-01-31 12:34:56.789'",
+                "FROM_UNIXTIME(1700000000.12)",
+                "b'0000000101'",
+                "X'00FF'",
+                r"'a\'b'",
+                "CAST('null' AS JSON)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate() {
+        let table_map_event = Arc::new(create_table_map_event(
+            vec![3, 15],
+            vec![0, 64],
+            Some(vec!["id", "name"]),
+            &[0],
+        ));
+        let row = |id: i32, name: &str| RowEvent {
+            column_values: vec![ColumnValue::Long(id), ColumnValue::String(name.into())],
+        };
+        let change = |operation, before, after| RowChange {
+            operation,
+            source: ChangeSource::default(),
+            row_index: 0,
+            table_map_event: table_map_event.clone(),
+            before,
+            after,
+            included_columns_before: Vec::new(),
+            included_columns_after: Vec::new(),
+        };
+
+        let generator = SqlGenerator::new();
+        let sql = |change| generator.generate(&change).unwrap().unwrap();
+        assert_eq!(
+            sql(change(RowOperation::Insert, None, Some(row(1, "a")))),
+            "INSERT INTO `db_1`.`tb``1`(`id`, `name`) VALUES (1, 'a');"
+        );
+        assert_eq!(
+            sql(change(
+                RowOperation::Update,
+                Some(row(1, "a")),
+                Some(row(1, "b"))
+            )),
+            "UPDATE `db_1`.`tb``1` SET `id`=1, `name`='b' WHERE `id`=1 LIMIT 1;"
+        );
+        assert_eq!(
+            sql(change(RowOperation::Delete, Some(row(1, "a")), None)),
+            "DELETE FROM `db_1`.`tb``1` WHERE `id`=1 LIMIT 1;"
+        );
+
+        // minimal row image, columns not in the images are skipped
+        let mut update = change(
+            RowOperation::Update,
+            Some(RowEvent {
+                column_values: vec![ColumnValue::Long(1), ColumnValue::None],
+            }),
+            Some(RowEvent {
+                column_values: vec![ColumnValue::None, ColumnValue::String(b"b".to_vec())],
+            }),
+        );
+        update.included_columns_before = vec![true, false];
+        update.included_columns_after = vec![false, true];
+        assert_eq!(
+            sql(update),
+            "UPDATE `db_1`.`tb``1` SET `name`='b' WHERE `id`=1 LIMIT 1;"
+        );
+    }
+
+    #[test]
+    fn test_generate_rows_events_without_metadata() {
+        let table_map_event = create_table_map_event(vec![3, 15], vec![0, 64], None, &[]);
+        let generator = SqlGenerator::new();
+
+        let event = WriteRowsEvent {
+            table_id: 1,
+            flags: 1,
+            extra_data: Vec::new(),
+            included_columns: vec![true, true],
+            rows: vec![RowEvent {
+                column_values: vec![ColumnValue::Long(1), ColumnValue::None],
+            }],
+        };
+        assert_eq!(
+            generator
+                .generate_write_rows(&table_map_event, &event)
+                .unwrap(),
+            vec!["INSERT INTO `db_1`.`tb``1` VALUES (1, NULL);"]
+        );
+
+        // minimal row image, the after image only contains the changed column
+        let event = UpdateRowsEvent {
+            table_id: 1,
+            flags: 1,
+            extra_data: Vec::new(),
+            included_columns_before: vec![true, true],
+            included_columns_after: vec![false, true],
+            rows: vec![(
+                RowEvent {
+                    column_values: vec![ColumnValue::Long(1), ColumnValue::None],
+                },
+                RowEvent {
+                    column_values: vec![ColumnValue::None, ColumnValue::String(b"b".to_vec())],
+                },
+            )],
+        };
+        assert!(matches!(
+            generator.generate_update_rows(&table_map_event, &event),
+            Err(BinlogError::UnexpectedData(_))
+        ));
+
+        // column names set by the caller
+        let names = vec!["id".to_string(), "name".to_string()];
+        let named_generator = SqlGenerator::new().with_column_names("db_1", "tb`1", names);
+        assert_eq!(
+            named_generator
+                .generate_update_rows(&table_map_event, &event)
+                .unwrap(),
+            vec!["UPDATE `db_1`.`tb``1` SET `name`='b' WHERE `id`=1 AND `name` IS NULL LIMIT 1;"]
+        );
+
+        let event = DeleteRowsEvent {
+            table_id: 1,
+            flags: 1,
+            extra_data: Vec::new(),
+            included_columns: vec![true, false],
+            rows: vec![RowEvent {
+                column_values: vec![ColumnValue::Long(1), ColumnValue::None],
+            }],
+        };
+        assert!(matches!(
+            generator.generate_delete_rows(&table_map_event, &event),
+            Err(BinlogError::UnexpectedData(_))
+        ));
+        assert_eq!(
+            named_generator
+                .generate_delete_rows(&table_map_event, &event)
+                .unwrap(),
+            vec!["DELETE FROM `db_1`.`tb``1` WHERE `id`=1 LIMIT 1;"]
+        );
+
+        // minimal row image, the column list can not be omitted
+        let event = WriteRowsEvent {
+            table_id: 1,
+            flags: 1,
+            extra_data: Vec::new(),
+            included_columns: vec![true, false],
+            rows: vec![RowEvent {
+                column_values: vec![ColumnValue::Long(1), ColumnValue::None],
+            }],
+        };
+        assert!(matches!(
+            generator.generate_write_rows(&table_map_event, &event),
+            Err(BinlogError::UnexpectedData(_))
+        ));
+        assert_eq!(
+            named_generator
+                .generate_write_rows(&table_map_event, &event)
+                .unwrap(),
+            vec!["INSERT INTO `db_1`.`tb``1`(`id`) VALUES (1);"]
+        );
+    }
+
+    #[test]
+    fn test_where_clause_skips_floats() {
+        // int, float, double without primary key
+        let table_map_event = create_table_map_event(
+            vec![3, 4, 5],
+            vec![0, 4, 8],
+            Some(vec!["id", "f", "d"]),
+            &[],
+        );
+        let generator = SqlGenerator::new();
+        let row = RowEvent {
+            column_values: vec![
+                ColumnValue::Long(1),
+                ColumnValue::Float(0.1),
+                ColumnValue::Double(0.2),
+            ],
+        };
+        let event = DeleteRowsEvent {
+            table_id: 1,
+            flags: 1,
+            extra_data: Vec::new(),
+            included_columns: vec![true; 3],
+            rows: vec![row],
+        };
+        assert_eq!(
+            generator
+                .generate_delete_rows(&table_map_event, &event)
+                .unwrap(),
+            vec!["DELETE FROM `db_1`.`tb``1` WHERE `id`=1 LIMIT 1;"]
+        );
+
+        // no column left to match the rows by
+        let event = DeleteRowsEvent {
+            included_columns: vec![false, true, true],
+            ..event
+        };
+        assert!(matches!(
+            generator.generate_delete_rows(&table_map_event, &event),
+            Err(BinlogError::UnexpectedData(_))
+        ));
+    }
+}
@@ -0,0 +1,86 @@
+use crate::binlog_error::BinlogError;
+
+/// Time zone to print TIMESTAMP values in, binlogs store them as UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeZone {
+    /// Fixed offset in seconds east of UTC.
+    Offset(i32),
+    /// Named time zone like "Europe/Berlin", the offset of each value follows the DST rules
+    /// of the zone at that time.
+    #[cfg(feature = "chrono-tz")]
+    Named(chrono_tz::Tz),
+}
+
+impl TimeZone {
+    /// Parses a time zone name of the IANA database, like MySQL's time_zone variable.
+    #[cfg(feature = "chrono-tz")]
+    pub fn named(name: &str) -> Result<Self, BinlogError> {
+        name.parse::<chrono_tz::Tz>()
+            .map(TimeZone::Named)
+            .map_err(|_| BinlogError::UnexpectedData(format!("unknown time zone: {}", name)))
+    }
+
+    /// Converts a timestamp in microseconds since the epoch into local microseconds.
+    pub fn to_local_micros(&self, micros: i64) -> Result<i64, BinlogError> {
+        let offset = match self {
+            TimeZone::Offset(offset) => *offset,
+
+            #[cfg(feature = "chrono-tz")]
+            TimeZone::Named(tz) => named_offset(tz, micros.div_euclid(1_000_000)),
+        };
+        micros
+            .checked_add(offset as i64 * 1_000_000)
+            .ok_or_else(|| BinlogError::UnexpectedData(format!("invalid timestamp: {}", micros)))
+    }
+}
+
+/// Gets the offset in seconds east of UTC of the zone at the timestamp (seconds since the epoch).
+#[cfg(feature = "chrono-tz")]
+fn named_offset(tz: &chrono_tz::Tz, timestamp: i64) -> i32 {
+    use chrono::{Offset, TimeZone as _};
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|utc| {
+            tz.offset_from_utc_datetime(&utc.naive_utc())
+                .fix()
+                .local_minus_utc()
+        })
+        .unwrap_or(0)
+}
+
+impl Default for TimeZone {
+    fn default() -> Self {
+        TimeZone::Offset(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimeZone;
+
+    #[test]
+    fn test_offset() {
+        let time_zone = TimeZone::Offset(8 * 3600);
+        assert_eq!(
+            time_zone.to_local_micros(1).unwrap(),
+            8 * 3600 * 1_000_000 + 1
+        );
+        assert!(time_zone.to_local_micros(i64::MAX).is_err());
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn test_named() {
+        let time_zone = TimeZone::named("Europe/Berlin").unwrap();
+        // 2024-01-31 12:00:00 UTC, CET
+        assert_eq!(
+            time_zone.to_local_micros(1_706_702_400_000_000).unwrap(),
+            1_706_706_000_000_000
+        );
+        // 2024-07-31 12:00:00 UTC, CEST
+        assert_eq!(
+            time_zone.to_local_micros(1_722_427_200_000_000).unwrap(),
+            1_722_434_400_000_000
+        );
+        assert!(TimeZone::named("Not/A_Zone").is_err());
+    }
+}
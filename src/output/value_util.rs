@@ -0,0 +1,388 @@
+// Helpers shared by the output formats to convert decoded column values,
+// temporal values are decoded by the parser into strings like:
+// DATE: 2024-01-31, TIME: -838:59:59.000000, DATETIME: 2024-01-31 12:34:56.123456
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    binlog_error::BinlogError,
+    column::{column_type::ColumnType, column_value::ColumnValue},
+    event::{row::RowColumn, table_map::table_metadata::ColumnMetadata},
+};
+
+pub(crate) const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// Current time in milliseconds since epoch.
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Days since 1970-01-01 of a civil date,
+/// refer: https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let month = month as i64;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Civil date (year, month, day) of days since 1970-01-01,
+/// refer: https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Parses a DATE string into days since epoch, None for zero dates like 0000-00-00.
+pub(crate) fn parse_date_days(date: &str) -> Option<i64> {
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if month == 0 || day == 0 {
+        return None;
+    }
+    Some(days_from_civil(year, month, day))
+}
+
+/// Parses a TIME string into signed microseconds.
+pub(crate) fn parse_time_micros(time: &str) -> Option<i64> {
+    let (negative, time) = match time.strip_prefix('-') {
+        Some(time) => (true, time),
+        None => (false, time),
+    };
+    let (hms, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut parts = hms.splitn(3, ':');
+    let hour: i64 = parts.next()?.parse().ok()?;
+    let minute: i64 = parts.next()?.parse().ok()?;
+    let second: i64 = parts.next()?.parse().ok()?;
+    let micros = ((hour * 60 + minute) * 60 + second) * 1_000_000 + parse_fraction(fraction)?;
+    Some(if negative { -micros } else { micros })
+}
+
+/// Parses a DATETIME string into microseconds since epoch, None for zero dates.
+pub(crate) fn parse_datetime_micros(datetime: &str) -> Option<i64> {
+    let (date, time) = datetime.split_once(' ').unwrap_or((datetime, "00:00:00"));
+    Some(parse_date_days(date)? * MICROS_PER_DAY + parse_time_micros(time)?)
+}
+
+/// Formats microseconds since epoch as an ISO-8601 UTC timestamp with `fsp` fraction digits,
+/// e.g. 2024-01-31T12:34:56.123Z
+pub(crate) fn format_iso_timestamp(micros: i64, fsp: u32) -> String {
+    format!("{}Z", format_timestamp(micros, fsp).replacen(' ', "T", 1))
+}
+
+/// Formats microseconds since epoch as a UTC datetime with `fsp` fraction digits,
+/// e.g. 2024-01-31 12:34:56.123
+pub(crate) fn format_timestamp(micros: i64, fsp: u32) -> String {
+    let days = micros.div_euclid(MICROS_PER_DAY);
+    let micros_of_day = micros.rem_euclid(MICROS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    let seconds = micros_of_day / 1_000_000;
+    let datetime = format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:06}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        micros_of_day % 1_000_000
+    );
+    truncate_fraction(&datetime, fsp).to_string()
+}
+
+/// Truncates the fraction of a TIME / DATETIME string to `fsp` digits,
+/// the parser always decodes TIME2 / DATETIME2 values with 6 digits.
+pub(crate) fn truncate_fraction(value: &str, fsp: u32) -> &str {
+    match value.find('.') {
+        Some(dot) if fsp == 0 => &value[..dot],
+        Some(dot) => &value[..(dot + 1 + fsp.min(6) as usize).min(value.len())],
+        None => value,
+    }
+}
+
+fn parse_fraction(fraction: &str) -> Option<i64> {
+    if fraction.is_empty() {
+        return Some(0);
+    }
+    let digits = &fraction[..fraction.len().min(6)];
+    let value: i64 = digits.parse().ok()?;
+    Some(value * 10_i64.pow(6 - digits.len() as u32))
+}
+
+/// Fractional seconds precision of TIME2 / DATETIME2 / TIMESTAMP2 columns.
+pub(crate) fn fsp(column: &RowColumn) -> u32 {
+    match column.column_type {
+        ColumnType::Time2 | ColumnType::DateTime2 | ColumnType::TimeStamp2 => {
+            column.column_meta as u32
+        }
+        _ => 0,
+    }
+}
+
+/// Gets the value of an integer column as i128, unsigned columns are reinterpreted
+/// if the signedness is known from the column metadata.
+pub(crate) fn integer_value(column: &RowColumn) -> Option<i128> {
+    let unsigned = column.is_signed() == Some(false);
+    let value = match (column.value, unsigned) {
+        (ColumnValue::Tiny(v), false) => *v as i128,
+        (ColumnValue::Tiny(v), true) => *v as u8 as i128,
+        (ColumnValue::Short(v), false) => *v as i128,
+        (ColumnValue::Short(v), true) => *v as u16 as i128,
+        (ColumnValue::Long(v), false) => *v as i128,
+        (ColumnValue::Long(v), true) if column.column_type == ColumnType::Int24 => {
+            (*v as u32 & 0xFFFFFF) as i128
+        }
+        (ColumnValue::Long(v), true) => *v as u32 as i128,
+        (ColumnValue::LongLong(v), false) => *v as i128,
+        (ColumnValue::LongLong(v), true) => *v as u64 as i128,
+        (ColumnValue::Year(v), _) => *v as i128,
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Gets the text of a character value, invalid UTF-8 sequences are replaced.
+pub(crate) fn text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// Converts a BIT value to its bytes in little-endian order, the length is the
+/// number of bytes needed for the column width.
+pub(crate) fn bit_bytes(column: &RowColumn, bits: u64) -> Vec<u8> {
+    let bit_count = bit_count(column);
+    let length = bit_count.div_ceil(8).max(1) as usize;
+    bits.to_le_bytes()[..length.min(8)].to_vec()
+}
+
+pub(crate) fn bit_count(column: &RowColumn) -> u32 {
+    (column.column_meta >> 8) as u32 * 8 + (column.column_meta & 0xFF) as u32
+}
+
+/// Gets the MySQL column definition like `int unsigned`, `varchar(64)` or `decimal(10,2)`,
+/// lengths of character columns are derived from the byte lengths in the column meta and
+/// may be inaccurate for charsets other than latin1 / utf8mb3 / utf8mb4.
+pub(crate) fn column_definition(column: &RowColumn) -> String {
+    let meta = column.column_meta;
+    let unsigned = if column.is_signed() == Some(false) {
+        " unsigned"
+    } else {
+        ""
+    };
+    let with_fsp = |name: &str| match fsp(column) {
+        0 => name.to_string(),
+        fsp => format!("{}({})", name, fsp),
+    };
+    let char_length = |byte_length: u16| byte_length as u32 / charset_max_bytes(column);
+
+    match column.column_type {
+        ColumnType::Tiny => format!("tinyint{}", unsigned),
+        ColumnType::Short => format!("smallint{}", unsigned),
+        ColumnType::Int24 => format!("mediumint{}", unsigned),
+        ColumnType::Long => format!("int{}", unsigned),
+        ColumnType::LongLong => format!("bigint{}", unsigned),
+        ColumnType::Float => format!("float{}", unsigned),
+        ColumnType::Double => format!("double{}", unsigned),
+        ColumnType::NewDecimal | ColumnType::Decimal => {
+            format!("decimal({},{}){}", meta & 0xFF, meta >> 8, unsigned)
+        }
+        ColumnType::Date | ColumnType::NewDate => "date".to_string(),
+        ColumnType::Time | ColumnType::Time2 => with_fsp("time"),
+        ColumnType::DateTime | ColumnType::DateTime2 => with_fsp("datetime"),
+        ColumnType::TimeStamp | ColumnType::TimeStamp2 => with_fsp("timestamp"),
+        ColumnType::Year => "year".to_string(),
+        ColumnType::VarChar | ColumnType::VarString => {
+            if column.is_binary() {
+                format!("varbinary({})", meta)
+            } else {
+                format!("varchar({})", char_length(meta))
+            }
+        }
+        ColumnType::String => {
+            let (_, length) = ColumnType::parse_string_column_meta(meta, ColumnType::String as u8)
+                .unwrap_or((0, meta));
+            if column.is_binary() {
+                format!("binary({})", length)
+            } else {
+                format!("char({})", char_length(length))
+            }
+        }
+        ColumnType::TinyBlob | ColumnType::MediumBlob | ColumnType::LongBlob | ColumnType::Blob => {
+            // the column meta is the number of bytes of the value length
+            let prefix = match meta {
+                1 => "tiny",
+                3 => "medium",
+                4 => "long",
+                _ => "",
+            };
+            let suffix = if column.is_binary() { "blob" } else { "text" };
+            format!("{}{}", prefix, suffix)
+        }
+        ColumnType::Enum => format!("enum({})", quote_values(column, |m| &m.enum_string_values)),
+        ColumnType::Set => format!("set({})", quote_values(column, |m| &m.set_string_values)),
+        ColumnType::Bit => format!("bit({})", bit_count(column)),
+        ColumnType::Json => "json".to_string(),
+        ColumnType::Geometry => {
+            let geometry_type = column.metadata.and_then(|m| m.geometry_type);
+            match geometry_type {
+                Some(1) => "point",
+                Some(2) => "linestring",
+                Some(3) => "polygon",
+                Some(4) => "multipoint",
+                Some(5) => "multilinestring",
+                Some(6) => "multipolygon",
+                Some(7) => "geomcollection",
+                _ => "geometry",
+            }
+            .to_string()
+        }
+        ColumnType::Vector => match column.metadata.and_then(|m| m.vector_dimensionality) {
+            Some(dimensionality) => format!("vector({})", dimensionality),
+            None => "vector".to_string(),
+        },
+        _ => format!("{:?}", column.column_type).to_lowercase(),
+    }
+}
+
+fn quote_values(
+    column: &RowColumn,
+    get_values: impl Fn(&ColumnMetadata) -> &Option<Vec<String>>,
+) -> String {
+    let values = column.metadata.and_then(|m| get_values(m).as_ref());
+    match values {
+        Some(values) => values
+            .iter()
+            .map(|v| format!("'{}'", v.replace('\'', "''")))
+            .collect::<Vec<String>>()
+            .join(","),
+        None => String::new(),
+    }
+}
+
+/// Max bytes per character of the charset of a collation.
+fn charset_max_bytes(column: &RowColumn) -> u32 {
+    match column.charset_collation {
+        // utf8mb4
+        Some(45 | 46 | 224..=247 | 255..=323) => 4,
+        // utf8mb3
+        Some(33 | 76 | 83 | 192..=215 | 223) => 3,
+        // ucs2, utf16, utf32 and multi byte asian charsets are not handled
+        _ => 1,
+    }
+}
+
+/// Converts a decimal string into its unscaled value in big-endian two's complement,
+/// the same as java.math.BigInteger.toByteArray(), and the scale.
+pub(crate) fn decimal_unscaled_bytes(decimal: &str) -> Result<(Vec<u8>, u32), BinlogError> {
+    let invalid = || BinlogError::UnexpectedData(format!("invalid decimal: {}", decimal));
+    let (negative, unsigned) = match decimal.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, decimal),
+    };
+    let (integral, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let mut digits = Vec::with_capacity(integral.len() + fraction.len());
+    for c in integral.chars().chain(fraction.chars()) {
+        digits.push(c.to_digit(10).ok_or_else(invalid)? as u16);
+    }
+    if digits.is_empty() {
+        return Err(invalid());
+    }
+
+    // convert base 10 digits to base 256 by repeated division, little-endian
+    let mut magnitude = Vec::new();
+    while digits.iter().any(|d| *d != 0) {
+        let mut remainder = 0u16;
+        for digit in digits.iter_mut() {
+            let current = remainder * 10 + *digit;
+            *digit = current / 256;
+            remainder = current % 256;
+        }
+        magnitude.push(remainder as u8);
+    }
+    // make sure the sign bit is not set for the magnitude
+    magnitude.push(0);
+
+    if negative {
+        let mut carry = true;
+        for byte in magnitude.iter_mut() {
+            let (value, overflow) = (!*byte).overflowing_add(carry as u8);
+            *byte = value;
+            carry = overflow;
+        }
+    }
+
+    // remove redundant sign bytes
+    let sign_byte = if negative { 0xFF } else { 0 };
+    while magnitude.len() > 1
+        && magnitude[magnitude.len() - 1] == sign_byte
+        && (magnitude[magnitude.len() - 2] & 0x80) == (sign_byte & 0x80)
+    {
+        magnitude.pop();
+    }
+    magnitude.reverse();
+    Ok((magnitude, fraction.len() as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(civil_from_days(11017), (2000, 3, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(parse_date_days("0000-00-00"), None);
+        assert_eq!(
+            parse_datetime_micros("1970-01-02 00:00:01.500000"),
+            Some(MICROS_PER_DAY + 1_500_000)
+        );
+        assert_eq!(parse_time_micros("-01:00:00.000001"), Some(-3_600_000_001));
+        assert_eq!(parse_time_micros("838:59:59"), Some(3_020_399_000_000));
+        assert_eq!(
+            format_iso_timestamp(1_700_000_000_123_456, 3),
+            "2023-11-14T22:13:20.123Z"
+        );
+        assert_eq!(format_iso_timestamp(0, 0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1_500_000, 6), "1970-01-01 00:00:01.500000");
+        assert_eq!(truncate_fraction("12:00:00.123456", 2), "12:00:00.12");
+        assert_eq!(
+            truncate_fraction("2024-01-31 12:00:00.000000", 0),
+            "2024-01-31 12:00:00"
+        );
+        assert_eq!(truncate_fraction("2024-01-31", 3), "2024-01-31");
+    }
+
+    #[test]
+    fn test_decimal_unscaled_bytes() {
+        assert_eq!(decimal_unscaled_bytes("0").unwrap(), (vec![0], 0));
+        assert_eq!(decimal_unscaled_bytes("1.27").unwrap(), (vec![0x7F], 2));
+        assert_eq!(decimal_unscaled_bytes("1.28").unwrap(), (vec![0, 0x80], 2));
+        assert_eq!(decimal_unscaled_bytes("-1.28").unwrap(), (vec![0x80], 2));
+        assert_eq!(
+            decimal_unscaled_bytes("-1.29").unwrap(),
+            (vec![0xFF, 0x7F], 2)
+        );
+        assert_eq!(
+            decimal_unscaled_bytes("-12345.6789").unwrap(),
+            (vec![0xF8, 0xA4, 0x32, 0xEB], 4)
+        );
+        assert!(decimal_unscaled_bytes("1.2x").is_err());
+    }
+}
@@ -31,9 +31,9 @@ mod test {
     }
 
     fn run_and_check(col_type: &str, values: &Vec<&str>) {
-        let runner = TestRunner::run_one_col_test(col_type, values, &vec![]);
-        for i in 0..values.len() {
-            let value: u64 = values[i].parse::<u64>().unwrap();
+        let runner = TestRunner::run_one_col_test(col_type, values, &[]);
+        for (i, value) in values.iter().enumerate() {
+            let value: u64 = value.parse::<u64>().unwrap();
             assert_eq!(
                 runner.insert_events[0].rows[i].column_values[0],
                 ColumnValue::Bit(value)
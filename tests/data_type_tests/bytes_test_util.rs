@@ -13,17 +13,17 @@ impl BytesTestUtil {
     pub fn run_and_check(
         mysql_column_type: &str,
         binlog_column_type: ColumnType,
-        values: &Vec<String>,
-        check_values: &Vec<Vec<u8>>,
+        values: &[String],
+        check_values: &[Vec<u8>],
     ) {
-        let values: Vec<&str> = values.into_iter().map(|i| i.as_str()).collect();
-        let runner = TestRunner::run_one_col_test(mysql_column_type, &values, &vec![]);
-        for i in 0..check_values.len() {
+        let values: Vec<&str> = values.iter().map(|i| i.as_str()).collect();
+        let runner = TestRunner::run_one_col_test(mysql_column_type, &values, &[]);
+        for (i, check_value) in check_values.iter().enumerate() {
             let column_value = match binlog_column_type {
                 ColumnType::String | ColumnType::VarChar => {
-                    ColumnValue::String(check_values[i].clone())
+                    ColumnValue::String(check_value.clone())
                 }
-                ColumnType::Blob => ColumnValue::Blob(check_values[i].clone()),
+                ColumnType::Blob => ColumnValue::Blob(check_value.clone()),
                 _ => ColumnValue::None,
             };
             assert_eq!(
@@ -42,7 +42,7 @@ impl BytesTestUtil {
             let mut bytes = Vec::new();
             // The visible character number range is 32-126
             for i in 1..n {
-                bytes.push(32 + i as u8);
+                bytes.push(32 + i);
                 str.push(char::from_u32(32 + i as u32).unwrap());
             }
             (str, bytes)
@@ -80,13 +80,13 @@ impl BytesTestUtil {
         values.push("  a        ".to_string());
         values.push("  a    b   ".to_string());
 
-        for i in check_values.len()..values.len() {
+        for value in values.iter().skip(check_values.len()) {
             let mut bytes = Vec::new();
 
             let str = if keep_trailing_space_in_binlog {
-                &values[i]
+                value
             } else {
-                values[i].trim_end()
+                value.trim_end()
             };
 
             for i in 0..str.len() {
@@ -127,10 +127,10 @@ impl BytesTestUtil {
         (Self::get_test_values(&values), check_values)
     }
 
-    fn get_test_values(values: &Vec<String>) -> Vec<String> {
+    fn get_test_values(values: &[String]) -> Vec<String> {
         // ["a", "ab"] -> ["('a')", "('ab')"]
         let mut test_values = Vec::new();
-        for s in values.clone() {
+        for s in values {
             test_values.push(format!("('{}')", s));
         }
         test_values
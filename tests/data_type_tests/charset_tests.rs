@@ -77,17 +77,12 @@ mod test {
         run_and_check(col_type, "SET names utf8mb4", &values, &check_values);
     }
 
-    fn run_and_check(
-        col_type: &str,
-        init_sql: &str,
-        values: &Vec<&str>,
-        check_values: &Vec<Vec<u8>>,
-    ) {
-        let runner = TestRunner::run_one_col_test(col_type, values, &vec![init_sql]);
-        for i in 0..check_values.len() {
+    fn run_and_check(col_type: &str, init_sql: &str, values: &Vec<&str>, check_values: &[Vec<u8>]) {
+        let runner = TestRunner::run_one_col_test(col_type, values, &[init_sql]);
+        for (i, check_value) in check_values.iter().enumerate() {
             assert_eq!(
                 runner.insert_events[0].rows[i].column_values[0],
-                ColumnValue::String(check_values[i].clone())
+                ColumnValue::String(check_value.clone())
             );
         }
     }
@@ -92,13 +92,13 @@ mod test {
 
     fn run_and_check(col_type: &str, values: &[&str], check_values: &[&str]) {
         let runner =
-            TestRunner::run_one_col_test(col_type, values, &vec!["SET @@session.time_zone='UTC'"]);
+            TestRunner::run_one_col_test(col_type, values, &["SET @@session.time_zone='UTC'"]);
 
         assert_eq!(runner.insert_events[0].rows.len(), check_values.len());
-        for i in 0..check_values.len() {
+        for (i, check_value) in check_values.iter().enumerate() {
             assert_eq!(
                 runner.insert_events[0].rows[i].column_values[0],
-                ColumnValue::DateTime(check_values[i].to_string()),
+                ColumnValue::DateTime(check_value.to_string()),
             );
         }
     }
@@ -83,14 +83,14 @@ mod test {
         let (values, check_values) = generate_decimal_values(precision, scale);
         let mut insert_sqls = vec![];
         for v in values {
-            insert_sqls.push(Mock::one_col_insert_sql(&vec![v.as_str()]));
+            insert_sqls.push(Mock::one_col_insert_sql(&[v.as_str()]));
         }
 
         runner.execute_sqls_and_get_binlogs(&prepare_sqls, &insert_sqls);
-        for i in 0..check_values.len() {
+        for (i, check_value) in check_values.iter().enumerate() {
             assert_eq!(
                 runner.insert_events[i].rows[0].column_values[0],
-                ColumnValue::Decimal(check_values[i].clone())
+                ColumnValue::Decimal(check_value.clone())
             );
         }
     }
@@ -140,8 +140,8 @@ mod test {
 
         // negative values
         let mut values = tmp_values.clone();
-        for i in 0..tmp_values.len() {
-            values.push("-".to_string() + &tmp_values[i]);
+        for tmp_value in tmp_values.iter() {
+            values.push("-".to_string() + tmp_value);
         }
 
         // 0
@@ -157,7 +157,7 @@ mod test {
         // ["0", "1.1"] -> ["(0.0000)", "(1.1000)"]
         let mut check_values = Vec::new();
         for s in values.clone() {
-            if scale <= 0 {
+            if scale == 0 {
                 check_values.push(s.clone());
             } else if !s.contains(".") {
                 check_values.push(s + "." + &n_digit_str('0', scale));
@@ -199,7 +199,7 @@ mod test {
             tb
         )];
         let values = vec!["'sponge'", "'{}'", "'bob'"];
-        let insert_sql = Mock::insert_sql(&vec![values]);
+        let insert_sql = Mock::insert_sql(&[values]);
 
         runner.execute_sqls_and_get_binlogs(&prepare_sqls, &vec![insert_sql]);
 
@@ -221,7 +221,7 @@ mod test {
     #[serial]
     fn test_null() {
         let origin_values = vec!["null"];
-        let runner = run_json_test(&origin_values, &vec![], false);
+        let runner = run_json_test(&origin_values, &[], false);
         assert_eq!(
             runner.insert_events[0].rows[0].column_values[0],
             ColumnValue::None
@@ -497,19 +497,14 @@ mod test {
         ];
         run_json_test(
             &origin_values,
-            &vec!["212765.700000000010000", "111111.11111110000001"],
+            &["212765.700000000010000", "111111.11111110000001"],
             false,
         );
     }
 
-    fn run_json_test(
-        origin_values: &Vec<&str>,
-        expected_values: &Vec<&str>,
-        quote: bool,
-    ) -> TestRunner {
+    fn run_json_test(origin_values: &[&str], expected_values: &[&str], quote: bool) -> TestRunner {
         let values = origin_values
-            .clone()
-            .into_iter()
+            .iter()
             .map(|v| {
                 if quote {
                     format!("('{}')", v)
@@ -524,7 +519,7 @@ mod test {
             str_values.push(i.as_str());
         }
 
-        let runner = TestRunner::run_one_col_test("JSON", &str_values, &vec![]);
+        let runner = TestRunner::run_one_col_test("JSON", &str_values, &[]);
 
         if !expected_values.is_empty() {
             let mut binlog_values = Vec::new();
@@ -534,12 +529,12 @@ mod test {
                 {
                     binlog_values.push(json_string);
                 } else {
-                    assert!(false, "expect json");
+                    panic!("expect json");
                 }
             }
 
             for i in 0..origin_values.len() {
-                Assert::assert_json_string_eq(&expected_values[i], &binlog_values[i]);
+                Assert::assert_json_string_eq(expected_values[i], &binlog_values[i]);
             }
         }
 
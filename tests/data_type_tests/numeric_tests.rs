@@ -11,7 +11,7 @@ mod test {
     #[test]
     #[serial]
     fn test_tinyint() {
-        let runner = run("TINYINT", &vec!["-128", "127"]);
+        let runner = run("TINYINT", &["-128", "127"]);
         assert_eq!(
             runner.insert_events[0].rows[0].column_values[0],
             ColumnValue::Tiny(-128)
@@ -25,7 +25,7 @@ mod test {
     #[test]
     #[serial]
     fn test_tinyint_unsigned() {
-        let runner = run("TINYINT UNSIGNED", &vec!["255", "127"]);
+        let runner = run("TINYINT UNSIGNED", &["255", "127"]);
         assert_eq!(
             runner.insert_events[0].rows[0].column_values[0],
             ColumnValue::Tiny(-1)
@@ -39,7 +39,7 @@ mod test {
     #[test]
     #[serial]
     fn test_smallint() {
-        let runner = run("SMALLINT", &vec!["-32768", "32767"]);
+        let runner = run("SMALLINT", &["-32768", "32767"]);
         assert_eq!(
             runner.insert_events[0].rows[0].column_values[0],
             ColumnValue::Short(-32768)
@@ -53,7 +53,7 @@ mod test {
     #[test]
     #[serial]
     fn test_smallint_unsigned() {
-        let runner = run("SMALLINT UNSIGNED", &vec!["65535", "32767"]);
+        let runner = run("SMALLINT UNSIGNED", &["65535", "32767"]);
         assert_eq!(
             runner.insert_events[0].rows[0].column_values[0],
             ColumnValue::Short(-1)
@@ -67,7 +67,7 @@ mod test {
     #[test]
     #[serial]
     fn test_mediumint() {
-        let runner = run("MEDIUMINT", &vec!["-8388608", "8388607"]);
+        let runner = run("MEDIUMINT", &["-8388608", "8388607"]);
         assert_eq!(
             runner.insert_events[0].rows[0].column_values[0],
             ColumnValue::Long(-8388608)
@@ -81,7 +81,7 @@ mod test {
     #[test]
     #[serial]
     fn test_mediumint_unsigned() {
-        let runner = run("MEDIUMINT UNSIGNED", &vec!["16777215", "8388607"]);
+        let runner = run("MEDIUMINT UNSIGNED", &["16777215", "8388607"]);
         assert_eq!(
             runner.insert_events[0].rows[0].column_values[0],
             ColumnValue::Long(-1)
@@ -95,7 +95,7 @@ mod test {
     #[test]
     #[serial]
     fn test_int() {
-        let runner = run("INT", &vec!["-2147483648", "2147483647"]);
+        let runner = run("INT", &["-2147483648", "2147483647"]);
         assert_eq!(
             runner.insert_events[0].rows[0].column_values[0],
             ColumnValue::Long(-2147483648)
@@ -109,7 +109,7 @@ mod test {
     #[test]
     #[serial]
     fn test_int_unsigned() {
-        let runner = run("INT UNSIGNED", &vec!["4294967295", "2147483647"]);
+        let runner = run("INT UNSIGNED", &["4294967295", "2147483647"]);
         assert_eq!(
             runner.insert_events[0].rows[0].column_values[0],
             ColumnValue::Long(-1)
@@ -123,10 +123,7 @@ mod test {
     #[test]
     #[serial]
     fn test_bigint() {
-        let runner = run(
-            "BIGINT",
-            &vec!["-9223372036854775808", "9223372036854775807"],
-        );
+        let runner = run("BIGINT", &["-9223372036854775808", "9223372036854775807"]);
         assert_eq!(
             runner.insert_events[0].rows[0].column_values[0],
             ColumnValue::LongLong(-9223372036854775808)
@@ -142,7 +139,7 @@ mod test {
     fn test_bigint_unsigned() {
         let runner = run(
             "BIGINT UNSIGNED",
-            &vec!["18446744073709551615", "9223372036854775807"],
+            &["18446744073709551615", "9223372036854775807"],
         );
         assert_eq!(
             runner.insert_events[0].rows[0].column_values[0],
@@ -157,24 +154,24 @@ mod test {
     #[test]
     #[serial]
     fn test_float() {
-        let runner = run("FLOAT(10,5)", &vec!["1234.12345"]);
+        let runner = run("FLOAT(10,5)", &["1234.12345"]);
         assert_eq!(
             runner.insert_events[0].rows[0].column_values[0],
-            ColumnValue::Float(1234.12345),
+            ColumnValue::Float(1_234.123_4),
         );
     }
 
     #[test]
     #[serial]
     fn test_double() {
-        let runner = run("DOUBLE(20, 10)", &vec!["1234567890.0123456789"]);
+        let runner = run("DOUBLE(20, 10)", &["1234567890.0123456789"]);
         assert_eq!(
             runner.insert_events[0].rows[0].column_values[0],
-            ColumnValue::Double(1234567890.0123456789)
+            ColumnValue::Double(1_234_567_890.012_345_8)
         );
     }
 
     fn run(col_type: &str, values: &[&str]) -> TestRunner {
-        TestRunner::run_one_col_test(col_type, values, &vec![])
+        TestRunner::run_one_col_test(col_type, values, &[])
     }
 }
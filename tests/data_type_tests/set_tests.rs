@@ -165,12 +165,12 @@ mod test {
         );
     }
 
-    fn run_and_check(col_type: &str, values: &Vec<&str>, check_values: &Vec<u64>) {
-        let runner = TestRunner::run_one_col_test(col_type, values, &vec![]);
-        for i in 0..check_values.len() {
+    fn run_and_check(col_type: &str, values: &[&str], check_values: &[u64]) {
+        let runner = TestRunner::run_one_col_test(col_type, values, &[]);
+        for (i, check_value) in check_values.iter().enumerate() {
             assert_eq!(
                 runner.insert_events[0].rows[i].column_values[0],
-                ColumnValue::Set(check_values[i]),
+                ColumnValue::Set(*check_value),
             );
         }
     }
@@ -47,11 +47,11 @@ mod test {
             test_utc_timestamp + 123400,
             test_utc_timestamp + 123450,
             test_utc_timestamp + 123456,
-            test_utc_timestamp + 000001,
-            test_utc_timestamp + 000012,
-            test_utc_timestamp + 000123,
-            test_utc_timestamp + 001234,
-            test_utc_timestamp + 012345,
+            test_utc_timestamp + 1,
+            test_utc_timestamp + 12,
+            test_utc_timestamp + 123,
+            test_utc_timestamp + 1234,
+            test_utc_timestamp + 12345,
         ];
 
         run_and_check(col_type, &values, &check_values);
@@ -125,13 +125,13 @@ mod test {
 
     fn run_and_check(col_type: &str, values: &[&str], check_values: &[i64]) {
         let runner =
-            TestRunner::run_one_col_test(col_type, values, &vec!["SET @@session.time_zone='UTC'"]);
+            TestRunner::run_one_col_test(col_type, values, &["SET @@session.time_zone='UTC'"]);
 
         assert_eq!(runner.insert_events[0].rows.len(), check_values.len());
-        for i in 0..check_values.len() {
+        for (i, check_value) in check_values.iter().enumerate() {
             assert_eq!(
                 runner.insert_events[0].rows[i].column_values[0],
-                ColumnValue::Timestamp(check_values[i]),
+                ColumnValue::Timestamp(*check_value),
             );
         }
     }
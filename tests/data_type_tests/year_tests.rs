@@ -8,13 +8,13 @@ mod test {
     #[test]
     #[serial]
     fn test_year() {
-        let runner = TestRunner::run_one_col_test("YEAR", &vec!["'1901'", "'2155'"], &vec![]);
+        let runner = TestRunner::run_one_col_test("YEAR", &["'1901'", "'2155'"], &[]);
         let check_values = [1901, 2155];
         assert_eq!(runner.insert_events[0].rows.len(), check_values.len());
-        for i in 0..check_values.len() {
+        for (i, check_value) in check_values.iter().enumerate() {
             assert_eq!(
                 runner.insert_events[0].rows[i].column_values[0],
-                ColumnValue::Year(check_values[i]),
+                ColumnValue::Year(*check_value),
             );
         }
     }
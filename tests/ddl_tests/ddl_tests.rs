@@ -57,12 +57,12 @@ mod test {
     }
 
     fn run_sql_and_check_binlog(prepare_sqls: &[&str], test_sqls: &[&str], with_quotes: bool) {
-        let prepare_sqls: Vec<String> = prepare_sqls.into_iter().map(|i| i.to_string()).collect();
-        let test_sqls: Vec<String> = test_sqls.into_iter().map(|i| i.to_string()).collect();
+        let prepare_sqls: Vec<String> = prepare_sqls.iter().map(|i| i.to_string()).collect();
+        let test_sqls: Vec<String> = test_sqls.iter().map(|i| i.to_string()).collect();
 
         let mut runner = TestRunner::new();
         runner.execute_sqls_and_get_binlogs(&prepare_sqls, &test_sqls);
-        for i in 0..test_sqls.len() {
+        for (i, test_sql) in test_sqls.iter().enumerate() {
             // since comments may be added to ddl sql in binlog, here we check contain instead of equal.
             // example: the binlog for "drop table db_test_ddl.tb_test_ddl_2" may be
             // "drop table db_test_ddl.tb_test_ddl_2 /* generated by server */"
@@ -70,13 +70,13 @@ mod test {
                 assert!(runner.query_events[i]
                     .query
                     .to_lowercase()
-                    .contains(&test_sqls[i].to_lowercase()));
+                    .contains(&test_sql.to_lowercase()));
             } else {
                 assert!(runner.query_events[i]
                     .query
                     .to_lowercase()
                     .replace("`", "")
-                    .contains(&test_sqls[i].to_lowercase()));
+                    .contains(&test_sql.to_lowercase()));
             }
         }
     }
@@ -1 +0,0 @@
-mod ddl_tests;
@@ -4,7 +4,11 @@ mod test {
     use mysql_binlog_connector_rust::column::column_value::ColumnValue;
     use serial_test::serial;
 
-    use crate::runner::{assert::test::Assert, mock::test::Mock, test_runner::test::TestRunner};
+    use crate::runner::{
+        assert::test::Assert,
+        mock::test::{DefaultValues, Mock},
+        test_runner::test::TestRunner,
+    };
 
     #[test]
     #[serial]
@@ -61,171 +65,181 @@ mod test {
         // row 0, before
         Mock::default_check_values(
             &runner.update_events[0].rows[0].0,
-            0,
-            1,
-            2,
-            3,
-            4,
-            5,
-            "123456.1234".to_string(),
-            1234.12,
-            12345.123,
-            3,
-            "2022-01-02 03:04:05.123456".to_string(),
-            "03:04:05.123456".to_string(),
-            "2022-01-02".to_string(),
-            2022,
-            1641092645123456,
-            vec![97u8, 98],
-            vec![99u8, 100],
-            vec![101u8, 102],
-            vec![103u8, 104],
-            vec![105u8, 106],
-            vec![107u8, 108],
-            vec![109u8, 110],
-            vec![111u8, 112],
-            vec![113u8, 114],
-            vec![115u8, 116],
-            vec![117u8, 118],
-            vec![119u8, 120],
-            1,
-            1,
+            DefaultValues {
+                pk: 0,
+                f_1: 1,
+                f_2: 2,
+                f_3: 3,
+                f_4: 4,
+                f_5: 5,
+                f_6: "123456.1234".to_string(),
+                f_7: 1234.12,
+                f_8: 12345.123,
+                f_9: 3,
+                f_10: "2022-01-02 03:04:05.123456".to_string(),
+                f_11: "03:04:05.123456".to_string(),
+                f_12: "2022-01-02".to_string(),
+                f_13: 2022,
+                f_14: 1641092645123456,
+                f_15: vec![97u8, 98],
+                f_16: vec![99u8, 100],
+                f_17: vec![101u8, 102],
+                f_18: vec![103u8, 104],
+                f_19: vec![105u8, 106],
+                f_20: vec![107u8, 108],
+                f_21: vec![109u8, 110],
+                f_22: vec![111u8, 112],
+                f_23: vec![113u8, 114],
+                f_24: vec![115u8, 116],
+                f_25: vec![117u8, 118],
+                f_26: vec![119u8, 120],
+                f_27: 1,
+                f_28: 1,
+            },
         );
 
         // row 0, after
         Mock::default_check_values(
             &runner.update_events[0].rows[0].1,
-            0,
-            10,
-            20,
-            30,
-            40,
-            50,
-            "654321.4321".to_string(),
-            4321.21,
-            54321.321,
-            4,
-            "2021-02-01 04:05:06.654321".to_string(),
-            "04:05:06.654321".to_string(),
-            "2012-02-01".to_string(),
-            2021,
-            1612152306654321,
-            vec![49u8],
-            vec![50u8],
-            vec![51u8],
-            vec![52u8],
-            vec![53u8],
-            vec![54u8],
-            vec![55u8],
-            vec![56u8],
-            vec![57u8],
-            vec![49u8, 48],
-            vec![49u8, 49],
-            vec![49u8, 50],
-            2,
-            2,
+            DefaultValues {
+                pk: 0,
+                f_1: 10,
+                f_2: 20,
+                f_3: 30,
+                f_4: 40,
+                f_5: 50,
+                f_6: "654321.4321".to_string(),
+                f_7: 4321.21,
+                f_8: 54321.321,
+                f_9: 4,
+                f_10: "2021-02-01 04:05:06.654321".to_string(),
+                f_11: "04:05:06.654321".to_string(),
+                f_12: "2012-02-01".to_string(),
+                f_13: 2021,
+                f_14: 1612152306654321,
+                f_15: vec![49u8],
+                f_16: vec![50u8],
+                f_17: vec![51u8],
+                f_18: vec![52u8],
+                f_19: vec![53u8],
+                f_20: vec![54u8],
+                f_21: vec![55u8],
+                f_22: vec![56u8],
+                f_23: vec![57u8],
+                f_24: vec![49u8, 48],
+                f_25: vec![49u8, 49],
+                f_26: vec![49u8, 50],
+                f_27: 2,
+                f_28: 2,
+            },
         );
 
         // row 1, before
         Mock::default_check_values(
             &runner.update_events[1].rows[0].0,
-            1,
-            10,
-            20,
-            30,
-            40,
-            50,
-            "654321.4321".to_string(),
-            4321.21,
-            54321.321,
-            4,
-            "2021-02-01 04:05:06.654321".to_string(),
-            "04:05:06.654321".to_string(),
-            "2012-02-01".to_string(),
-            2021,
-            1612152306654321,
-            vec![49u8],
-            vec![50u8],
-            vec![51u8],
-            vec![52u8],
-            vec![53u8],
-            vec![54u8],
-            vec![55u8],
-            vec![56u8],
-            vec![57u8],
-            vec![49u8, 48],
-            vec![49u8, 49],
-            vec![49u8, 50],
-            2,
-            2,
+            DefaultValues {
+                pk: 1,
+                f_1: 10,
+                f_2: 20,
+                f_3: 30,
+                f_4: 40,
+                f_5: 50,
+                f_6: "654321.4321".to_string(),
+                f_7: 4321.21,
+                f_8: 54321.321,
+                f_9: 4,
+                f_10: "2021-02-01 04:05:06.654321".to_string(),
+                f_11: "04:05:06.654321".to_string(),
+                f_12: "2012-02-01".to_string(),
+                f_13: 2021,
+                f_14: 1612152306654321,
+                f_15: vec![49u8],
+                f_16: vec![50u8],
+                f_17: vec![51u8],
+                f_18: vec![52u8],
+                f_19: vec![53u8],
+                f_20: vec![54u8],
+                f_21: vec![55u8],
+                f_22: vec![56u8],
+                f_23: vec![57u8],
+                f_24: vec![49u8, 48],
+                f_25: vec![49u8, 49],
+                f_26: vec![49u8, 50],
+                f_27: 2,
+                f_28: 2,
+            },
         );
 
         // row 1, after
         Mock::default_check_values(
             &runner.update_events[1].rows[0].1,
-            1,
-            6,
-            7,
-            8,
-            9,
-            10,
-            "234561.2341".to_string(),
-            2341.12,
-            23451.231,
-            5,
-            "2020-03-04 05:06:07.234561".to_string(),
-            "05:06:07.234561".to_string(),
-            "2022-05-06".to_string(),
-            2020,
-            1583298367234561,
-            vec![97u8],
-            vec![98u8],
-            vec![99u8],
-            vec![100u8],
-            vec![101u8],
-            vec![102u8],
-            vec![103u8],
-            vec![104u8],
-            vec![105u8],
-            vec![106u8],
-            vec![107u8],
-            vec![108u8],
-            3,
-            4,
+            DefaultValues {
+                pk: 1,
+                f_1: 6,
+                f_2: 7,
+                f_3: 8,
+                f_4: 9,
+                f_5: 10,
+                f_6: "234561.2341".to_string(),
+                f_7: 2341.12,
+                f_8: 23451.231,
+                f_9: 5,
+                f_10: "2020-03-04 05:06:07.234561".to_string(),
+                f_11: "05:06:07.234561".to_string(),
+                f_12: "2022-05-06".to_string(),
+                f_13: 2020,
+                f_14: 1583298367234561,
+                f_15: vec![97u8],
+                f_16: vec![98u8],
+                f_17: vec![99u8],
+                f_18: vec![100u8],
+                f_19: vec![101u8],
+                f_20: vec![102u8],
+                f_21: vec![103u8],
+                f_22: vec![104u8],
+                f_23: vec![105u8],
+                f_24: vec![106u8],
+                f_25: vec![107u8],
+                f_26: vec![108u8],
+                f_27: 3,
+                f_28: 4,
+            },
         );
 
         // row 2, before
         Mock::default_check_values(
             &runner.update_events[2].rows[0].0,
-            2,
-            6,
-            7,
-            8,
-            9,
-            10,
-            "234561.2341".to_string(),
-            2341.12,
-            23451.231,
-            5,
-            "2020-03-04 05:06:07.234561".to_string(),
-            "05:06:07.234561".to_string(),
-            "2022-05-06".to_string(),
-            2020,
-            1583298367234561,
-            vec![97u8],
-            vec![98u8],
-            vec![99u8],
-            vec![100u8],
-            vec![101u8],
-            vec![102u8],
-            vec![103u8],
-            vec![104u8],
-            vec![105u8],
-            vec![106u8],
-            vec![107u8],
-            vec![108u8],
-            3,
-            4,
+            DefaultValues {
+                pk: 2,
+                f_1: 6,
+                f_2: 7,
+                f_3: 8,
+                f_4: 9,
+                f_5: 10,
+                f_6: "234561.2341".to_string(),
+                f_7: 2341.12,
+                f_8: 23451.231,
+                f_9: 5,
+                f_10: "2020-03-04 05:06:07.234561".to_string(),
+                f_11: "05:06:07.234561".to_string(),
+                f_12: "2022-05-06".to_string(),
+                f_13: 2020,
+                f_14: 1583298367234561,
+                f_15: vec![97u8],
+                f_16: vec![98u8],
+                f_17: vec![99u8],
+                f_18: vec![100u8],
+                f_19: vec![101u8],
+                f_20: vec![102u8],
+                f_21: vec![103u8],
+                f_22: vec![104u8],
+                f_23: vec![105u8],
+                f_24: vec![106u8],
+                f_25: vec![107u8],
+                f_26: vec![108u8],
+                f_27: 3,
+                f_28: 4,
+            },
         );
 
         // row 2, after
@@ -239,20 +253,20 @@ mod test {
         }
         // non-Null fields
         let row_2_after = &runner.update_events[2].rows[0].1.column_values;
-        Assert::assert_numeric_eq(&row_2_after[1], 11 as i128);
-        Assert::assert_numeric_eq(&row_2_after[3], 3 as i128);
-        Assert::assert_numeric_eq(&row_2_after[5], 5 as i128);
+        Assert::assert_numeric_eq(&row_2_after[1], 11_i128);
+        Assert::assert_numeric_eq(&row_2_after[3], 3_i128);
+        Assert::assert_numeric_eq(&row_2_after[5], 5_i128);
         Assert::assert_float_eq(&row_2_after[7], 1234.12);
-        Assert::assert_numeric_eq(&row_2_after[9], 3 as i128);
+        Assert::assert_numeric_eq(&row_2_after[9], 3_i128);
         Assert::assert_string_eq(&row_2_after[11], "03:04:05.123456".to_string());
-        Assert::assert_numeric_eq(&row_2_after[13], 2022 as i128);
+        Assert::assert_numeric_eq(&row_2_after[13], 2022_i128);
         Assert::assert_bytes_eq(&row_2_after[15], vec![97u8, 98]);
         Assert::assert_bytes_eq(&row_2_after[17], vec![101u8, 102]);
         Assert::assert_bytes_eq(&row_2_after[19], vec![105u8, 106]);
         Assert::assert_bytes_eq(&row_2_after[21], vec![109u8, 110]);
         Assert::assert_bytes_eq(&row_2_after[23], vec![113u8, 114]);
         Assert::assert_bytes_eq(&row_2_after[25], vec![117u8, 118]);
-        Assert::assert_unsigned_numeric_eq(&row_2_after[27], 1 as u64);
+        Assert::assert_unsigned_numeric_eq(&row_2_after[27], 1_u64);
 
         // row 3, before
         Assert::assert_numeric_eq(&runner.update_events[3].rows[0].0.column_values[0], 3);
@@ -265,20 +279,20 @@ mod test {
         }
         // non-Null fields
         let row_3_before = &runner.update_events[3].rows[0].0.column_values;
-        Assert::assert_numeric_eq(&row_3_before[1], 11 as i128);
-        Assert::assert_numeric_eq(&row_3_before[3], 3 as i128);
-        Assert::assert_numeric_eq(&row_3_before[5], 5 as i128);
+        Assert::assert_numeric_eq(&row_3_before[1], 11_i128);
+        Assert::assert_numeric_eq(&row_3_before[3], 3_i128);
+        Assert::assert_numeric_eq(&row_3_before[5], 5_i128);
         Assert::assert_float_eq(&row_3_before[7], 1234.12);
-        Assert::assert_numeric_eq(&row_3_before[9], 3 as i128);
+        Assert::assert_numeric_eq(&row_3_before[9], 3_i128);
         Assert::assert_string_eq(&row_3_before[11], "03:04:05.123456".to_string());
-        Assert::assert_numeric_eq(&row_3_before[13], 2022 as i128);
+        Assert::assert_numeric_eq(&row_3_before[13], 2022_i128);
         Assert::assert_bytes_eq(&row_3_before[15], vec![97u8, 98]);
         Assert::assert_bytes_eq(&row_3_before[17], vec![101u8, 102]);
         Assert::assert_bytes_eq(&row_3_before[19], vec![105u8, 106]);
         Assert::assert_bytes_eq(&row_3_before[21], vec![109u8, 110]);
         Assert::assert_bytes_eq(&row_3_before[23], vec![113u8, 114]);
         Assert::assert_bytes_eq(&row_3_before[25], vec![117u8, 118]);
-        Assert::assert_unsigned_numeric_eq(&row_3_before[27], 1 as u64);
+        Assert::assert_unsigned_numeric_eq(&row_3_before[27], 1_u64);
 
         // row 3, after
         for i in 1..28 {
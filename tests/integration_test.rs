@@ -1,5 +1,7 @@
 mod data_type_tests;
+#[path = "ddl_tests/ddl_tests.rs"]
 mod ddl_tests;
 mod dml_tests;
+#[path = "parse_file_tests/parse_file_tests.rs"]
 mod parse_file_tests;
 mod runner;
@@ -0,0 +1,67 @@
+#!/usr/bin/env bash
+# Captures a binlog encrypted with binlog_encryption=ON and the keyring holding its master key,
+# for test_captured_encrypted_binlog in parse_file_tests.rs:
+#
+#   tests/parse_file_tests/capture_encrypted_binlog.sh [output_dir]
+#
+# Requires docker. The server loads component_keyring_file, whose data file is the JSON
+# format read by Keyring::from_file. The encrypted binlog is written to
+# <output_dir>/mysql-bin.000081 and the keyring to <output_dir>/mysql-bin.000081.keyring
+# (default: the directory of this script), test_captured_encrypted_binlog can be un-ignored
+# afterwards.
+set -euo pipefail
+
+IMAGE="${IMAGE:-mysql:8.0.36}"
+CONTAINER="binlog-capture-encrypted"
+OUTPUT_DIR="${1:-$(cd "$(dirname "$0")" && pwd)}"
+CONFIG_DIR=$(mktemp -d)
+
+cleanup() {
+    docker rm -f "$CONTAINER" >/dev/null 2>&1 || true
+    rm -rf "$CONFIG_DIR"
+}
+trap cleanup EXIT
+
+# the global manifest and the config of component_keyring_file
+echo '{"components": "file://component_keyring_file"}' >"$CONFIG_DIR/mysqld.my"
+echo '{"path": "/var/lib/mysql/component_keyring_file", "read_only": false}' \
+    >"$CONFIG_DIR/component_keyring_file.cnf"
+
+docker run -d --name "$CONTAINER" \
+    -e MYSQL_ROOT_PASSWORD="123456" \
+    -v "$CONFIG_DIR/mysqld.my:/usr/sbin/mysqld.my:ro" \
+    -v "$CONFIG_DIR/component_keyring_file.cnf:/usr/lib64/mysql/plugin/component_keyring_file.cnf:ro" \
+    "$IMAGE" \
+    --server_id=1 \
+    --log_bin=/var/lib/mysql/mysql-bin \
+    --gtid_mode=ON \
+    --enforce_gtid_consistency=ON \
+    --binlog_format=ROW \
+    --binlog_row_image=FULL \
+    --binlog_checksum=CRC32 \
+    --binlog_encryption=ON >/dev/null
+
+mysql_exec() {
+    docker exec -i "$CONTAINER" mysql -uroot -p123456 --silent --skip-column-names "$@" 2>/dev/null
+}
+
+until mysql_exec -e "SELECT 1" >/dev/null; do
+    sleep 1
+done
+
+mysql_exec <<'SQL'
+CREATE DATABASE test_db;
+CREATE TABLE test_db.encrypted_table (id INT PRIMARY KEY, name VARCHAR(64));
+FLUSH BINARY LOGS;
+INSERT INTO test_db.encrypted_table VALUES (1, 'a');
+INSERT INTO test_db.encrypted_table VALUES (2, 'b');
+INSERT INTO test_db.encrypted_table VALUES (3, 'c');
+SQL
+
+# the inserts are in the current binlog, rotate so it is complete
+BINLOG=$(mysql_exec -e "SHOW MASTER STATUS" | awk '{print $1}')
+mysql_exec -e "FLUSH BINARY LOGS"
+
+docker cp "$CONTAINER:/var/lib/mysql/$BINLOG" "$OUTPUT_DIR/mysql-bin.000081"
+docker cp "$CONTAINER:/var/lib/mysql/component_keyring_file" "$OUTPUT_DIR/mysql-bin.000081.keyring"
+echo "captured $BINLOG of $IMAGE into $OUTPUT_DIR/mysql-bin.000081(.keyring)"
@@ -0,0 +1,60 @@
+#!/usr/bin/env bash
+# Captures a MySQL 9 binlog with a VECTOR column and binlog_row_metadata=FULL, to replace the
+# hand-built mysql-bin.000090 fixture:
+#
+#   tests/parse_file_tests/capture_mysql9_binlog.sh [output_dir]
+#
+# Requires docker. The binlog is written to <output_dir>/mysql-bin.000090 (default: the
+# directory of this script) and the output of `mysqlbinlog --verbose --base64-output=DECODE-ROWS`
+# on it, in UTC, to <output_dir>/mysql-bin.000090.mysqlbinlog. The assertions of the
+# *_90_vector_binlog tests in parse_file_tests.rs need to be updated to the captured events
+# afterwards, and test_mysqlbinlog_90_vector_binlog_diff un-ignored.
+set -euo pipefail
+
+IMAGE="${IMAGE:-mysql:9.0.1}"
+CONTAINER="binlog-capture-mysql9"
+OUTPUT_DIR="${1:-$(cd "$(dirname "$0")" && pwd)}"
+
+cleanup() {
+    docker rm -f "$CONTAINER" >/dev/null 2>&1 || true
+}
+trap cleanup EXIT
+
+docker run -d --name "$CONTAINER" \
+    -e MYSQL_ROOT_PASSWORD="123456" \
+    "$IMAGE" \
+    --server_id=1 \
+    --log_bin=/var/lib/mysql/mysql-bin \
+    --gtid_mode=ON \
+    --enforce_gtid_consistency=ON \
+    --binlog_format=ROW \
+    --binlog_row_image=FULL \
+    --binlog_row_metadata=FULL \
+    --binlog_checksum=CRC32 >/dev/null
+
+mysql_exec() {
+    docker exec -i "$CONTAINER" mysql -uroot -p123456 --silent --skip-column-names "$@" 2>/dev/null
+}
+
+until mysql_exec -e "SELECT 1" >/dev/null; do
+    sleep 1
+done
+
+mysql_exec <<'SQL'
+CREATE DATABASE test_db;
+CREATE TABLE test_db.vector_table (id INT PRIMARY KEY, v VECTOR(3));
+FLUSH BINARY LOGS;
+INSERT INTO test_db.vector_table VALUES (1, STRING_TO_VECTOR('[1,2,3]'));
+INSERT INTO test_db.vector_table VALUES (2, STRING_TO_VECTOR('[-0.5,0,3.25]'));
+INSERT INTO test_db.vector_table VALUES (3, NULL);
+SQL
+
+# the inserts are in the current binlog, rotate so it is complete
+BINLOG=$(mysql_exec -e "SHOW BINARY LOG STATUS" | awk '{print $1}')
+mysql_exec -e "FLUSH BINARY LOGS"
+
+docker cp "$CONTAINER:/var/lib/mysql/$BINLOG" "$OUTPUT_DIR/mysql-bin.000090"
+docker exec -e TZ=UTC "$CONTAINER" \
+    mysqlbinlog --verbose --base64-output=DECODE-ROWS "/var/lib/mysql/$BINLOG" \
+    >"$OUTPUT_DIR/mysql-bin.000090.mysqlbinlog"
+echo "captured $BINLOG of $IMAGE into $OUTPUT_DIR/mysql-bin.000090(.mysqlbinlog)"
@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+# Captures the output of the reference mysqlbinlog on the mysql-bin.000057 and mysql-bin.000080
+# fixtures, which test_mysqlbinlog_*_binlog_diff in parse_file_tests.rs diff MysqlbinlogRenderer
+# against:
+#
+#   tests/parse_file_tests/capture_mysqlbinlog_output.sh [output_dir]
+#
+# Requires docker. The output of `mysqlbinlog --verbose --base64-output=DECODE-ROWS` on each
+# fixture, in UTC, is written to <output_dir>/mysql-bin.0000NN.mysqlbinlog (default: the
+# directory of this script). The diff tests are un-ignored afterwards.
+set -euo pipefail
+
+IMAGE="${IMAGE:-mysql:8.0.31}"
+FIXTURE_DIR="$(cd "$(dirname "$0")" && pwd)"
+OUTPUT_DIR="${1:-$FIXTURE_DIR}"
+
+for BINLOG in mysql-bin.000057 mysql-bin.000080; do
+    docker run --rm -e TZ=UTC -v "$FIXTURE_DIR:/fixtures:ro" --entrypoint mysqlbinlog "$IMAGE" \
+        --verbose --base64-output=DECODE-ROWS "/fixtures/$BINLOG" \
+        >"$OUTPUT_DIR/$BINLOG.mysqlbinlog"
+    echo "captured $OUTPUT_DIR/$BINLOG.mysqlbinlog with $IMAGE"
+done
@@ -1 +0,0 @@
-mod parse_file_tests;
@@ -1,8 +1,50 @@
 #[cfg(test)]
 mod test {
-    use std::{collections::HashMap, env, fs::File};
+    use std::{collections::HashMap, env, fs::File, io::Read, time::Duration};
 
-    use mysql_binlog_connector_rust::{binlog_error::BinlogError, binlog_parser::BinlogParser};
+    use mysql_binlog_connector_rust::{
+        binlog_async_reader::BinlogAsyncReader,
+        binlog_bounded_reader::BinlogBoundedReader,
+        binlog_client::StartPosition,
+        binlog_directory_reader::BinlogDirectoryReader,
+        binlog_encoder::BinlogEncoder,
+        binlog_error::BinlogError,
+        binlog_file::BinlogFile,
+        binlog_mirror::BinlogMirror,
+        binlog_offset_index::BinlogOffsetIndex,
+        binlog_parser::BinlogParser,
+        binlog_recovery_reader::BinlogRecoveryReader,
+        binlog_writer::BinlogWriter,
+        change::{
+            change_event::ChangeEvent, change_tracker::ChangeTracker,
+            transaction::TransactionBuffer,
+        },
+        column::{column_type::ColumnType, column_value::ColumnValue},
+        command::gtid_set::GtidSet,
+        event::{
+            checksum_type::ChecksumType, event_data::EventData, event_data_ref::EventDataRef,
+            event_header::EventHeader, rotate_event::RotateEvent, row::Row, row_event::RowEvent,
+        },
+        output::{
+            canal::CanalConverter, debezium::DebeziumConverter, maxwell::MaxwellConverter,
+            mysqlbinlog::MysqlbinlogRenderer, sql::SqlGenerator,
+        },
+    };
+    use serde_json::json;
+
+    #[cfg(feature = "encryption")]
+    use mysql_binlog_connector_rust::binlog_encryption::{EncryptedBinlogReader, Keyring};
+    #[cfg(all(feature = "gzip", feature = "xz"))]
+    use mysql_binlog_connector_rust::binlog_file::Compression;
+    #[cfg(feature = "mmap")]
+    use mysql_binlog_connector_rust::binlog_mmap_parser::BinlogMmapParser;
+    #[cfg(all(feature = "gzip", feature = "xz"))]
+    use std::io::{Seek, SeekFrom};
+    #[cfg(feature = "mmap")]
+    use std::{
+        ops::Range,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
 
     #[test]
     fn test_bad_magic() {
@@ -19,8 +61,8 @@ mod test {
                 assert_eq!(error, "bad magic")
             }
 
-            _ => {
-                assert!(false)
+            error => {
+                panic!("unexpected error: {:?}", error)
             }
         }
     }
@@ -35,15 +77,1151 @@ mod test {
         test_parse_binlog("/mysql-bin.000080", 37);
     }
 
+    // mysql-bin.000090 is hand-built in the MySQL 9.0.1 format, not captured from a server,
+    // capture_mysql9_binlog.sh captures the same statements from a MySQL 9 server to replace it
+    #[test]
+    fn test_parse_90_vector_binlog() {
+        test_parse_binlog("/mysql-bin.000090", 6);
+
+        let mut file = open_file("/mysql-bin.000090");
+        let mut parser = BinlogParser {
+            checksum_length: 4,
+            table_map_event_by_table_id: HashMap::new(),
+        };
+        assert!(parser.check_magic(&mut file).is_ok());
+
+        let mut rows = Vec::new();
+        let mut table_map_event = None;
+        while let Ok((_, data)) = parser.next(&mut file) {
+            match data {
+                EventData::TableMap(event) => table_map_event = Some(event),
+                EventData::WriteRows(event) => rows.extend(event.rows),
+                _ => {}
+            }
+        }
+
+        let table_map_event = table_map_event.unwrap();
+        assert_eq!(table_map_event.get_column_type(1), ColumnType::Vector);
+        assert_eq!(
+            table_map_event
+                .get_column_metadata(1)
+                .unwrap()
+                .vector_dimensionality,
+            Some(3)
+        );
+
+        let values: Vec<&ColumnValue> = rows.iter().map(|row| &row.column_values[1]).collect();
+        assert_eq!(
+            values,
+            vec![
+                &ColumnValue::Vector(vec![1.0, 2.0, 3.0]),
+                &ColumnValue::Vector(vec![-0.5, 0.0, 3.25]),
+                &ColumnValue::None,
+            ]
+        );
+
+        let row = Row::new(&rows[0], &table_map_event);
+        assert_eq!(row.get("id"), Some(&ColumnValue::Long(1)));
+        assert_eq!(
+            row.get("v"),
+            Some(&ColumnValue::Vector(vec![1.0, 2.0, 3.0]))
+        );
+    }
+
+    #[test]
+    fn test_debezium_90_vector_binlog() {
+        let mut file = open_file("/mysql-bin.000090");
+        let mut parser = BinlogParser {
+            checksum_length: 4,
+            table_map_event_by_table_id: HashMap::new(),
+        };
+        assert!(parser.check_magic(&mut file).is_ok());
+
+        let mut tracker = ChangeTracker::new("mysql-bin.000090");
+        let mut changes = Vec::new();
+        while let Ok((header, data)) = parser.next(&mut file) {
+            changes.extend(tracker.handle(&header, data));
+        }
+        assert!(matches!(changes.first(), Some(ChangeEvent::Schema(_))));
+        assert!(matches!(
+            changes.last(),
+            Some(ChangeEvent::Commit { xid: Some(42), .. })
+        ));
+
+        let converter = DebeziumConverter::new("server_1");
+        let records: Vec<_> = changes
+            .iter()
+            .filter_map(|change| converter.convert(change).unwrap())
+            .collect();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].value["databaseName"], "test_db");
+
+        let record = &records[1];
+        assert_eq!(record.topic, "server_1.test_db.vector_table");
+        assert_eq!(record.key, Some(json!({"id": 1})));
+        assert_eq!(record.value["op"], "c");
+        assert_eq!(
+            record.value["after"],
+            json!({"id": 1, "v": [1.0, 2.0, 3.0]})
+        );
+        assert_eq!(record.value["source"]["file"], "mysql-bin.000090");
+        assert_eq!(records[3].value["after"], json!({"id": 3, "v": null}));
+    }
+
+    #[test]
+    fn test_canal_and_maxwell_90_vector_binlog() {
+        let mut file = open_file("/mysql-bin.000090");
+        let mut parser = BinlogParser {
+            checksum_length: 4,
+            table_map_event_by_table_id: HashMap::new(),
+        };
+        assert!(parser.check_magic(&mut file).is_ok());
+
+        let mut tracker = ChangeTracker::new("mysql-bin.000090");
+        let mut buffer = TransactionBuffer::new();
+        let mut transactions = Vec::new();
+        while let Ok((header, data)) = parser.next(&mut file) {
+            for change in tracker.handle(&header, data) {
+                transactions.extend(buffer.push(change));
+            }
+        }
+        // CREATE TABLE and the insert transaction
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[1].xid, Some(42));
+
+        let mut canal = CanalConverter::new();
+        let messages = canal.convert_transaction(&transactions[1]).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].table, "vector_table");
+        assert_eq!(messages[0].pk_names, Some(vec!["id".to_string()]));
+        assert_eq!(
+            serde_json::to_value(&messages[0].data).unwrap(),
+            json!([
+                {"id": "1", "v": "[1.0,2.0,3.0]"},
+                {"id": "2", "v": "[-0.5,0.0,3.25]"},
+                {"id": "3", "v": null},
+            ])
+        );
+        assert_eq!(
+            serde_json::to_value(&messages[0].mysql_type).unwrap(),
+            json!({"id": "int", "v": "vector(3)"})
+        );
+
+        let maxwell = MaxwellConverter::new();
+        let records = maxwell.convert_transaction(&transactions[1]).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            serde_json::to_string(&records[2]).unwrap(),
+            r#"{"database":"test_db","table":"vector_table","type":"insert","ts":1718000000,"xid":42,"commit":true,"data":{"id":3,"v":null}}"#
+        );
+    }
+
+    #[test]
+    fn test_sql_90_vector_binlog() {
+        let mut file = open_file("/mysql-bin.000090");
+        let mut parser = BinlogParser {
+            checksum_length: 4,
+            table_map_event_by_table_id: HashMap::new(),
+        };
+        assert!(parser.check_magic(&mut file).is_ok());
+
+        let mut tracker = ChangeTracker::new("mysql-bin.000090");
+        let generator = SqlGenerator::new();
+        let mut statements = Vec::new();
+        while let Ok((header, data)) = parser.next(&mut file) {
+            for change in tracker.handle(&header, data) {
+                if let ChangeEvent::Row(change) = change {
+                    statements.extend(generator.generate(&change).unwrap());
+                }
+            }
+        }
+        assert_eq!(
+            statements,
+            vec![
+                "INSERT INTO `test_db`.`vector_table`(`id`, `v`) VALUES (1, STRING_TO_VECTOR('[1,2,3]'));",
+                "INSERT INTO `test_db`.`vector_table`(`id`, `v`) VALUES (2, STRING_TO_VECTOR('[-0.5,0,3.25]'));",
+                "INSERT INTO `test_db`.`vector_table`(`id`, `v`) VALUES (3, NULL);",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mysqlbinlog_90_vector_binlog() {
+        let mut file = open_file("/mysql-bin.000090");
+        let mut out = Vec::new();
+        MysqlbinlogRenderer::new()
+            .render(&mut file, &mut out)
+            .unwrap();
+        // vector values are printed as raw bytes
+        let out = String::from_utf8_lossy(&out);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[4], "DELIMITER /*!*/;");
+        assert_eq!(lines[5], "# at 4");
+        assert_eq!(
+            lines[6],
+            "#240610  6:13:20 server id 1  end_log_pos 123 CRC32 0xaa784a4f \tStart: binlog v 4, server v 9.0.1 created 240610  6:13:20 at startup"
+        );
+        assert!(out.contains(
+            "#240610  6:13:20 server id 1  end_log_pos 433 CRC32 0x93faaba7 \tWrite_rows: table id 109 flags: STMT_END_F\n\
+             ### INSERT INTO `test_db`.`vector_table`\n\
+             ### SET\n\
+             ###   @1=1\n"
+        ));
+        assert!(out.contains("###   @1=3\n###   @2=NULL\n# at 433\n"));
+        assert!(out.contains("\tXid = 42\nCOMMIT/*!*/;\nDELIMITER ;\n# End of log file\n"));
+    }
+
+    #[test]
+    fn test_mysqlbinlog_57_binlog() {
+        let mut file = open_file("/mysql-bin.000057");
+        let mut out = Vec::new();
+        MysqlbinlogRenderer::new()
+            .render(&mut file, &mut out)
+            .unwrap();
+        let out = String::from_utf8_lossy(&out);
+
+        // the session variables of the status block are printed for the first query
+        assert!(out.contains(
+            "#221120 13:52:37 server id 1  end_log_pos 378 CRC32 0x4bbacc30 \tQuery\tthread_id=8\texec_time=0\terror_code=0\n\
+             use `a`/*!*/;\n\
+             SET TIMESTAMP=1668952357/*!*/;\n\
+             SET @@session.pseudo_thread_id=8/*!*/;\n\
+             SET @@session.foreign_key_checks=1, @@session.sql_auto_is_null=0, @@session.unique_checks=1, @@session.autocommit=1/*!*/;\n\
+             SET @@session.sql_mode=1168113696/*!*/;\n\
+             SET @@session.auto_increment_increment=1, @@session.auto_increment_offset=1/*!*/;\n\
+             /*!\\C latin1 *//*!*/;\n\
+             SET @@session.character_set_client=8,@@session.collation_connection=8,@@session.collation_server=33/*!*/;\n\
+             SET @@session.lc_time_names=0/*!*/;\n\
+             SET @@session.collation_database=DEFAULT/*!*/;\n\
+             /*!80011 SET @@session.default_collation_for_utf8mb4=255*//*!*/;\n\
+             /*!80013 SET @@session.sql_require_primary_key=0*//*!*/;\n\
+             create table b(id int)\n"
+        ));
+        // and only the changed ones afterwards, the events of compressed transactions too
+        assert!(out.contains(
+            "#221120 13:52:38 server id 1  end_log_pos 0 \tQuery\tthread_id=8\texec_time=0\terror_code=0\n\
+             SET TIMESTAMP=1668952358/*!*/;\n\
+             BEGIN\n"
+        ));
+        assert!(out.contains(
+            "\tWrite_rows: table id 92 flags: STMT_END_F\n\
+             ### INSERT INTO `a`.`b`\n\
+             ### SET\n\
+             ###   @1=1\n"
+        ));
+    }
+
+    // the expected outputs are captured by capture_mysqlbinlog_output.sh and
+    // capture_mysql9_binlog.sh, with mysqlbinlog --verbose --base64-output=DECODE-ROWS in UTC
+    #[test]
+    #[ignore = "needs mysql-bin.000057.mysqlbinlog captured by capture_mysqlbinlog_output.sh"]
+    fn test_mysqlbinlog_57_binlog_diff() {
+        test_mysqlbinlog_diff("/mysql-bin.000057");
+    }
+
+    #[test]
+    #[ignore = "needs mysql-bin.000080.mysqlbinlog captured by capture_mysqlbinlog_output.sh"]
+    fn test_mysqlbinlog_80_binlog_diff() {
+        test_mysqlbinlog_diff("/mysql-bin.000080");
+    }
+
+    #[test]
+    #[ignore = "needs mysql-bin.000090.mysqlbinlog captured by capture_mysql9_binlog.sh"]
+    fn test_mysqlbinlog_90_vector_binlog_diff() {
+        test_mysqlbinlog_diff("/mysql-bin.000090");
+    }
+
+    fn test_mysqlbinlog_diff(file_name: &str) {
+        let expected =
+            std::fs::read(open_file_path(&format!("{}.mysqlbinlog", file_name))).unwrap();
+        let expected = String::from_utf8_lossy(&expected);
+        let expected: Vec<&str> = expected.lines().collect();
+
+        let mut file = open_file(file_name);
+        let mut out = Vec::new();
+        MysqlbinlogRenderer::new()
+            .render(&mut file, &mut out)
+            .unwrap();
+        let out = String::from_utf8_lossy(&out);
+        let lines: Vec<&str> = out.lines().collect();
+        for (i, (line, expected_line)) in lines.iter().zip(&expected).enumerate() {
+            assert_eq!(line, expected_line, "line {}", i + 1);
+        }
+        assert_eq!(lines.len(), expected.len());
+    }
+
+    #[test]
+    fn test_parse_90_vector_binlog_ref() {
+        test_parse_binlog_ref("/mysql-bin.000090");
+    }
+
+    #[test]
+    fn test_parse_80_binlog_ref() {
+        test_parse_binlog_ref("/mysql-bin.000080");
+    }
+
+    #[test]
+    fn test_encode_57_binlog() {
+        test_encode_binlog("/mysql-bin.000057");
+
+        // events in transaction payloads are compressed again if the payload is not kept
+        let mut events = parse_events("/mysql-bin.000057");
+        for (_, data) in events.iter_mut() {
+            if let EventData::TransactionPayload(event) = data {
+                event.payload.clear();
+            }
+        }
+        let buf = write_events(&events);
+        let mut parser = BinlogParser {
+            checksum_length: 0,
+            table_map_event_by_table_id: HashMap::new(),
+        };
+        let mut cursor = std::io::Cursor::new(buf);
+        parser.check_magic(&mut cursor).unwrap();
+        for (_, expected_data) in events.iter() {
+            let (_, data) = parser.next(&mut cursor).unwrap();
+            if let (EventData::TransactionPayload(event), EventData::TransactionPayload(expected)) =
+                (data, expected_data)
+            {
+                assert_eq!(event.uncompressed_size, expected.uncompressed_size);
+                assert_eq!(
+                    format!("{:?}", event.uncompressed_events),
+                    format!("{:?}", expected.uncompressed_events)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_80_binlog() {
+        test_encode_binlog("/mysql-bin.000080");
+    }
+
+    #[test]
+    fn test_encode_90_vector_binlog() {
+        test_encode_binlog("/mysql-bin.000090");
+    }
+
+    #[test]
+    fn test_mirror_80_binlog() {
+        let expected_buf = std::fs::read(open_file_path("/mysql-bin.000080")).unwrap();
+        let raw_events = split_events(&expected_buf);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mysql-bin.000080");
+
+        // the server sends an artificial RotateEvent, then the events of the file
+        let mut mirror = BinlogMirror::open(dir.path()).unwrap();
+        assert!(mirror.start_position().is_none());
+        mirror.checksum_length = 4;
+        mirror
+            .write(&fake_rotate_event("mysql-bin.000080", 4))
+            .unwrap();
+        for raw_event in &raw_events {
+            mirror.write(raw_event).unwrap();
+        }
+        mirror.sync().unwrap();
+        drop(mirror);
+        assert_eq!(std::fs::read(&path).unwrap(), expected_buf);
+
+        // a partial event left by a crash is truncated when reopened
+        let mut partial_buf = expected_buf.clone();
+        partial_buf.extend_from_slice(&raw_events[1][..10]);
+        std::fs::write(&path, &partial_buf).unwrap();
+        let mut mirror = BinlogMirror::open(dir.path()).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), expected_buf);
+        assert!(matches!(
+            mirror.start_position(),
+            Some(StartPosition::BinlogPosition(filename, position))
+                if filename == "mysql-bin.000080" && position as usize == expected_buf.len()
+        ));
+
+        // when resumed, the resent FormatDescriptionEvent is not written again
+        mirror.checksum_length = 4;
+        mirror
+            .write(&fake_rotate_event(
+                "mysql-bin.000080",
+                expected_buf.len() as u64,
+            ))
+            .unwrap();
+        mirror.write(&raw_events[0]).unwrap();
+        assert_eq!(
+            mirror.position(),
+            Some(("mysql-bin.000080", expected_buf.len() as u64))
+        );
+
+        // rotated to the next file
+        mirror
+            .write(&fake_rotate_event("mysql-bin.000081", 4))
+            .unwrap();
+        mirror.write(&raw_events[0]).unwrap();
+        mirror.sync().unwrap();
+        assert_eq!(
+            mirror.binlog_filenames(),
+            &["mysql-bin.000080", "mysql-bin.000081"]
+        );
+        let next_buf = std::fs::read(dir.path().join("mysql-bin.000081")).unwrap();
+        assert_eq!(next_buf.len(), 4 + raw_events[0].len());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("binlog.index")).unwrap(),
+            "./mysql-bin.000080\n./mysql-bin.000081\n"
+        );
+    }
+
+    #[test]
+    fn test_directory_reader() {
+        let dir = tempfile::tempdir().unwrap();
+        let events_80 = parse_events("/mysql-bin.000080");
+        let events_90 = parse_events("/mysql-bin.000090");
+
+        // 000001 rotates to 000003, 000002 is not read
+        let mut buf = std::fs::read(open_file_path("/mysql-bin.000080")).unwrap();
+        let rotate_header = EventHeader {
+            event_type: 4,
+            ..events_80[1].0.clone()
+        };
+        let rotate = EventData::Rotate(RotateEvent {
+            binlog_filename: "mysql-bin.000003".into(),
+            binlog_position: 4,
+        });
+        let mut encoder = BinlogEncoder::new();
+        encoder.checksum_type = ChecksumType::CRC32;
+        let rotate_buf = encoder
+            .encode_at(&rotate_header, &rotate, buf.len() as u64)
+            .unwrap();
+        buf.extend_from_slice(&rotate_buf);
+        std::fs::write(dir.path().join("mysql-bin.000001"), &buf).unwrap();
+        std::fs::copy(
+            open_file_path("/mysql-bin.000057"),
+            dir.path().join("mysql-bin.000002"),
+        )
+        .unwrap();
+        std::fs::copy(
+            open_file_path("/mysql-bin.000090"),
+            dir.path().join("mysql-bin.000003"),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("mysql-bin.index"),
+            "./mysql-bin.000001\n./mysql-bin.000002\n./mysql-bin.000003\n",
+        )
+        .unwrap();
+
+        let read_events = |reader: BinlogDirectoryReader| {
+            reader
+                .map(|res| res.unwrap())
+                .collect::<Vec<(EventHeader, EventData)>>()
+        };
+        let events = read_events(
+            BinlogDirectoryReader::from_index_file(dir.path().join("mysql-bin.index")).unwrap(),
+        );
+        assert_eq!(events.len(), events_80.len() + 1 + events_90.len());
+        assert!(matches!(events[events_80.len()].1, EventData::Rotate(_)));
+        assert_eq!(
+            events.last().unwrap().0.next_event_position,
+            events_90.last().unwrap().0.next_event_position
+        );
+        let events = read_events(BinlogDirectoryReader::from_dir(dir.path()).unwrap());
+        assert_eq!(events.len(), events_80.len() + 1 + events_90.len());
+
+        // start at a position, after the FormatDescriptionEvent
+        let reader = BinlogDirectoryReader::from_dir(dir.path())
+            .unwrap()
+            .with_start_position("mysql-bin.000001", 1007);
+        let events = read_events(reader);
+        assert!(matches!(events[0].1, EventData::FormatDescription(_)));
+        assert_eq!(events[1].0.next_event_position, 1076);
+        let skipped = events_80
+            .iter()
+            .filter(|(header, _)| header.next_event_position <= 1007)
+            .count();
+        assert_eq!(
+            events.len(),
+            1 + events_80.len() - skipped + 1 + events_90.len()
+        );
+
+        // start after an executed GTID set
+        let gtid_set = GtidSet::new("58cf6502-63db-11ed-8079-0242ac110002:1-55").unwrap();
+        let reader = BinlogDirectoryReader::from_dir(dir.path())
+            .unwrap()
+            .with_start_gtid_set(gtid_set);
+        let gtids: Vec<String> = read_events(reader)
+            .into_iter()
+            .filter_map(|(_, data)| match data {
+                EventData::Gtid(event) => Some(event.gtid),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(gtids[0], "58cf6502-63db-11ed-8079-0242ac110002:56");
+        assert_eq!(gtids.len(), 7);
+    }
+
+    #[test]
+    fn test_directory_reader_file_order() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "mysql-bin.1000000",
+            "mysql-bin.999999",
+            "mysql-bin.000010.gz",
+            "mysql-bin.index",
+            "relay-bin.000001",
+        ] {
+            std::fs::write(dir.path().join(name), b"").unwrap();
+        }
+
+        assert!(BinlogDirectoryReader::from_dir(dir.path()).is_err());
+        let reader =
+            BinlogDirectoryReader::from_dir_with_basename(dir.path(), "mysql-bin").unwrap();
+        let names: Vec<String> = reader
+            .binlog_paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "mysql-bin.000010.gz",
+                "mysql-bin.999999",
+                "mysql-bin.1000000"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bounded_reader_80_binlog() {
+        // transaction 58 starts before the stop position and is read to its end
+        let reader = BinlogBoundedReader::open(open_file_path("/mysql-bin.000080"))
+            .unwrap()
+            .with_start_position(942)
+            .with_stop_position(1421);
+        let events: Vec<(EventHeader, EventData)> = reader.map(|res| res.unwrap()).collect();
+        assert_eq!(events.len(), 10);
+        assert!(matches!(events[0].1, EventData::FormatDescription(_)));
+        assert_eq!(events[1].0.next_event_position, 1007);
+        assert_eq!(events.last().unwrap().0.next_event_position, 1525);
+        assert_eq!(gtid_numbers(events), vec![56, 57, 58]);
+
+        let reader = BinlogBoundedReader::open(open_file_path("/mysql-bin.000080"))
+            .unwrap()
+            .with_start_time(1669271856)
+            .with_stop_time(1669281294);
+        let events: Vec<(EventHeader, EventData)> = reader.map(|res| res.unwrap()).collect();
+        assert!(matches!(events[1].1, EventData::PreviousGtids(_)));
+        assert_eq!(gtid_numbers(events), vec![55, 56, 57, 58]);
+
+        let uuid = "58cf6502-63db-11ed-8079-0242ac110002";
+        let reader = BinlogBoundedReader::open(open_file_path("/mysql-bin.000080"))
+            .unwrap()
+            .with_include_gtids(GtidSet::new(&format!("{}:54-55:60", uuid)).unwrap())
+            .with_exclude_gtids(GtidSet::new(&format!("{}:55", uuid)).unwrap());
+        let events: Vec<(EventHeader, EventData)> = reader.map(|res| res.unwrap()).collect();
+        assert_eq!(events.len(), 2 + 5 + 2);
+        assert_eq!(gtid_numbers(events), vec![54, 60]);
+    }
+
+    fn gtid_numbers(events: Vec<(EventHeader, EventData)>) -> Vec<u64> {
+        events
+            .into_iter()
+            .filter_map(|(_, data)| match data {
+                EventData::Gtid(event) => event.gtid.split(':').nth(1)?.parse().ok(),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_async_reader_80_binlog() {
+        let expected_events = parse_events("/mysql-bin.000080");
+        let events = async_std::task::block_on(async {
+            let mut reader = BinlogAsyncReader::open(open_file_path("/mysql-bin.000080"))
+                .await
+                .unwrap();
+            let mut events = Vec::new();
+            while let Some(event) = reader.read().await.unwrap() {
+                events.push(event);
+            }
+            events
+        });
+        assert_eq!(events.len(), expected_events.len());
+        for ((header, _), (expected_header, _)) in events.iter().zip(expected_events.iter()) {
+            assert_eq!(
+                header.next_event_position,
+                expected_header.next_event_position
+            );
+        }
+    }
+
+    #[test]
+    fn test_async_reader_follow() {
+        let buf = std::fs::read(open_file_path("/mysql-bin.000080")).unwrap();
+        let expected_count = parse_events("/mysql-bin.000080").len();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mysql-bin.000001");
+        // the file ends within an event
+        std::fs::write(&path, &buf[..1000]).unwrap();
+
+        let events = async_std::task::block_on(async {
+            let mut reader = BinlogAsyncReader::open(&path)
+                .await
+                .unwrap()
+                .with_follow(Duration::from_millis(10));
+            let mut events = Vec::new();
+            while events.len() < 5 {
+                events.push(reader.read().await.unwrap().unwrap());
+            }
+
+            let append_path = path.clone();
+            let rest = buf[1000..].to_vec();
+            let writer = async_std::task::spawn(async move {
+                async_std::task::sleep(Duration::from_millis(50)).await;
+                let mut file = std::fs::OpenOptions::new()
+                    .append(true)
+                    .open(append_path)
+                    .unwrap();
+                std::io::Write::write_all(&mut file, &rest).unwrap();
+            });
+            while events.len() < expected_count {
+                events.push(reader.read().await.unwrap().unwrap());
+            }
+            writer.await;
+
+            // nothing more is appended
+            let res = async_std::future::timeout(Duration::from_millis(50), reader.read()).await;
+            assert!(res.is_err());
+            events
+        });
+        assert_eq!(
+            events.last().unwrap().0.next_event_position as usize,
+            buf.len()
+        );
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_mmap_parser_80_binlog() {
+        let expected_events = parse_events("/mysql-bin.000080");
+        // SAFETY: the fixture is not modified by the tests
+        let parser =
+            unsafe { BinlogMmapParser::open(open_file_path("/mysql-bin.000080")) }.unwrap();
+        assert_eq!(parser.checksum_length, 4);
+
+        let mut positions = Vec::new();
+        parser
+            .parse_region(parser.events_range(), |offset, header, _| {
+                assert_eq!(
+                    offset as u32 + header.event_length,
+                    header.next_event_position
+                );
+                positions.push(header.next_event_position);
+                Ok(())
+            })
+            .unwrap();
+        let expected_positions: Vec<u32> = expected_events
+            .iter()
+            .map(|(header, _)| header.next_event_position)
+            .collect();
+        assert_eq!(positions, expected_positions);
+
+        // regions start at GTID events
+        let regions = parser.split_regions(3);
+        assert_eq!(regions.len(), 3);
+        assert_eq!(regions[0].start, 4);
+        assert_eq!(regions[2].end, parser.as_slice().len());
+        for region in &regions[1..] {
+            assert_eq!(parser.as_slice()[region.start + 4], 33);
+        }
+
+        let event_count = AtomicUsize::new(0);
+        let row_count = AtomicUsize::new(0);
+        parser
+            .parse_parallel(&regions, |_, _, data| {
+                event_count.fetch_add(1, Ordering::Relaxed);
+                if let EventDataRef::WriteRows(event) | EventDataRef::DeleteRows(event) = data {
+                    row_count.fetch_add(event.rows.len(), Ordering::Relaxed);
+                }
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(event_count.into_inner(), expected_events.len());
+        let expected_row_count: usize = expected_events
+            .iter()
+            .map(|(_, data)| match data {
+                EventData::WriteRows(event) => event.rows.len(),
+                EventData::DeleteRows(event) => event.rows.len(),
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(row_count.into_inner(), expected_row_count);
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_mmap_parser_damaged_event_length() {
+        let mut buf = Vec::new();
+        open_file("/mysql-bin.000080")
+            .read_to_end(&mut buf)
+            .unwrap();
+        let events = split_events(&buf);
+        let offset_20: usize = 4 + events[..20].iter().map(|e| e.len()).sum::<usize>();
+        let offset_21 = offset_20 + events[20].len();
+        let dir = tempfile::tempdir().unwrap();
+
+        let parse_file = |buf: &[u8], name: &str, range: Option<Range<usize>>| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, buf).unwrap();
+            // SAFETY: the file is private to this test and not modified while mapped
+            let parser = unsafe { BinlogMmapParser::open(&path) }.unwrap();
+            let range = range.unwrap_or(parser.events_range());
+            let mut count = 0;
+            let res = parser.parse_region(range, |_, _, _| {
+                count += 1;
+                Ok(())
+            });
+            (res, count)
+        };
+
+        // a length too short in the middle of the file
+        let mut damaged_buf = buf.clone();
+        damaged_buf[offset_20 + 9..offset_20 + 13].copy_from_slice(&5u32.to_le_bytes());
+        let (res, count) = parse_file(&damaged_buf, "short", None);
+        assert!(
+            matches!(res, Err(BinlogError::UnexpectedData(e)) if e.contains(&format!("at offset {}", offset_20)))
+        );
+        assert_eq!(count, 20);
+
+        // a length running past the end of the region
+        let mut damaged_buf = buf.clone();
+        let length = events[20].len() as u32 + 100;
+        damaged_buf[offset_20 + 9..offset_20 + 13].copy_from_slice(&length.to_le_bytes());
+        let (res, count) = parse_file(&damaged_buf, "long", Some(4..offset_21));
+        assert!(matches!(res, Err(BinlogError::UnexpectedData(_))));
+        assert_eq!(count, 20);
+
+        // a partial event at the end of the file is not an error
+        let (res, count) = parse_file(&buf[..buf.len() - 10], "partial", None);
+        assert!(res.is_ok());
+        assert_eq!(count, events.len() - 1);
+    }
+
+    #[cfg(all(feature = "gzip", feature = "xz"))]
+    #[test]
+    fn test_compressed_binlogs() {
+        let dir = tempfile::tempdir().unwrap();
+        let compress = |file_name: &str, compression: Compression| {
+            let buf = std::fs::read(open_file_path(file_name)).unwrap();
+            match compression {
+                Compression::Gzip => {
+                    let mut encoder =
+                        flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                    std::io::Write::write_all(&mut encoder, &buf).unwrap();
+                    encoder.finish().unwrap()
+                }
+                Compression::Zstd => zstd::encode_all(&buf[..], 3).unwrap(),
+                Compression::Xz => {
+                    let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+                    std::io::Write::write_all(&mut encoder, &buf).unwrap();
+                    encoder.finish().unwrap()
+                }
+                Compression::None => buf,
+            }
+        };
+        std::fs::write(
+            dir.path().join("mysql-bin.000001.gz"),
+            compress("/mysql-bin.000080", Compression::Gzip),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("mysql-bin.000002.zst"),
+            compress("/mysql-bin.000057", Compression::Zstd),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("mysql-bin.000003.xz"),
+            compress("/mysql-bin.000090", Compression::Xz),
+        )
+        .unwrap();
+
+        // decompressed as the original files, seeking forward only
+        let expected_buf = std::fs::read(open_file_path("/mysql-bin.000080")).unwrap();
+        let mut file = BinlogFile::open(dir.path().join("mysql-bin.000001.gz")).unwrap();
+        assert_eq!(file.compression(), Compression::Gzip);
+        file.seek(SeekFrom::Start(100)).unwrap();
+        assert_eq!(file.stream_position().unwrap(), 100);
+        assert!(file.seek(SeekFrom::Start(50)).is_err());
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, expected_buf[100..]);
+        assert!(file.is_eof().unwrap());
+
+        let mut reader = BinlogDirectoryReader::from_dir(dir.path()).unwrap();
+        let mut count = 0;
+        let mut filenames = Vec::new();
+        while reader.read().unwrap().is_some() {
+            count += 1;
+            let filename = reader.binlog_filename().unwrap().to_string();
+            if !filenames.contains(&filename) {
+                filenames.push(filename);
+            }
+        }
+        let expected_count = parse_events("/mysql-bin.000080").len()
+            + parse_events("/mysql-bin.000057").len()
+            + parse_events("/mysql-bin.000090").len();
+        assert_eq!(count, expected_count);
+        assert_eq!(
+            filenames,
+            vec!["mysql-bin.000001", "mysql-bin.000002", "mysql-bin.000003"]
+        );
+
+        // the start position is in uncompressed coordinates
+        let reader = BinlogBoundedReader::open(dir.path().join("mysql-bin.000001.gz"))
+            .unwrap()
+            .with_start_position(942)
+            .with_stop_position(1421);
+        let events: Vec<(EventHeader, EventData)> = reader.map(|res| res.unwrap()).collect();
+        assert_eq!(events.len(), 10);
+        assert_eq!(gtid_numbers(events), vec![56, 57, 58]);
+    }
+
+    #[test]
+    fn test_encrypted_binlog_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mysql-bin.000001");
+        let mut buf = vec![0xfd, b'b', b'i', b'n', 1];
+        buf.resize(512, 0);
+        std::fs::write(&path, buf).unwrap();
+        assert!(matches!(
+            BinlogFile::open(&path),
+            Err(BinlogError::DecryptError(_))
+        ));
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn test_compressed_binlog_without_feature() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mysql-bin.000001.gz");
+        std::fs::write(&path, [0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00]).unwrap();
+        assert!(matches!(
+            BinlogFile::open(&path),
+            Err(BinlogError::UnexpectedData(_))
+        ));
+    }
+
+    // mysql-bin.000081 and its keyring are captured by capture_encrypted_binlog.sh
+    #[cfg(feature = "encryption")]
+    #[test]
+    #[ignore = "needs mysql-bin.000081(.keyring) captured by capture_encrypted_binlog.sh"]
+    fn test_captured_encrypted_binlog() {
+        let keyring = Keyring::from_file(open_file_path("/mysql-bin.000081.keyring")).unwrap();
+        let mut reader =
+            EncryptedBinlogReader::new(open_file("/mysql-bin.000081"), &keyring).unwrap();
+        assert!(reader.header.key_id.starts_with("MySQLReplicationKey_"));
+
+        let mut parser = BinlogParser {
+            checksum_length: 0,
+            table_map_event_by_table_id: HashMap::new(),
+        };
+        assert!(parser.check_magic(&mut reader).is_ok());
+        let mut ids = Vec::new();
+        while let Ok((_, data)) = parser.next(&mut reader) {
+            if let EventData::WriteRows(event) = data {
+                let table_map_event = &parser.table_map_event_by_table_id[&event.table_id];
+                assert_eq!(table_map_event.table_name, "encrypted_table");
+                for row in event.rows {
+                    ids.push(row.column_values[0].clone());
+                }
+            }
+        }
+        assert_eq!(
+            ids,
+            vec![
+                ColumnValue::Long(1),
+                ColumnValue::Long(2),
+                ColumnValue::Long(3)
+            ]
+        );
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypted_binlog() {
+        use aes::cipher::{BlockEncryptMut, KeyIvInit, StreamCipher};
+        use sha2::{Digest, Sha512};
+
+        let key_id = "MySQLReplicationKey_58cf6502-63db-11ed-8079-0242ac110002_1";
+        let master_key = [7u8; 32];
+        let password = [9u8; 32];
+        let iv = [3u8; 16];
+
+        // header: magic, version, key id, encrypted file password, iv, padded to 512 bytes
+        let mut encrypted_password = password;
+        cbc::Encryptor::<aes::Aes256>::new(&master_key.into(), &iv.into())
+            .encrypt_padded_mut::<cbc::cipher::block_padding::NoPadding>(
+                &mut encrypted_password,
+                32,
+            )
+            .unwrap();
+        let mut buf = vec![0xfd, b'b', b'i', b'n', 1];
+        buf.extend_from_slice(&[1, key_id.len() as u8]);
+        buf.extend_from_slice(key_id.as_bytes());
+        buf.push(2);
+        buf.extend_from_slice(&encrypted_password);
+        buf.push(3);
+        buf.extend_from_slice(&iv);
+        buf.resize(512, 0);
+
+        let plain_buf = std::fs::read(open_file_path("/mysql-bin.000080")).unwrap();
+        let mut encrypted_buf = plain_buf.clone();
+        let digest = Sha512::digest(password);
+        ctr::Ctr128BE::<aes::Aes256>::new_from_slices(&digest[..32], &digest[32..48])
+            .unwrap()
+            .apply_keystream(&mut encrypted_buf);
+        buf.extend_from_slice(&encrypted_buf);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mysql-bin.000001");
+        std::fs::write(&path, &buf).unwrap();
+        assert!(matches!(
+            BinlogFile::open(&path),
+            Err(BinlogError::DecryptError(_))
+        ));
+
+        // master key from a keyring file
+        let keyring_path = dir.path().join("component_keyring_file");
+        let hex: String = master_key.iter().map(|b| format!("{:02x}", b)).collect();
+        std::fs::write(
+            &keyring_path,
+            json!({
+                "version": "1.0",
+                "elements": [{"user": "", "data_id": key_id, "data_type": "AES", "data": hex, "extension": []}]
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let keyring = Keyring::from_file(&keyring_path).unwrap();
+        let mut reader = EncryptedBinlogReader::new(File::open(&path).unwrap(), &keyring).unwrap();
+        assert_eq!(reader.header.key_id, key_id);
+        let mut decrypted_buf = Vec::new();
+        reader.read_to_end(&mut decrypted_buf).unwrap();
+        assert_eq!(decrypted_buf, plain_buf);
+
+        // master key from a callback, seeking in plain coordinates
+        let reader = EncryptedBinlogReader::with_key_provider(File::open(&path).unwrap(), |id| {
+            (id == key_id).then(|| master_key.to_vec())
+        })
+        .unwrap();
+        let reader = BinlogBoundedReader::new(std::io::BufReader::new(reader))
+            .unwrap()
+            .with_start_position(942)
+            .with_stop_position(1421);
+        let events: Vec<(EventHeader, EventData)> = reader.map(|res| res.unwrap()).collect();
+        assert_eq!(gtid_numbers(events), vec![56, 57, 58]);
+
+        let res = EncryptedBinlogReader::new(File::open(&path).unwrap(), &Keyring::new());
+        assert!(matches!(res, Err(BinlogError::DecryptError(_))));
+    }
+
+    #[test]
+    fn test_offset_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mysql-bin.000080");
+        std::fs::copy(open_file_path("/mysql-bin.000080"), &path).unwrap();
+
+        let index = BinlogOffsetIndex::load_or_build(&path).unwrap();
+        let sidecar_path = BinlogOffsetIndex::sidecar_path(&path);
+        assert_eq!(sidecar_path, dir.path().join("mysql-bin.000080.idx"));
+        assert_eq!(BinlogOffsetIndex::load(&sidecar_path).unwrap(), index);
+        assert_eq!(index.file_size, std::fs::metadata(&path).unwrap().len());
+        assert_eq!(index.transactions.len(), 10);
+        assert_eq!(index.transactions[0].position, 194);
+        assert_eq!(index.transactions[0].table_ids, vec![109]);
+        assert!(index.transactions[4].table_ids.is_empty());
+
+        let uuid = "58cf6502-63db-11ed-8079-0242ac110002";
+        let transaction = index.find_gtid(&format!("{}:56", uuid)).unwrap();
+        assert_eq!(transaction.position, 942);
+        assert_eq!(index.find_position(1000), Some(transaction));
+        assert_eq!(index.find_position(942), Some(transaction));
+        assert_eq!(index.find_position(100), None);
+        assert_eq!(
+            index.find_time(1669281294).unwrap().gtid,
+            Some(format!("{}:59", uuid))
+        );
+        assert!(index.find_gtid(&format!("{}:100", uuid)).is_none());
+
+        // seeking before and after the FormatDescriptionEvent
+        let mut reader = BinlogBoundedReader::open(&path).unwrap();
+        assert!(reader
+            .seek_to_gtid(&index, &format!("{}:58", uuid))
+            .unwrap());
+        let events: Vec<(EventHeader, EventData)> = reader.map(|res| res.unwrap()).collect();
+        assert!(matches!(events[0].1, EventData::FormatDescription(_)));
+        assert_eq!(gtid_numbers(events)[0], 58);
+
+        let mut reader = BinlogBoundedReader::open(&path).unwrap();
+        reader.read().unwrap();
+        assert!(reader.seek_to_position(&index, 1000).unwrap());
+        let (_, data) = reader.read().unwrap().unwrap();
+        assert!(matches!(data, EventData::Gtid(event) if event.gtid.ends_with(":56")));
+        assert!(reader.seek_to_time(&index, 1669281294).unwrap());
+        let events: Vec<(EventHeader, EventData)> = reader.map(|res| res.unwrap()).collect();
+        assert_eq!(gtid_numbers(events)[0], 59);
+    }
+
+    #[test]
+    fn test_recovery_reader() {
+        let mut buf = Vec::new();
+        open_file("/mysql-bin.000080")
+            .read_to_end(&mut buf)
+            .unwrap();
+        let expected_events = parse_events("/mysql-bin.000080");
+        let events = split_events(&buf);
+        let offsets: Vec<usize> = events
+            .iter()
+            .scan(4, |offset, event| {
+                let start = *offset;
+                *offset += event.len();
+                Some(start)
+            })
+            .collect();
+
+        // a damaged QueryEvent, 7 garbage bytes before a GtidEvent and a truncated last event
+        let mut damaged_buf = buf[..offsets[17]].to_vec();
+        damaged_buf[offsets[13] + 30] ^= 0xff;
+        damaged_buf.extend_from_slice(b"garbage");
+        damaged_buf.extend_from_slice(&buf[offsets[17]..buf.len() - 10]);
+
+        let mut reader = BinlogRecoveryReader::new(std::io::Cursor::new(damaged_buf)).unwrap();
+        let mut recovered_events = Vec::new();
+        while let Some(event) = reader.read().unwrap() {
+            recovered_events.push(event);
+        }
+        assert_eq!(recovered_events.len(), expected_events.len() - 2);
+        assert_eq!(
+            gtid_numbers(recovered_events.clone()),
+            (53..=62).collect::<Vec<u64>>()
+        );
+        let expected_positions: Vec<u32> = expected_events
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != 13 && *i != events.len() - 1)
+            .map(|(_, (header, _))| header.next_event_position)
+            .collect();
+        let positions: Vec<u32> = recovered_events
+            .iter()
+            .map(|(header, _)| header.next_event_position)
+            .collect();
+        assert_eq!(positions, expected_positions);
+
+        let ranges: Vec<(u64, u64)> = reader
+            .skipped_ranges
+            .iter()
+            .map(|range| (range.start, range.end))
+            .collect();
+        let last_start = (offsets[events.len() - 1] + 7) as u64;
+        assert_eq!(
+            ranges,
+            vec![
+                (offsets[13] as u64, offsets[14] as u64),
+                (offsets[17] as u64, offsets[17] as u64 + 7),
+                (last_start, (buf.len() - 10 + 7) as u64),
+            ]
+        );
+        assert_eq!(reader.skipped_ranges[0].reason, "checksum mismatch");
+        assert_eq!(reader.skipped_ranges[2].reason, "truncated event");
+    }
+
+    #[test]
+    fn test_recovery_reader_damaged_format_description() {
+        let mut buf = Vec::new();
+        open_file("/mysql-bin.000080")
+            .read_to_end(&mut buf)
+            .unwrap();
+        let fde_length = u32::from_le_bytes(buf[13..17].try_into().unwrap());
+
+        // a flipped byte of the server version
+        let mut damaged_buf = buf.clone();
+        damaged_buf[28] ^= 0xff;
+        // an event length too short for the post-header lengths, chained to a next position
+        let mut short_buf = buf.clone();
+        short_buf[13..17].copy_from_slice(&79u32.to_le_bytes());
+        short_buf[17..21].copy_from_slice(&83u32.to_le_bytes());
+
+        // the damaged event is skipped without being parsed, or fails to parse
+        for (buf, end, reason) in [
+            (damaged_buf, 4 + fde_length as u64, "checksum mismatch"),
+            (
+                short_buf,
+                83,
+                "format description event without its post-header length",
+            ),
+        ] {
+            let mut reader = BinlogRecoveryReader::new(std::io::Cursor::new(buf)).unwrap();
+            let mut events = Vec::new();
+            while let Some(event) = reader.read().unwrap() {
+                events.push(event);
+            }
+            assert_eq!(gtid_numbers(events), (53..=62).collect::<Vec<u64>>());
+            let range = &reader.skipped_ranges[0];
+            assert_eq!((range.start, range.end), (4, end));
+            assert!(range.reason.contains(reason));
+        }
+    }
+
+    #[test]
+    fn test_recovery_reader_stray_event_copy() {
+        let mut buf = Vec::new();
+        open_file("/mysql-bin.000080")
+            .read_to_end(&mut buf)
+            .unwrap();
+        let events = split_events(&buf);
+        let offsets: Vec<usize> = events
+            .iter()
+            .scan(4, |offset, event| {
+                let start = *offset;
+                *offset += event.len();
+                Some(start)
+            })
+            .collect();
+        let expected_gtids = gtid_numbers(parse_events("/mysql-bin.000080"));
+
+        // the GtidEvent and QueryEvent at offsets 12..14 are overwritten by garbage holding an
+        // intact copy of the GtidEvent 22, which chains by its own header only
+        let mut damaged_buf = buf.clone();
+        let region = &mut damaged_buf[offsets[12]..offsets[14]];
+        region.fill(0);
+        region[3..3 + events[22].len()].copy_from_slice(&events[22]);
+
+        let mut reader = BinlogRecoveryReader::new(std::io::Cursor::new(damaged_buf)).unwrap();
+        let mut recovered_events = Vec::new();
+        while let Some(event) = reader.read().unwrap() {
+            recovered_events.push(event);
+        }
+        let lost_gtid = expected_gtids[2];
+        let expected_gtids: Vec<u64> = expected_gtids
+            .into_iter()
+            .filter(|gtid| *gtid != lost_gtid)
+            .collect();
+        assert_eq!(gtid_numbers(recovered_events), expected_gtids);
+        let ranges: Vec<(u64, u64)> = reader
+            .skipped_ranges
+            .iter()
+            .map(|range| (range.start, range.end))
+            .collect();
+        assert_eq!(ranges, vec![(offsets[12] as u64, offsets[14] as u64)]);
+    }
+
     fn open_file(file_name: &str) -> File {
+        File::open(open_file_path(file_name)).unwrap()
+    }
+
+    fn open_file_path(file_name: &str) -> String {
         let current_dir = env::current_dir();
-        let file_path = format!(
+        format!(
             "{}/{}/{}",
             current_dir.unwrap().display(),
             "tests/parse_file_tests",
             file_name
-        );
-        File::open(file_path).unwrap()
+        )
     }
 
     fn test_parse_binlog(file_name: &str, expect_event_count: i32) {
@@ -56,9 +1234,153 @@ mod test {
         assert!(parser.check_magic(&mut file).is_ok());
 
         let mut count = 0;
-        while let Ok(_) = parser.next(&mut file) {
+        while parser.next(&mut file).is_ok() {
             count += 1;
         }
         assert_eq!(count, expect_event_count);
     }
+
+    #[test]
+    fn test_parse_ref_short_rows_event() {
+        let mut parser = BinlogParser {
+            checksum_length: 4,
+            table_map_event_by_table_id: HashMap::new(),
+        };
+        // a WriteRowsEvent whose length does not cover its header and checksum
+        for event_length in 19u32..23 {
+            let mut buf = vec![0u8; 23];
+            buf[4] = 30;
+            buf[9..13].copy_from_slice(&event_length.to_le_bytes());
+            assert!(matches!(
+                parser.next_ref(&buf),
+                Err(BinlogError::UnexpectedData(_))
+            ));
+        }
+    }
+
+    fn test_parse_binlog_ref(file_name: &str) {
+        // parse all events with the owned path
+        let mut file = open_file(file_name);
+        let mut parser = BinlogParser {
+            checksum_length: 4,
+            table_map_event_by_table_id: HashMap::new(),
+        };
+        assert!(parser.check_magic(&mut file).is_ok());
+        let mut expected_rows = Vec::new();
+        while let Ok((_, data)) = parser.next(&mut file) {
+            match data {
+                EventData::WriteRows(event) => expected_rows.extend(event.rows),
+                EventData::DeleteRows(event) => expected_rows.extend(event.rows),
+                EventData::UpdateRows(event) => {
+                    for (before, after) in event.rows {
+                        expected_rows.push(before);
+                        expected_rows.push(after);
+                    }
+                }
+                _ => {}
+            }
+        }
+        assert!(!expected_rows.is_empty());
+
+        // parse all events with the borrowed path
+        let buf = std::fs::read(open_file_path(file_name)).unwrap();
+        let mut parser = BinlogParser {
+            checksum_length: 4,
+            table_map_event_by_table_id: HashMap::new(),
+        };
+        let mut rows: Vec<RowEvent> = Vec::new();
+        let mut position = 4;
+        while position < buf.len() {
+            let (header, data) = parser.next_ref(&buf[position..]).unwrap();
+            match data {
+                EventDataRef::WriteRows(event) | EventDataRef::DeleteRows(event) => {
+                    for row in event.rows {
+                        rows.push(row.to_row_event().unwrap());
+                    }
+                }
+                EventDataRef::UpdateRows(event) => {
+                    for (before, after) in event.rows {
+                        rows.push(before.to_row_event().unwrap());
+                        rows.push(after.to_row_event().unwrap());
+                    }
+                }
+                EventDataRef::Owned(_) => {}
+            }
+            position += header.event_length as usize;
+        }
+
+        assert_eq!(rows.len(), expected_rows.len());
+        for (row, expected_row) in rows.iter().zip(expected_rows.iter()) {
+            assert_eq!(row.column_values, expected_row.column_values);
+        }
+    }
+
+    fn parse_events(file_name: &str) -> Vec<(EventHeader, EventData)> {
+        let mut file = open_file(file_name);
+        let mut parser = BinlogParser {
+            checksum_length: 0,
+            table_map_event_by_table_id: HashMap::new(),
+        };
+        assert!(parser.check_magic(&mut file).is_ok());
+        let mut events = Vec::new();
+        while let Ok(event) = parser.next(&mut file) {
+            events.push(event);
+        }
+        events
+    }
+
+    fn write_events(events: &[(EventHeader, EventData)]) -> Vec<u8> {
+        let (header, EventData::FormatDescription(format_description_event)) = &events[0] else {
+            panic!("the first event is not a FormatDescriptionEvent");
+        };
+        let mut writer = BinlogWriter::new(Vec::new(), header, format_description_event).unwrap();
+        for (header, data) in &events[1..] {
+            writer.write(header, data).unwrap();
+        }
+        writer.into_inner()
+    }
+
+    fn test_encode_binlog(file_name: &str) {
+        let events = parse_events(file_name);
+        let buf = write_events(&events);
+        let expected_buf = std::fs::read(open_file_path(file_name)).unwrap();
+        let mismatch = buf
+            .iter()
+            .zip(expected_buf.iter())
+            .position(|(a, b)| a != b);
+        assert_eq!(mismatch, None, "{} differs at {:?}", file_name, mismatch);
+        assert_eq!(buf.len(), expected_buf.len());
+    }
+
+    /// Splits the raw events of a binlog file, without the magic.
+    fn split_events(buf: &[u8]) -> Vec<Vec<u8>> {
+        let mut events = Vec::new();
+        let mut offset = 4;
+        while offset < buf.len() {
+            let event_length =
+                u32::from_le_bytes(buf[offset + 9..offset + 13].try_into().unwrap()) as usize;
+            events.push(buf[offset..offset + event_length].to_vec());
+            offset += event_length;
+        }
+        events
+    }
+
+    /// The artificial RotateEvent sent by the server at the start of a dump.
+    fn fake_rotate_event(binlog_filename: &str, binlog_position: u64) -> Vec<u8> {
+        let header = EventHeader {
+            timestamp: 0,
+            event_type: 4,
+            server_id: 1,
+            event_length: 0,
+            next_event_position: 0,
+            event_flags: 0x20,
+        };
+        let data = EventData::Rotate(RotateEvent {
+            binlog_filename: binlog_filename.to_string(),
+            binlog_position,
+        });
+        let mut encoder = BinlogEncoder::new();
+        encoder.checksum_type = ChecksumType::CRC32;
+        encoder.encode(&header, &data).unwrap()
+    }
 }
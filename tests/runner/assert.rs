@@ -32,7 +32,7 @@ pub(crate) mod test {
                     assert_eq!(*v, value as u64);
                 }
                 _ => {
-                    assert!(false)
+                    panic!("unexpected column value: {:?}", column_value)
                 }
             }
         }
@@ -43,10 +43,10 @@ pub(crate) mod test {
                     assert_eq!(*v, value as u32);
                 }
                 ColumnValue::Set(v) => {
-                    assert_eq!(*v, value as u64);
+                    assert_eq!(*v, value);
                 }
                 _ => {
-                    assert!(false)
+                    panic!("unexpected column value: {:?}", column_value)
                 }
             }
         }
@@ -57,7 +57,7 @@ pub(crate) mod test {
                     assert_eq!(*v, value);
                 }
                 _ => {
-                    assert!(false)
+                    panic!("unexpected column value: {:?}", column_value)
                 }
             }
         }
@@ -68,7 +68,7 @@ pub(crate) mod test {
                     assert_eq!(*v, value);
                 }
                 _ => {
-                    assert!(false)
+                    panic!("unexpected column value: {:?}", column_value)
                 }
             }
         }
@@ -79,7 +79,7 @@ pub(crate) mod test {
                     assert_eq!(*v, value);
                 }
                 _ => {
-                    assert!(false)
+                    panic!("unexpected column value: {:?}", column_value)
                 }
             }
         }
@@ -93,7 +93,7 @@ pub(crate) mod test {
                     assert_eq!(*v, value);
                 }
                 _ => {
-                    assert!(false)
+                    panic!("unexpected column value: {:?}", column_value)
                 }
             }
         }
@@ -104,7 +104,7 @@ pub(crate) mod test {
                     assert_eq!(*v, value);
                 }
                 _ => {
-                    assert!(false)
+                    panic!("unexpected column value: {:?}", column_value)
                 }
             }
         }
@@ -176,6 +176,39 @@ pub(crate) mod test {
         "NULL", "NULL", "NULL", "NULL", "NULL", "NULL", "NULL",
     ];
 
+    // expected values of the columns created by default_create_sql
+    pub struct DefaultValues {
+        pub pk: i32,
+        pub f_1: i8,
+        pub f_2: i16,
+        pub f_3: i32,
+        pub f_4: i32,
+        pub f_5: i64,
+        pub f_6: String,
+        pub f_7: f32,
+        pub f_8: f64,
+        pub f_9: i64,
+        pub f_10: String,
+        pub f_11: String,
+        pub f_12: String,
+        pub f_13: u16,
+        pub f_14: i64,
+        pub f_15: Vec<u8>,
+        pub f_16: Vec<u8>,
+        pub f_17: Vec<u8>,
+        pub f_18: Vec<u8>,
+        pub f_19: Vec<u8>,
+        pub f_20: Vec<u8>,
+        pub f_21: Vec<u8>,
+        pub f_22: Vec<u8>,
+        pub f_23: Vec<u8>,
+        pub f_24: Vec<u8>,
+        pub f_25: Vec<u8>,
+        pub f_26: Vec<u8>,
+        pub f_27: u32,
+        pub f_28: u64,
+    }
+
     pub struct Mock {}
 
     impl Mock {
@@ -208,96 +241,65 @@ pub(crate) mod test {
             )
         }
 
-        pub fn default_check_values(
-            event: &RowEvent,
-            pk: i32,
-            f_1_v: i8,
-            f_2_v: i16,
-            f_3_v: i32,
-            f_4_v: i32,
-            f_5_v: i64,
-            f_6_v: String,
-            f_7_v: f32,
-            f_8_v: f64,
-            f_9_v: i64,
-            f_10_v: String,
-            f_11_v: String,
-            f_12_v: String,
-            f_13_v: u16,
-            f_14_v: i64,
-            f_15_v: Vec<u8>,
-            f_16_v: Vec<u8>,
-            f_17_v: Vec<u8>,
-            f_18_v: Vec<u8>,
-            f_19_v: Vec<u8>,
-            f_20_v: Vec<u8>,
-            f_21_v: Vec<u8>,
-            f_22_v: Vec<u8>,
-            f_23_v: Vec<u8>,
-            f_24_v: Vec<u8>,
-            f_25_v: Vec<u8>,
-            f_26_v: Vec<u8>,
-            f_27_v: u32,
-            f_28_v: u64,
-        ) {
+        pub fn default_check_values(event: &RowEvent, values: DefaultValues) {
             // INT
-            Assert::assert_numeric_eq(&event.column_values[0], pk as i128);
+            Assert::assert_numeric_eq(&event.column_values[0], values.pk as i128);
             // TINYINT
-            Assert::assert_numeric_eq(&event.column_values[1], f_1_v as i128);
+            Assert::assert_numeric_eq(&event.column_values[1], values.f_1 as i128);
             // SMALLINT
-            Assert::assert_numeric_eq(&event.column_values[2], f_2_v as i128);
+            Assert::assert_numeric_eq(&event.column_values[2], values.f_2 as i128);
             // MEDIUMINT
-            Assert::assert_numeric_eq(&event.column_values[3], f_3_v as i128);
+            Assert::assert_numeric_eq(&event.column_values[3], values.f_3 as i128);
             // INT
-            Assert::assert_numeric_eq(&event.column_values[4], f_4_v as i128);
+            Assert::assert_numeric_eq(&event.column_values[4], values.f_4 as i128);
             // BIGINT
-            Assert::assert_numeric_eq(&event.column_values[5], f_5_v as i128);
+            Assert::assert_numeric_eq(&event.column_values[5], values.f_5 as i128);
             // DECIMAL(10,4)
-            Assert::assert_string_eq(&event.column_values[6], f_6_v);
+            Assert::assert_string_eq(&event.column_values[6], values.f_6);
             // FLOAT(6,2)
-            Assert::assert_float_eq(&event.column_values[7], f_7_v);
+            Assert::assert_float_eq(&event.column_values[7], values.f_7);
             // DOUBLE(8,3)
-            Assert::assert_double_eq(&event.column_values[8], f_8_v);
+            Assert::assert_double_eq(&event.column_values[8], values.f_8);
             // BIT(3)
-            Assert::assert_numeric_eq(&event.column_values[9], f_9_v as i128);
+            Assert::assert_numeric_eq(&event.column_values[9], values.f_9 as i128);
             // DATETIME(6)
-            Assert::assert_string_eq(&event.column_values[10], f_10_v);
+            Assert::assert_string_eq(&event.column_values[10], values.f_10);
             // TIME(6)
-            Assert::assert_string_eq(&event.column_values[11], f_11_v);
+            Assert::assert_string_eq(&event.column_values[11], values.f_11);
             // DATE
-            Assert::assert_string_eq(&event.column_values[12], f_12_v);
+            Assert::assert_string_eq(&event.column_values[12], values.f_12);
             // YEAR
-            Assert::assert_numeric_eq(&event.column_values[13], f_13_v as i128);
+            Assert::assert_numeric_eq(&event.column_values[13], values.f_13 as i128);
             // TIMESTAMP(6)
-            Assert::assert_timestamp_eq(&event.column_values[14], f_14_v);
+            Assert::assert_timestamp_eq(&event.column_values[14], values.f_14);
             // CHAR(255)
-            Assert::assert_bytes_eq(&event.column_values[15], f_15_v);
+            Assert::assert_bytes_eq(&event.column_values[15], values.f_15);
             // VARCHAR(255)
-            Assert::assert_bytes_eq(&event.column_values[16], f_16_v);
+            Assert::assert_bytes_eq(&event.column_values[16], values.f_16);
             // BINARY(255)
-            Assert::assert_bytes_eq(&event.column_values[17], f_17_v);
+            Assert::assert_bytes_eq(&event.column_values[17], values.f_17);
             // VARBINARY(255)
-            Assert::assert_bytes_eq(&event.column_values[18], f_18_v);
+            Assert::assert_bytes_eq(&event.column_values[18], values.f_18);
             // TINYTEXT
-            Assert::assert_bytes_eq(&event.column_values[19], f_19_v);
+            Assert::assert_bytes_eq(&event.column_values[19], values.f_19);
             // TEXT
-            Assert::assert_bytes_eq(&event.column_values[20], f_20_v);
+            Assert::assert_bytes_eq(&event.column_values[20], values.f_20);
             // MEDIUMTEXT
-            Assert::assert_bytes_eq(&event.column_values[21], f_21_v);
+            Assert::assert_bytes_eq(&event.column_values[21], values.f_21);
             // LONGTEXT
-            Assert::assert_bytes_eq(&event.column_values[22], f_22_v);
+            Assert::assert_bytes_eq(&event.column_values[22], values.f_22);
             // TINYBLOB
-            Assert::assert_bytes_eq(&event.column_values[23], f_23_v);
+            Assert::assert_bytes_eq(&event.column_values[23], values.f_23);
             // BLOB
-            Assert::assert_bytes_eq(&event.column_values[24], f_24_v);
+            Assert::assert_bytes_eq(&event.column_values[24], values.f_24);
             // MEDIUMBLOB
-            Assert::assert_bytes_eq(&event.column_values[25], f_25_v);
+            Assert::assert_bytes_eq(&event.column_values[25], values.f_25);
             // LONGBLOB
-            Assert::assert_bytes_eq(&event.column_values[26], f_26_v);
+            Assert::assert_bytes_eq(&event.column_values[26], values.f_26);
             // ENUM
-            Assert::assert_unsigned_numeric_eq(&event.column_values[27], f_27_v as u64);
+            Assert::assert_unsigned_numeric_eq(&event.column_values[27], values.f_27 as u64);
             // SET
-            Assert::assert_unsigned_numeric_eq(&event.column_values[28], f_28_v);
+            Assert::assert_unsigned_numeric_eq(&event.column_values[28], values.f_28);
         }
 
         pub fn insert_sql(values_list: &[Vec<&str>]) -> String {
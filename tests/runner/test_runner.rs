@@ -81,7 +81,7 @@ pub(crate) mod test {
             let insert_sql = Mock::one_col_insert_sql(values);
 
             let mut prepare_sqls: Vec<String> =
-                prepare_sqls.into_iter().map(|i| i.to_string()).collect();
+                prepare_sqls.iter().map(|i| i.to_string()).collect();
             prepare_sqls.push(create_sql);
 
             runner.execute_sqls_and_get_binlogs(&prepare_sqls, &vec![insert_sql]);
@@ -93,7 +93,7 @@ pub(crate) mod test {
             prepare_sqls: &Vec<String>,
             test_sqls: &Vec<String>,
         ) {
-            block_on(self.execute_sqls_and_get_binlogs_internal(&prepare_sqls, &test_sqls));
+            block_on(self.execute_sqls_and_get_binlogs_internal(prepare_sqls, test_sqls));
         }
 
         async fn execute_sqls_and_get_binlogs_internal(
@@ -188,7 +188,7 @@ pub(crate) mod test {
 
             for sql in prepare_sqls {
                 println!("execute prepare sql: {}", sql);
-                CommandUtil::execute_sql(&mut channel, &sql).await?;
+                CommandUtil::execute_sql(&mut channel, sql).await?;
             }
 
             // get current binlog info
@@ -197,7 +197,7 @@ pub(crate) mod test {
 
             for sql in test_sqls {
                 println!("execute test sql: {}", sql);
-                CommandUtil::execute_sql(&mut channel, &sql).await?;
+                CommandUtil::execute_sql(&mut channel, sql).await?;
             }
 
             Ok((binlog_filename, binlog_position))